@@ -0,0 +1,339 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use serde_json::json;
+use crate::js::{from_js, from_js_optional, to_js};
+use crate::license::{License, LicenseExpression};
+use crate::validation::{ReleaseType, Song};
+
+/// Who to credit, shared by every song on the site
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AttributionConfig {
+    pub artist: String,
+    /// Artist homepage, linked from the artist name when present
+    /// Defaults to the site like `artist`, so set it (or null) for another artist
+    pub site_url: Option<String>,
+}
+
+impl Default for AttributionConfig {
+    fn default() -> Self {
+        AttributionConfig {
+            artist: "babafun".to_string(),
+            site_url: Some("https://babafun.github.io".to_string()),
+        }
+    }
+}
+
+/// Ready-to-paste credit lines in every supported format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct Attribution {
+    pub text: String,
+    pub html: String,
+    pub markdown: String,
+}
+
+/// A piece of attribution text, optionally linked
+struct Part {
+    text: String,
+    url: Option<String>,
+}
+
+impl Part {
+    fn plain(text: impl Into<String>) -> Self {
+        Part { text: text.into(), url: None }
+    }
+
+    /// Links `text` to `url` if it is an http(s) URL; anything else (such as
+    /// `javascript:` links from song data) leaves the text unlinked
+    fn linked(text: impl Into<String>, url: Option<String>) -> Self {
+        Part { text: text.into(), url: url.filter(|url| is_web_url(url)) }
+    }
+}
+
+fn is_web_url(url: &str) -> bool {
+    let scheme = url.split_once("://").map_or("", |(scheme, _)| scheme);
+    scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+}
+
+/// Renders lines of parts as plain text, HTML and Markdown
+fn render(lines: &[Vec<Part>]) -> Attribution {
+    let join = |render_part: &dyn Fn(&Part) -> String, separator: &str| {
+        lines
+            .iter()
+            .map(|line| line.iter().map(render_part).collect::<String>())
+            .collect::<Vec<_>>()
+            .join(separator)
+    };
+
+    Attribution {
+        text: join(
+            &|part| match &part.url {
+                // A bare link is written once, not as "url (url)"
+                Some(url) if *url == part.text => url.clone(),
+                Some(url) => format!("{} ({})", part.text, url),
+                None => part.text.clone(),
+            },
+            "\n",
+        ),
+        html: join(
+            &|part| match &part.url {
+                Some(url) => format!("<a href=\"{}\">{}</a>", escape_html(url), escape_html(&part.text)),
+                None => escape_html(&part.text),
+            },
+            "<br>\n",
+        ),
+        markdown: join(
+            &|part| match &part.url {
+                Some(url) => format!("[{}]({})", escape_markdown(&part.text), escape_markdown_url(url)),
+                None => escape_markdown(&part.text),
+            },
+            "  \n",
+        ),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Percent-encodes the characters that would end or break a Markdown link target
+fn escape_markdown_url(url: &str) -> String {
+    let mut escaped = String::with_capacity(url.len());
+    for c in url.chars() {
+        if c.is_whitespace() || c.is_control() || "()<>".contains(c) {
+            let mut buffer = [0; 4];
+            for byte in c.encode_utf8(&mut buffer).bytes() {
+                escaped.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\`*_[]<>#|".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// License name, linked to its deed when it is a single license with one
+fn license_part(expression: &LicenseExpression) -> Part {
+    let url = match expression {
+        LicenseExpression::License(license) => license.url(),
+        _ => None,
+    };
+    Part::linked(expression.to_string(), url)
+}
+
+/// TASL (Title, Author, Source, License) credit for independent releases
+fn tasl_lines(song: &Song, config: &AttributionConfig) -> Vec<Vec<Part>> {
+    let expression = song.license_expression();
+    let mut line = vec![
+        Part::linked(format!("\"{}\"", song.title), Some(song.streaming_link.clone())),
+        Part::plain(" by "),
+        Part::linked(config.artist.clone(), config.site_url.clone()),
+    ];
+
+    match &expression {
+        LicenseExpression::License(License::Unspecified | License::AllRightsReserved) => {
+            line.push(Part::plain(". All rights reserved."));
+        }
+        LicenseExpression::License(License::Cc0 { .. } | License::PublicDomainMark { .. }) => {
+            line.push(Part::plain(" is marked with "));
+            line.push(license_part(&expression));
+            line.push(Part::plain("."));
+        }
+        _ => {
+            line.push(Part::plain(" is licensed under "));
+            line.push(license_part(&expression));
+            line.push(Part::plain("."));
+        }
+    }
+
+    vec![line]
+}
+
+/// Credit block in a label's house style, plus the license when one is set
+fn label_lines(song: &Song, config: &AttributionConfig, label: &str, title_prefix: &str, title_suffix: &str, link_prefix: &str) -> Vec<Vec<Part>> {
+    let mut lines = vec![
+        vec![Part::plain(format!("{}{} - {}{}", title_prefix, config.artist, song.title, title_suffix))],
+        vec![Part::plain(format!("Music provided by {}", label))],
+        vec![Part::plain(link_prefix), Part::linked(song.streaming_link.clone(), Some(song.streaming_link.clone()))],
+    ];
+
+    let expression = song.license_expression();
+    if expression != LicenseExpression::License(License::Unspecified) {
+        lines.push(vec![Part::plain("License: "), license_part(&expression)]);
+    }
+    lines
+}
+
+/// Builds the credit a creator should paste when using `song`
+/// NCS and Monstercat releases use the labels' own credit formats,
+/// everything else uses TASL-style attribution
+pub fn build_attribution(song: &Song, config: &AttributionConfig) -> Attribution {
+    let lines = match song.release_type {
+        ReleaseType::NCS => label_lines(song, config, "NoCopyrightSounds", "Song: ", " [NCS Release]", "Free Download/Stream: "),
+        ReleaseType::Monstercat => label_lines(song, config, "Monstercat", "Track: ", "", "Link: "),
+        ReleaseType::Independent => tasl_lines(song, config),
+    };
+    render(&lines)
+}
+
+/// Generates attribution for a song in plain text, HTML and Markdown
+/// `config_json` is `{ artist, siteUrl }`; pass an empty string for the site defaults
+/// Returns JSON: `{ text, html, markdown }`
+#[wasm_bindgen]
+pub fn generate_attribution(song_json: &str, config_json: &str) -> String {
+    let song: Song = match serde_json::from_str(song_json) {
+        Ok(s) => s,
+        Err(e) => return json!({ "error": format!("Invalid JSON: {}", e) }).to_string(),
+    };
+
+    let config = if config_json.trim().is_empty() {
+        AttributionConfig::default()
+    } else {
+        match serde_json::from_str(config_json) {
+            Ok(c) => c,
+            Err(e) => return json!({ "error": format!("Invalid attribution config: {}", e) }).to_string(),
+        }
+    };
+
+    json!(build_attribution(&song, &config)).to_string()
+}
+
+/// Generates attribution for a song object
+/// `config` is `{ artist, siteUrl }`; leave it out for the site defaults
+/// Returns `{ text, html, markdown }`
+#[wasm_bindgen(js_name = generateAttribution, unchecked_return_type = "Attribution")]
+pub fn generate_attribution_js(
+    #[wasm_bindgen(unchecked_param_type = "Song")] song: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "AttributionConfig")] config: Option<JsValue>,
+) -> Result<JsValue, JsError> {
+    let song: Song = from_js(song, "song")?;
+    let config: Option<AttributionConfig> = from_js_optional(config, "attribution config")?;
+    to_js(&build_attribution(&song, &config.unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn song(release_type: ReleaseType, title: &str, license: &str) -> Song {
+        testing::song("song-001")
+            .title(title)
+            .release_type(release_type)
+            .link("https://push.fm/track")
+            .license(license)
+            .build()
+    }
+
+    #[test]
+    fn test_tasl_attribution() {
+        let result = build_attribution(&song(ReleaseType::Independent, "Digital Dreams", "cc by 4.0"), &AttributionConfig::default());
+        assert_eq!(
+            result.text,
+            "\"Digital Dreams\" (https://push.fm/track) by babafun (https://babafun.github.io) is licensed under CC BY 4.0 (https://creativecommons.org/licenses/by/4.0/)."
+        );
+        assert_eq!(
+            result.markdown,
+            "[\"Digital Dreams\"](https://push.fm/track) by [babafun](https://babafun.github.io) is licensed under [CC BY 4.0](https://creativecommons.org/licenses/by/4.0/)."
+        );
+        assert_eq!(
+            result.html,
+            "<a href=\"https://push.fm/track\">&quot;Digital Dreams&quot;</a> by <a href=\"https://babafun.github.io\">babafun</a> is licensed under <a href=\"https://creativecommons.org/licenses/by/4.0/\">CC BY 4.0</a>."
+        );
+    }
+
+    #[test]
+    fn test_public_domain_and_compound_wording() {
+        let config = AttributionConfig { artist: "babafun".to_string(), site_url: None };
+
+        let result = build_attribution(&song(ReleaseType::Independent, "Calm", "CC0 1.0"), &config);
+        assert!(result.text.ends_with("by babafun is marked with CC0 1.0 (https://creativecommons.org/publicdomain/zero/1.0/)."));
+
+        let result = build_attribution(&song(ReleaseType::Independent, "Dual", "CC-BY-4.0 OR BGML-P"), &config);
+        assert!(result.text.ends_with("is licensed under CC BY 4.0 OR BGML-P."));
+    }
+
+    #[test]
+    fn test_label_formats() {
+        let config = AttributionConfig::default();
+
+        let result = build_attribution(&song(ReleaseType::NCS, "Electric Pulse", ""), &config);
+        assert_eq!(
+            result.text,
+            "Song: babafun - Electric Pulse [NCS Release]\nMusic provided by NoCopyrightSounds\nFree Download/Stream: https://push.fm/track"
+        );
+        assert_eq!(result.markdown.lines().count(), 3);
+        assert!(result.markdown.ends_with("Free Download/Stream: [https://push.fm/track](https://push.fm/track)"));
+        assert!(result.html.contains("Music provided by NoCopyrightSounds<br>\n"));
+        assert!(result.html.ends_with("Free Download/Stream: <a href=\"https://push.fm/track\">https://push.fm/track</a>"));
+
+        let result = build_attribution(&song(ReleaseType::Monstercat, "Drop", "All Rights Reserved"), &config);
+        assert_eq!(
+            result.text,
+            "Track: babafun - Drop\nMusic provided by Monstercat\nLink: https://push.fm/track\nLicense: All Rights Reserved"
+        );
+    }
+
+    #[test]
+    fn test_attribution_escaping() {
+        let result = build_attribution(&song(ReleaseType::Independent, "<Loud> & [Proud]", "CC BY 4.0"), &AttributionConfig::default());
+        assert!(result.html.contains(">&quot;&lt;Loud&gt; &amp; [Proud]&quot;</a>"));
+        assert!(result.markdown.contains("[\"\\<Loud\\> & \\[Proud\\]\"](https://push.fm/track)"));
+
+        let mut spaced = song(ReleaseType::Independent, "T", "CC BY 4.0");
+        spaced.streaming_link = "https://x.com/a b)c<d>(e\nf".to_string();
+        let result = build_attribution(&spaced, &AttributionConfig::default());
+        assert!(result.markdown.starts_with("[\"T\"](https://x.com/a%20b%29c%3Cd%3E%28e%0Af) by"));
+    }
+
+    #[test]
+    fn test_only_web_urls_are_linked() {
+        let mut hostile = song(ReleaseType::Independent, "Click", "CC BY 4.0");
+        hostile.streaming_link = "javascript:alert(1)".to_string();
+        let config = AttributionConfig { artist: "babafun".to_string(), site_url: Some("data:text/html,hi".to_string()) };
+        let result = build_attribution(&hostile, &config);
+        assert!(result.text.starts_with("\"Click\" by babafun is licensed"));
+        assert!(result.html.starts_with("&quot;Click&quot; by babafun is licensed"));
+        assert!(result.markdown.starts_with("\"Click\" by babafun is licensed"));
+        assert!(!result.html.contains("javascript:") && !result.markdown.contains("data:"));
+
+        hostile.streaming_link = "HTTPS://push.fm/track".to_string();
+        assert!(build_attribution(&hostile, &config).html.starts_with("<a href=\"HTTPS://push.fm/track\">"));
+    }
+
+    #[test]
+    fn test_generate_attribution_json() {
+        let song = testing::song("1").release_type(ReleaseType::Independent).license("CC BY 4.0").json().to_string();
+
+        let parsed: serde_json::Value = serde_json::from_str(&generate_attribution(&song, r#"{"artist":"Someone","siteUrl":null}"#)).unwrap();
+        assert_eq!(
+            parsed["text"],
+            "\"T\" (https://example.com) by Someone is licensed under CC BY 4.0 (https://creativecommons.org/licenses/by/4.0/)."
+        );
+
+        let parsed: serde_json::Value = serde_json::from_str(&generate_attribution(&song, "")).unwrap();
+        assert!(parsed["text"].as_str().unwrap().contains("by babafun (https://babafun.github.io)"));
+
+        // Keys left out of a config object keep the site defaults
+        assert_eq!(generate_attribution(&song, "{}"), generate_attribution(&song, ""));
+
+        let parsed: serde_json::Value = serde_json::from_str(&generate_attribution("{", "")).unwrap();
+        assert!(parsed["error"].as_str().unwrap().contains("Invalid JSON"));
+    }
+}
@@ -0,0 +1,428 @@
+//! `musicctl`: validate, lint, format, summarize and index `music.json` from the command line
+//!
+//! Exit codes: 0 on success, 1 when the file has errors (or warnings with
+//! `--deny-warnings`), 2 for usage and I/O problems
+
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+use rust::{check_music_data, check_songs, format_music_json, Catalog, SearchIndex, Severity, ValidationReport};
+use serde_json::json;
+
+const USAGE: &str = "\
+Usage: musicctl <command> [options] <file>
+
+Commands:
+  validate   Report errors in a music.json file (or a bare array of songs)
+  lint       Report errors and warnings
+  fmt        Print the file in canonical form: trimmed strings, canonical
+             license spellings, songs ordered by ID, stable key order
+  stats      Summarize the catalog
+  index      Build the search index artifact the site loads instead of
+             indexing in the browser
+
+Options:
+  --format <human|json>  Output format for validate, lint and stats (default: human)
+  --deny-warnings        lint: exit non-zero on warnings too
+  --write                fmt: rewrite the file in place instead of printing it
+  --check                fmt: exit non-zero if the file isn't canonical, without printing it
+                         index: exit non-zero if the output file is missing or stale
+  --output <file>        index: where to write the index (required)
+
+Use - as the file to read from stdin.";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Command {
+    Validate,
+    Lint,
+    Fmt,
+    Stats,
+    Index,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Human,
+    Json,
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    command: Command,
+    file: String,
+    format: Format,
+    deny_warnings: bool,
+    write: bool,
+    check: bool,
+    output: Option<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut args = args.iter();
+    let command = match args.next().map(String::as_str) {
+        Some("validate") => Command::Validate,
+        Some("lint") => Command::Lint,
+        Some("fmt") => Command::Fmt,
+        Some("stats") => Command::Stats,
+        Some("index") => Command::Index,
+        Some(other) => return Err(format!("Unknown command '{}'", other)),
+        None => return Err("Missing command".to_string()),
+    };
+
+    let mut file = None;
+    let mut format = Format::Human;
+    let mut deny_warnings = false;
+    let mut write = false;
+    let mut check = false;
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().map(String::as_str) {
+                    Some("human") => Format::Human,
+                    Some("json") => Format::Json,
+                    Some(other) => return Err(format!("Unknown format '{}', expected human or json", other)),
+                    None => return Err("--format needs a value".to_string()),
+                }
+            }
+            "--deny-warnings" if command == Command::Lint => deny_warnings = true,
+            "--write" if command == Command::Fmt => write = true,
+            "--check" if matches!(command, Command::Fmt | Command::Index) => check = true,
+            "--output" if command == Command::Index => match args.next() {
+                Some(path) => output = Some(path.to_string()),
+                None => return Err("--output needs a file".to_string()),
+            },
+            flag if flag.starts_with("--") => return Err(format!("Unknown option '{}'", flag)),
+            path if file.is_none() => file = Some(path.to_string()),
+            extra => return Err(format!("Unexpected argument '{}'", extra)),
+        }
+    }
+
+    let file = file.ok_or("Missing file")?;
+    if write && file == "-" {
+        return Err("--write needs a file, not stdin".to_string());
+    }
+    if write && check {
+        return Err("--write and --check can't be combined".to_string());
+    }
+    if command == Command::Index && output.is_none() {
+        return Err("index needs --output <file>".to_string());
+    }
+    Ok(Options { command, file, format, deny_warnings, write, check, output })
+}
+
+fn read_input(file: &str) -> io::Result<String> {
+    if file == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        Ok(text)
+    } else {
+        std::fs::read_to_string(file)
+    }
+}
+
+/// Display name of the input in diagnostics
+fn origin(file: &str) -> &str {
+    if file == "-" { "<stdin>" } else { file }
+}
+
+/// Validates `text` as music data, or as a bare song array when it is one
+fn check(text: &str) -> ValidationReport {
+    if text.trim_start().starts_with('[') {
+        check_songs(text)
+    } else {
+        check_music_data(text)
+    }
+}
+
+fn print_report(out: &mut dyn Write, report: &ValidationReport, text: &str, options: &Options) -> io::Result<()> {
+    let mut report = report.clone();
+    if options.command == Command::Validate {
+        report.diagnostics.retain(|d| d.severity == Severity::Error);
+    }
+
+    match options.format {
+        Format::Json => writeln!(out, "{}", report.to_json()),
+        Format::Human => {
+            for diagnostic in &report.diagnostics {
+                writeln!(out, "{}\n", diagnostic.render(text, origin(&options.file)))?;
+            }
+            let errors = report.errors().count();
+            let warnings = report.warnings().count();
+            if errors == 0 && warnings == 0 {
+                writeln!(out, "{}: ok", origin(&options.file))
+            } else if options.command == Command::Validate {
+                writeln!(out, "{}: {} error(s)", origin(&options.file), errors)
+            } else {
+                writeln!(out, "{}: {} error(s), {} warning(s)", origin(&options.file), errors, warnings)
+            }
+        }
+    }
+}
+
+fn print_stats(out: &mut dyn Write, catalog: &Catalog, format: Format) -> io::Result<()> {
+    let stats = catalog.compute_stats();
+    if format == Format::Json {
+        return writeln!(out, "{}", json!(stats));
+    }
+
+    writeln!(out, "Songs:            {}", stats.song_count)?;
+    writeln!(out, "Albums:           {}", stats.album_count)?;
+    writeln!(out, "Creator-friendly: {}", stats.creator_friendly_count)?;
+    writeln!(out, "Content ID:       {}", stats.content_id_count)?;
+    writeln!(out, "Release types:")?;
+    for (release_type, count) in &stats.release_types {
+        writeln!(out, "  {:<16}{}", release_type, count)?;
+    }
+    writeln!(out, "Licenses:")?;
+    let width = stats.licenses.keys().map(|license| license.len().max("(none)".len())).max().unwrap_or(0);
+    for (license, count) in &stats.licenses {
+        let license = if license.is_empty() { "(none)" } else { license };
+        writeln!(out, "  {:<width$}  {}", license, count, width = width)?;
+    }
+    Ok(())
+}
+
+/// Writes the catalog's search index to `output`, or with `check` only
+/// reports whether the existing file is current
+fn write_index(out: &mut dyn Write, catalog: &Catalog, output: &str, check: bool) -> io::Result<u8> {
+    let rebuild = format!("run `musicctl index --output {}`", output);
+    if check {
+        return match std::fs::read(output) {
+            Ok(bytes) => match SearchIndex::from_bytes_for(&bytes, catalog.songs()) {
+                Ok(_) => writeln!(out, "{}: ok", output).map(|_| 0),
+                Err(e) => writeln!(out, "{}: {}", output, e).map(|_| 1),
+            },
+            Err(e) => writeln!(out, "{}: cannot read: {}; {}", output, e, rebuild).map(|_| 1),
+        };
+    }
+
+    let bytes = catalog.search_index().to_bytes();
+    std::fs::write(output, &bytes)?;
+    writeln!(out, "{}: indexed {} song(s) in {} bytes", output, catalog.length(), bytes.len()).map(|_| 0)
+}
+
+/// Runs one command, returning the process exit code
+fn run(args: &[String], out: &mut dyn Write, err: &mut dyn Write) -> u8 {
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(e) => {
+            let _ = writeln!(err, "error: {}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    let text = match read_input(&options.file) {
+        Ok(text) => text,
+        Err(e) => {
+            let _ = writeln!(err, "error: cannot read {}: {}", origin(&options.file), e);
+            return 2;
+        }
+    };
+
+    let result = match options.command {
+        Command::Validate | Command::Lint => {
+            let report = check(&text);
+            print_report(out, &report, &text, &options).map(|_| {
+                let failed = !report.is_valid() || (options.deny_warnings && report.warnings().next().is_some());
+                u8::from(failed)
+            })
+        }
+        Command::Fmt => match format_music_json(&text) {
+            Ok(formatted) if options.check => {
+                if formatted == text {
+                    writeln!(out, "{}: ok", origin(&options.file)).map(|_| 0)
+                } else {
+                    writeln!(out, "{}: not canonically formatted; run `musicctl fmt --write`", origin(&options.file)).map(|_| 1)
+                }
+            }
+            Ok(formatted) if options.write => std::fs::write(&options.file, formatted).map(|_| 0),
+            Ok(formatted) => out.write_all(formatted.as_bytes()).map(|_| 0),
+            Err(e) => {
+                let _ = writeln!(err, "error: {}: {}", origin(&options.file), e);
+                return 1;
+            }
+        },
+        Command::Stats => {
+            let catalog = Catalog::load(&text);
+            if !catalog.is_valid() {
+                let _ = writeln!(err, "error: {} is invalid; run `musicctl validate` for details", origin(&options.file));
+                return 1;
+            }
+            print_stats(out, &catalog, options.format).map(|_| 0)
+        }
+        Command::Index => {
+            let catalog = Catalog::load(&text);
+            if !catalog.is_valid() {
+                let _ = writeln!(err, "error: {} is invalid; run `musicctl validate` for details", origin(&options.file));
+                return 1;
+            }
+            write_index(out, &catalog, options.output.as_deref().unwrap_or_default(), options.check)
+        }
+    };
+
+    result.unwrap_or_else(|e| {
+        let _ = writeln!(err, "error: {}", e);
+        2
+    })
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    ExitCode::from(run(&args, &mut io::stdout().lock(), &mut io::stderr().lock()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    const VALID: &str = r#"{"songs": [{"id": "song-001", "title": "Digital Dreams", "albumName": "Synthwave", "releaseType": "Independent", "hasContentId": false, "streamingLink": "https://push.fm/1", "license": "CC BY 4.0"}]}"#;
+    const WARNING: &str = r#"{"songs": [{"id": "song-001", "title": "Digital Dreams", "albumName": "Synthwave", "releaseType": "Independent", "hasContentId": false, "streamingLink": "push.fm/1", "license": "CC BY 4.0"}]}"#;
+    const INVALID: &str = "{\"songs\": [\n  {\"id\": \"\", \"title\": \"T\", \"albumName\": \"A\", \"releaseType\": \"Label\", \"hasContentId\": false, \"streamingLink\": \"https://x\", \"license\": \"\"}\n]}";
+
+    /// Writes `contents` to a fresh temp file and runs `musicctl <args> <file>`
+    fn run_on(name: &str, contents: &str, args: &[&str]) -> (u8, String, String) {
+        let path = std::env::temp_dir().join(format!("musicctl-{}-{}.json", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+
+        let mut args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        args.push(path.to_string_lossy().into_owned());
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let code = run(&args, &mut out, &mut err);
+
+        let out = String::from_utf8(out).unwrap().replace(&*path.to_string_lossy(), "music.json");
+        std::fs::remove_file(&path).unwrap();
+        (code, out, String::from_utf8(err).unwrap())
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = |list: &[&str]| list.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        let options = parse_args(&args(&["lint", "--format", "json", "--deny-warnings", "music.json"])).unwrap();
+        assert_eq!(options.command, Command::Lint);
+        assert_eq!(options.format, Format::Json);
+        assert!(options.deny_warnings);
+        assert_eq!(options.file, "music.json");
+
+        assert_eq!(parse_args(&args(&["publish", "x"])).unwrap_err(), "Unknown command 'publish'");
+        assert_eq!(parse_args(&args(&["validate"])).unwrap_err(), "Missing file");
+        assert!(parse_args(&args(&["validate", "--write", "x"])).is_err());
+        assert!(parse_args(&args(&["fmt", "--write", "-"])).is_err());
+        assert!(parse_args(&args(&["fmt", "--write", "--check", "x"])).is_err());
+        assert_eq!(parse_args(&args(&["index", "x"])).unwrap_err(), "index needs --output <file>");
+        assert_eq!(parse_args(&args(&["index", "--output", "i.bin", "x"])).unwrap().output.as_deref(), Some("i.bin"));
+        assert!(parse_args(&args(&["stats", "--output", "i.bin", "x"])).is_err());
+    }
+
+    #[test]
+    fn test_validate_valid_file() {
+        let (code, out, _) = run_on("valid", VALID, &["validate"]);
+        assert_eq!(code, 0);
+        assert_eq!(out, "music.json: ok\n");
+    }
+
+    #[test]
+    fn test_validate_reports_errors_with_snippets() {
+        let (code, out, _) = run_on("invalid", INVALID, &["validate"]);
+        assert_eq!(code, 1);
+        assert!(out.contains("error[empty-field]: Song at index 0 is invalid: Field 'id' cannot be empty\n --> music.json:2:10"));
+        assert!(out.contains("error[invalid-value]"));
+        assert!(out.ends_with("music.json: 2 error(s)\n"));
+    }
+
+    #[test]
+    fn test_validate_ignores_warnings_but_lint_can_deny_them() {
+        assert_eq!(run_on("warn-validate", WARNING, &["validate"]).0, 0);
+
+        let (code, out, _) = run_on("warn-lint", WARNING, &["lint"]);
+        assert_eq!(code, 0);
+        assert!(out.contains("warning[non-http-link]"));
+        assert!(out.ends_with("0 error(s), 1 warning(s)\n"));
+
+        assert_eq!(run_on("warn-deny", WARNING, &["lint", "--deny-warnings"]).0, 1);
+    }
+
+    #[test]
+    fn test_json_output() {
+        let (code, out, _) = run_on("json", INVALID, &["lint", "--format", "json"]);
+        assert_eq!(code, 1);
+        let report: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(report["errorCount"], 2);
+        assert_eq!(report["diagnostics"][0]["path"], "/songs/0/id");
+    }
+
+    #[test]
+    fn test_validate_bare_song_array() {
+        let songs = r#"[{"id": "a", "title": "T", "albumName": "A", "releaseType": "NCS", "hasContentId": false, "streamingLink": "https://x", "license": ""},
+                        {"id": "a", "title": "T", "albumName": "A", "releaseType": "NCS", "hasContentId": false, "streamingLink": "https://x", "license": ""}]"#;
+        let (code, out, _) = run_on("array", songs, &["validate"]);
+        assert_eq!(code, 1);
+        assert!(out.contains("error[duplicate-id]: Duplicate song ID 'a' found at index 1 (first used at index 0)\n --> music.json:2:32"));
+    }
+
+    #[test]
+    fn test_fmt_and_stats() {
+        let (code, out, _) = run_on("fmt", VALID, &["fmt"]);
+        assert_eq!(code, 0);
+        assert!(out.starts_with("{\n  \"songs\": [\n    {\n      \"id\": \"song-001\",\n"));
+        assert_eq!(run_on("fmt-again", &out, &["fmt"]).1, out);
+
+        let (code, out, _) = run_on("stats", VALID, &["stats", "--format", "json"]);
+        assert_eq!(code, 0);
+        assert_eq!(serde_json::from_str::<Value>(&out).unwrap()["songCount"], 1);
+
+        let (code, _, err) = run_on("stats-invalid", INVALID, &["stats"]);
+        assert_eq!(code, 1);
+        assert!(err.contains("is invalid"));
+    }
+
+    #[test]
+    fn test_fmt_canonicalizes_and_checks() {
+        let messy = VALID.replace("\"CC BY 4.0\"", "\" cc-by-4.0 \"");
+        let (code, out, _) = run_on("fmt-check-messy", &messy, &["fmt", "--check"]);
+        assert_eq!(code, 1);
+        assert_eq!(out, "music.json: not canonically formatted; run `musicctl fmt --write`\n");
+
+        let (code, formatted, _) = run_on("fmt-messy", &messy, &["fmt"]);
+        assert_eq!(code, 0);
+        assert!(formatted.contains("\"license\": \"CC BY 4.0\""));
+        assert_eq!(run_on("fmt-check-clean", &formatted, &["fmt", "--check"]), (0, "music.json: ok\n".to_string(), String::new()));
+
+        let (code, _, err) = run_on("fmt-invalid", INVALID, &["fmt"]);
+        assert_eq!(code, 1);
+        assert!(err.contains("Cannot format invalid music data"));
+    }
+
+    #[test]
+    fn test_index_writes_and_checks_artifact() {
+        let output = std::env::temp_dir().join(format!("musicctl-{}-index.bin", std::process::id()));
+        let output = output.to_string_lossy().into_owned();
+
+        let (code, out, _) = run_on("index-check-missing", VALID, &["index", "--check", "--output", &output]);
+        assert_eq!(code, 1);
+        assert!(out.contains("cannot read"));
+
+        let (code, out, _) = run_on("index", VALID, &["index", "--output", &output]);
+        assert_eq!(code, 0);
+        assert!(out.contains("indexed 1 song(s)"));
+        let bytes = std::fs::read(&output).unwrap();
+        assert!(SearchIndex::from_bytes_for(&bytes, Catalog::load(VALID).songs()).is_ok());
+        assert_eq!(run_on("index-check", VALID, &["index", "--check", "--output", &output]).0, 0);
+
+        let edited = VALID.replace("Digital Dreams", "Digital Dreams (Remix)");
+        let (code, out, _) = run_on("index-check-stale", &edited, &["index", "--check", "--output", &output]);
+        assert_eq!(code, 1);
+        assert!(out.contains("Search index is stale"));
+
+        assert_eq!(run_on("index-invalid", INVALID, &["index", "--output", &output]).0, 1);
+        std::fs::remove_file(&output).unwrap();
+    }
+
+    #[test]
+    fn test_missing_file_is_a_usage_error() {
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let code = run(&["validate".to_string(), "/nonexistent/music.json".to_string()], &mut out, &mut err);
+        assert_eq!(code, 2);
+        assert!(String::from_utf8(err).unwrap().starts_with("error: cannot read /nonexistent/music.json"));
+    }
+}
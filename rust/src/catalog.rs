@@ -0,0 +1,511 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use serde_json::Value;
+use std::cell::OnceCell;
+use std::collections::{BTreeMap, HashMap};
+use crate::filters::is_creator_friendly_with;
+use crate::grouping::{group_songs_by, group_songs_with, Album, AlbumInfo, GroupKey, SongGroup};
+use crate::js::{from_js, to_js};
+use crate::policy::CreatorFriendlyPolicy;
+use crate::report::ValidationReport;
+use crate::index_file::IndexError;
+use crate::facets::{facet_search, FacetQuery, FacetResult};
+use crate::query::{QueryError, SongQuery};
+use crate::search::SearchIndex;
+use crate::suggest::{Suggester, Suggestion, DEFAULT_SUGGESTION_LIMIT};
+use crate::sorting::{sort_songs, SongSort};
+use crate::validation::{check_music_data_value, parse_music_data, MusicData, ReleaseType, Song};
+
+/// Criteria for `Catalog::filter`, mirroring the search page's filters
+/// Empty lists and an empty query don't filter anything
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CatalogFilter {
+    pub query: String,
+    pub release_types: Vec<ReleaseType>,
+    /// Exact license strings, as written in `music.json`
+    pub licenses: Vec<String>,
+    pub creator_friendly_only: bool,
+    /// Order of the results; catalog order when absent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<SongSort>,
+}
+
+/// Summary numbers for a catalog
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogStats {
+    pub song_count: usize,
+    pub album_count: usize,
+    pub creator_friendly_count: usize,
+    pub content_id_count: usize,
+    /// Song counts keyed by release type
+    pub release_types: BTreeMap<String, usize>,
+    /// Song counts keyed by canonical license ("" for songs without one)
+    pub licenses: BTreeMap<String, usize>,
+}
+
+/// Music data parsed and validated once, then queried in place
+/// Invalid data loads as an empty catalog; the report says why
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    songs: Vec<Song>,
+    /// Album declarations from `music.json`
+    declared_albums: Vec<AlbumInfo>,
+    by_id: HashMap<String, usize>,
+    /// Full-text index over the songs, in catalog order
+    index: SearchIndex,
+    /// Title and album name autocomplete, built on the first `suggestions` call
+    /// so loading with a prebuilt index doesn't fold every name up front
+    suggester: OnceCell<Suggester>,
+    report: ValidationReport,
+    policy: CreatorFriendlyPolicy,
+}
+
+impl Catalog {
+    /// Loads `music.json` contents, validating them first
+    pub fn load(data_json: &str) -> Self {
+        let (data, report) = parse_valid(data_json);
+        Catalog::with_data(data, report, None)
+    }
+
+    /// Loads `music.json` contents like `load`, but with a search index built
+    /// ahead of time (`SearchIndex::to_bytes`) instead of indexing in place
+    /// The index is rejected if it is damaged, from another format version or
+    /// built from other songs; invalid data loads as an empty catalog as usual
+    pub fn load_with_index(data_json: &str, index: &[u8]) -> Result<Self, IndexError> {
+        let (data, report) = parse_valid(data_json);
+        let index = data.as_ref().map(|data| SearchIndex::from_bytes_for(index, &data.songs)).transpose()?;
+        Ok(Catalog::with_data(data, report, index))
+    }
+
+    /// Loads already-parsed music data, validating it first
+    pub fn load_value(data: Value) -> Self {
+        let mut report = ValidationReport::new();
+        check_music_data_value(&data, &mut report);
+        let data = if report.is_valid() { serde_json::from_value::<MusicData>(data).ok() } else { None };
+        Catalog::with_data(data, report, None)
+    }
+
+    /// Builds the search index unless a prebuilt one is given
+    fn with_data(data: Option<MusicData>, report: ValidationReport, index: Option<SearchIndex>) -> Self {
+        let (songs, declared_albums) = data.map(|data| (data.songs, data.albums)).unwrap_or_default();
+        let by_id = songs.iter().enumerate().map(|(i, song)| (song.id.clone(), i)).collect();
+        let index = index.unwrap_or_else(|| SearchIndex::build(&songs));
+        Catalog { songs, declared_albums, by_id, index, suggester: OnceCell::new(), report, policy: CreatorFriendlyPolicy::default() }
+    }
+
+    pub fn songs(&self) -> &[Song] {
+        &self.songs
+    }
+
+    pub fn declared_albums(&self) -> &[AlbumInfo] {
+        &self.declared_albums
+    }
+
+    pub fn search_index(&self) -> &SearchIndex {
+        &self.index
+    }
+
+    pub fn report(&self) -> &ValidationReport {
+        &self.report
+    }
+
+    pub fn policy(&self) -> &CreatorFriendlyPolicy {
+        &self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: CreatorFriendlyPolicy) {
+        self.policy = policy;
+    }
+
+    pub fn song(&self, id: &str) -> Option<&Song> {
+        self.by_id.get(id).map(|&i| &self.songs[i])
+    }
+
+    /// Songs that are creator-friendly under the catalog's policy
+    pub fn creator_friendly_songs(&self) -> Vec<&Song> {
+        self.songs.iter().filter(|song| is_creator_friendly_with(song, &self.policy)).collect()
+    }
+
+    /// Songs whose title, album or license contains `query`, ignoring case
+    pub fn search_songs(&self, query: &str) -> Vec<&Song> {
+        let query = query.to_lowercase();
+        self.songs.iter().filter(|song| matches_query(song, &query)).collect()
+    }
+
+    /// Songs matching `query` by title, album, license or release type, best
+    /// match first (see `SearchIndex`)
+    pub fn ranked_search(&self, query: &str) -> Vec<&Song> {
+        self.index.search(query).iter().filter_map(|hit| self.song(&hit.id)).collect()
+    }
+
+    /// Songs matching a parsed search query, in catalog order; `creator:` uses
+    /// the catalog's policy
+    pub fn query_songs(&self, query: &SongQuery) -> Vec<&Song> {
+        self.songs.iter().filter(|song| query.matches(song, &self.policy)).collect()
+    }
+
+    /// Songs matching a faceted search, with live counts for each facet value
+    /// (see `facet_search`); uses the declared albums and the catalog's policy
+    pub fn facet_search(&self, request: &FacetQuery) -> Result<FacetResult, QueryError> {
+        facet_search(&self.songs, request, &self.declared_albums, &self.policy)
+    }
+
+    /// Up to `limit` title and album name suggestions for a search box query,
+    /// best first (see `Suggester`)
+    pub fn suggestions(&self, query: &str, limit: usize) -> Vec<Suggestion> {
+        self.suggester.get_or_init(|| Suggester::new(&self.songs, &self.declared_albums)).suggest(query, limit)
+    }
+
+    /// Songs matching every criterion in `filter`, in catalog order
+    pub fn filter_songs(&self, filter: &CatalogFilter) -> Vec<&Song> {
+        let query = filter.query.to_lowercase();
+        let songs = self
+            .songs
+            .iter()
+            .filter(|song| query.is_empty() || matches_query(song, &query))
+            .filter(|song| filter.release_types.is_empty() || filter.release_types.contains(&song.release_type))
+            .filter(|song| filter.licenses.is_empty() || filter.licenses.contains(&song.license))
+            .filter(|song| !filter.creator_friendly_only || is_creator_friendly_with(song, &self.policy));
+        let mut songs: Vec<&Song> = songs.collect();
+        if let Some(sort) = &filter.sort {
+            sort_songs(&mut songs, sort);
+        }
+        songs
+    }
+
+    /// Songs grouped into albums, with declared album metadata attached
+    pub fn albums(&self) -> Vec<Album> {
+        group_songs_with(self.songs.iter().cloned(), &self.declared_albums)
+    }
+
+    /// Songs grouped by `keys`, see `group_songs_by`
+    /// The creator-friendly key uses the catalog's policy
+    pub fn groups(&self, keys: &[GroupKey]) -> Result<Vec<SongGroup>, String> {
+        group_songs_by(self.songs.iter().cloned(), keys, &self.declared_albums, &self.policy)
+    }
+
+    pub fn compute_stats(&self) -> CatalogStats {
+        let mut stats = CatalogStats { song_count: self.songs.len(), ..Default::default() };
+        for song in &self.songs {
+            if song.has_content_id {
+                stats.content_id_count += 1;
+            }
+            if is_creator_friendly_with(song, &self.policy) {
+                stats.creator_friendly_count += 1;
+            }
+            *stats.release_types.entry(format!("{:?}", song.release_type)).or_default() += 1;
+            *stats.licenses.entry(song.license_expression().to_string()).or_default() += 1;
+        }
+        stats.album_count = self.albums().len();
+        stats
+    }
+}
+
+/// `query` must already be lowercase
+fn matches_query(song: &Song, query: &str) -> bool {
+    song.title.to_lowercase().contains(query)
+        || song.album_name.to_lowercase().contains(query)
+        || song.license.to_lowercase().contains(query)
+}
+
+/// Parses and validates `music.json` contents in one pass over the text
+/// The data is only deserialized when the report has no errors
+fn parse_valid(data_json: &str) -> (Option<MusicData>, ValidationReport) {
+    let (report, value) = parse_music_data(data_json);
+    let data = value.filter(|_| report.is_valid()).and_then(|value| serde_json::from_value::<MusicData>(value).ok());
+    (data, report)
+}
+
+#[wasm_bindgen]
+impl Catalog {
+    /// Parses and validates `music.json` text once
+    /// Check `isValid` / `validationReport()` before querying
+    #[wasm_bindgen(constructor)]
+    pub fn new(data_json: &str) -> Catalog {
+        Catalog::load(data_json)
+    }
+
+    /// Builds a catalog from an already-parsed music data object
+    #[wasm_bindgen(js_name = fromObject)]
+    pub fn from_object(#[wasm_bindgen(unchecked_param_type = "MusicData")] data: JsValue) -> Result<Catalog, JsError> {
+        let value: Value = from_js(data, "music data")?;
+        Ok(Catalog::load_value(value))
+    }
+
+    /// Parses and validates `music.json` text, loading the search index from
+    /// bytes built ahead of time (e.g. `public/search-index.bin`)
+    /// Throws if the index is damaged, from another format version or stale;
+    /// fall back to `new Catalog(json)` to index in the browser instead
+    #[wasm_bindgen(js_name = withIndex)]
+    pub fn with_index(data_json: &str, index: &[u8]) -> Result<Catalog, JsError> {
+        Catalog::load_with_index(data_json, index).map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    /// Serializes the catalog's search index, e.g. to cache it
+    #[wasm_bindgen(js_name = exportIndex)]
+    pub fn export_index(&self) -> Vec<u8> {
+        self.index.to_bytes()
+    }
+
+    #[wasm_bindgen(getter, js_name = isValid)]
+    pub fn is_valid(&self) -> bool {
+        self.report.is_valid()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.songs.len()
+    }
+
+    /// Returns the load-time validation report (same shape as `validateMusicData`)
+    #[wasm_bindgen(js_name = validationReport, unchecked_return_type = "ValidationSummary")]
+    pub fn validation_report(&self) -> Result<JsValue, JsError> {
+        to_js(&self.report.summary())
+    }
+
+    /// Replaces the creator-friendliness policy used by later queries
+    #[wasm_bindgen(js_name = setPolicy)]
+    pub fn set_policy_js(&mut self, #[wasm_bindgen(unchecked_param_type = "CreatorFriendlyPolicy")] policy: JsValue) -> Result<(), JsError> {
+        self.policy = from_js(policy, "policy")?;
+        Ok(())
+    }
+
+    /// Returns the song with `id`, or `null`
+    #[wasm_bindgen(js_name = getSong, unchecked_return_type = "Song | null")]
+    pub fn get_song(&self, id: &str) -> Result<JsValue, JsError> {
+        to_js(&self.song(id))
+    }
+
+    /// Returns the creator-friendly songs
+    #[wasm_bindgen(js_name = creatorFriendly, unchecked_return_type = "Song[]")]
+    pub fn creator_friendly(&self) -> Result<JsValue, JsError> {
+        to_js(&self.creator_friendly_songs())
+    }
+
+    /// Returns songs whose title, album or license contains `query`
+    #[wasm_bindgen(unchecked_return_type = "Song[]")]
+    pub fn search(&self, query: &str) -> Result<JsValue, JsError> {
+        to_js(&self.search_songs(query))
+    }
+
+    /// Returns songs matching every word of `query`, best match first
+    #[wasm_bindgen(js_name = rankedSearch, unchecked_return_type = "Song[]")]
+    pub fn ranked_search_js(&self, query: &str) -> Result<JsValue, JsError> {
+        to_js(&self.ranked_search(query))
+    }
+
+    /// Returns the IDs of the songs matching every word of `query`, best match first
+    #[wasm_bindgen(js_name = rankedSearchIds, unchecked_return_type = "string[]")]
+    pub fn ranked_search_ids_js(&self, query: &str) -> Result<JsValue, JsError> {
+        to_js(&self.index.search_ids(query))
+    }
+
+    /// Returns songs matching a query such as `album:"Synthwave" year:2023.. -contentid`,
+    /// throwing a readable error if it doesn't parse
+    #[wasm_bindgen(unchecked_return_type = "Song[]")]
+    pub fn query(&self, query: &str) -> Result<JsValue, JsError> {
+        let query = SongQuery::parse(query).map_err(|e| JsError::new(&format!("Invalid query: {}", e)))?;
+        to_js(&self.query_songs(&query))
+    }
+
+    /// Returns `{ songs, facets }` for `{ query, selections, facets, sort }`:
+    /// the matching songs and, per facet, each value's label and count
+    #[wasm_bindgen(unchecked_return_type = "FacetResult")]
+    pub fn facets(&self, #[wasm_bindgen(unchecked_param_type = "FacetQuery")] request: JsValue) -> Result<JsValue, JsError> {
+        let request: FacetQuery = from_js(request, "facet query")?;
+        let result = self.facet_search(&request).map_err(|e| JsError::new(&format!("Invalid query: {}", e)))?;
+        to_js(&result)
+    }
+
+    /// Returns up to `limit` (default 10) title and album name suggestions for
+    /// `query`, with match scores and highlight ranges
+    #[wasm_bindgen(unchecked_return_type = "Suggestion[]")]
+    pub fn suggest(&self, query: &str, limit: Option<u32>) -> Result<JsValue, JsError> {
+        let limit = limit.map_or(DEFAULT_SUGGESTION_LIMIT, |limit| limit as usize);
+        to_js(&self.suggestions(query, limit))
+    }
+
+    /// Returns songs matching `{ query, releaseTypes, licenses, creatorFriendlyOnly }`,
+    /// ordered by `sort` when given
+    #[wasm_bindgen(unchecked_return_type = "Song[]")]
+    pub fn filter(&self, #[wasm_bindgen(unchecked_param_type = "CatalogFilter")] filter: JsValue) -> Result<JsValue, JsError> {
+        let filter: CatalogFilter = from_js(filter, "filter")?;
+        to_js(&self.filter_songs(&filter))
+    }
+
+    /// Returns albums with their declared metadata, same shape as `groupByAlbum`
+    #[wasm_bindgen(js_name = groupByAlbum, unchecked_return_type = "Album[]")]
+    pub fn group_by_album(&self) -> Result<JsValue, JsError> {
+        to_js(&self.albums())
+    }
+
+    /// Returns songs grouped by one or more keys, nested a level per key, with counts
+    #[wasm_bindgen(js_name = groupBy, unchecked_return_type = "SongGroup[]")]
+    pub fn group_by(&self, #[wasm_bindgen(unchecked_param_type = "GroupKey[]")] keys: JsValue) -> Result<JsValue, JsError> {
+        let keys: Vec<GroupKey> = from_js(keys, "group keys")?;
+        to_js(&self.groups(&keys).map_err(|e| JsError::new(&e))?)
+    }
+
+    /// Returns `{ songCount, albumCount, creatorFriendlyCount, contentIdCount, releaseTypes, licenses }`
+    #[wasm_bindgen(unchecked_return_type = "CatalogStats")]
+    pub fn stats(&self) -> Result<JsValue, JsError> {
+        to_js(&self.compute_stats())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::suggest::SuggestionKind;
+
+    const DATA: &str = r#"{"songs": [
+        {"id":"song-001","title":"Digital Dreams","albumName":"Synthwave Chronicles","releaseType":"Independent","hasContentId":false,"streamingLink":"https://push.fm/1","license":"CC BY 4.0"},
+        {"id":"song-002","title":"Neon Nights","albumName":"Synthwave Chronicles","releaseType":"Independent","hasContentId":true,"streamingLink":"https://push.fm/2","license":"All Rights Reserved"},
+        {"id":"song-003","title":"Electric Pulse","albumName":"Pulse","releaseType":"NCS","hasContentId":true,"streamingLink":"https://push.fm/3","license":""}
+    ]}"#;
+
+    fn ids(songs: &[&Song]) -> Vec<String> {
+        songs.iter().map(|song| song.id.clone()).collect()
+    }
+
+    #[test]
+    fn test_catalog_loads_and_looks_up() {
+        let catalog = Catalog::load(DATA);
+        assert!(catalog.is_valid());
+        assert_eq!(catalog.length(), 3);
+        assert_eq!(catalog.song("song-002").unwrap().title, "Neon Nights");
+        assert!(catalog.song("song-999").is_none());
+    }
+
+    #[test]
+    fn test_catalog_loads_prebuilt_index() {
+        let bytes = Catalog::load(DATA).export_index();
+        let catalog = Catalog::load_with_index(DATA, &bytes).unwrap();
+        assert_eq!(catalog.search_index(), Catalog::load(DATA).search_index());
+        assert_eq!(ids(&catalog.ranked_search("neon")), vec!["song-002"]);
+        // Suggestions are only prepared once asked for
+        assert!(catalog.suggester.get().is_none());
+        assert!(!catalog.suggestions("neo", 5).is_empty());
+        assert!(catalog.suggester.get().is_some());
+
+        let edited = DATA.replace("Neon Nights", "Neon Lights");
+        assert_eq!(Catalog::load_with_index(&edited, &bytes).unwrap_err(), IndexError::Stale);
+        assert_eq!(Catalog::load_with_index(DATA, b"").unwrap_err(), IndexError::NotAnIndex);
+        // Invalid data loads empty, as without an index
+        assert!(!Catalog::load_with_index("not json", &bytes).unwrap().is_valid());
+    }
+
+    #[test]
+    fn test_invalid_data_loads_empty_with_report() {
+        let catalog = Catalog::load(r#"{"songs": [{"id": ""}]}"#);
+        assert!(!catalog.is_valid());
+        assert_eq!(catalog.length(), 0);
+        assert!(!catalog.report().diagnostics.is_empty());
+
+        let catalog = Catalog::load("not json");
+        assert!(!catalog.is_valid());
+    }
+
+    #[test]
+    fn test_catalog_queries() {
+        let mut catalog = Catalog::load(DATA);
+        assert_eq!(ids(&catalog.creator_friendly_songs()), vec!["song-001", "song-003"]);
+        assert_eq!(ids(&catalog.search_songs("SYNTH")), vec!["song-001", "song-002"]);
+        assert_eq!(ids(&catalog.search_songs("ynth")), vec!["song-001", "song-002"]);
+        assert_eq!(ids(&catalog.search_songs("pulse")), vec!["song-003"]);
+        assert!(catalog.search_songs("neon pulse").is_empty());
+
+        // Ranked search matches whole words, best match first, tolerating typos
+        assert_eq!(ids(&catalog.ranked_search("pulse")), vec!["song-003"]);
+        assert_eq!(ids(&catalog.ranked_search("electric pulse")), vec!["song-003"]);
+        assert_eq!(ids(&catalog.ranked_search("neon nigths")), vec!["song-002"]);
+        assert!(catalog.ranked_search("ynth").is_empty());
+
+        let suggestions = catalog.suggestions("puls", 10);
+        assert_eq!(suggestions.iter().map(|s| s.text.as_str()).collect::<Vec<_>>(), vec!["Electric Pulse", "Pulse"]);
+        assert_eq!(suggestions[1].kind, SuggestionKind::Album);
+
+        let filter = CatalogFilter { release_types: vec![ReleaseType::Independent], creator_friendly_only: true, ..Default::default() };
+        assert_eq!(ids(&catalog.filter_songs(&filter)), vec!["song-001"]);
+
+        let albums = catalog.albums();
+        assert_eq!(albums.iter().map(|a| a.name.as_str()).collect::<Vec<_>>(), vec!["Pulse", "Synthwave Chronicles"]);
+
+        let query = SongQuery::parse("creator:yes -type:ncs").unwrap();
+        assert_eq!(ids(&catalog.query_songs(&query)), vec!["song-001"]);
+
+        catalog.set_policy(CreatorFriendlyPolicy { allow_content_id: false, ..Default::default() });
+        assert_eq!(ids(&catalog.creator_friendly_songs()), vec!["song-001"]);
+        assert_eq!(ids(&catalog.query_songs(&SongQuery::parse("creator").unwrap())), vec!["song-001"]);
+
+        let request = FacetQuery { facets: vec![GroupKey::CreatorFriendly], ..Default::default() };
+        let result = catalog.facet_search(&request).unwrap();
+        assert_eq!(result.facets[0].values.iter().map(|value| value.count).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_catalog_albums_use_declarations() {
+        let data = DATA.replace(r#""albumName":"Pulse","#, r#""albumName":"Pulse","albumId":"album-002","#).replace(
+            "\n    ]}",
+            r#"], "albums": [{"id": "album-002", "name": "Pulse", "albumType": "Single", "releaseDate": "2024-03-01"}]}"#,
+        );
+        let catalog = Catalog::load(&data);
+        assert!(catalog.is_valid(), "{:?}", catalog.report().diagnostics);
+        assert_eq!(catalog.declared_albums().len(), 1);
+
+        let albums = catalog.albums();
+        assert_eq!(albums[0].id.as_deref(), Some("album-002"));
+        assert_eq!(albums[0].metadata.release_date.as_deref(), Some("2024-03-01"));
+        assert_eq!(albums[1].id, None);
+        assert_eq!(catalog.compute_stats().album_count, 2);
+    }
+
+    #[test]
+    fn test_catalog_groups_use_policy() {
+        let mut catalog = Catalog::load(DATA);
+        let counts = |catalog: &Catalog| -> Vec<usize> {
+            catalog.groups(&[GroupKey::CreatorFriendly]).unwrap().iter().map(|group| group.count).collect()
+        };
+        assert_eq!(counts(&catalog), vec![2, 1]);
+
+        catalog.set_policy(CreatorFriendlyPolicy { allow_content_id: false, ..Default::default() });
+        assert_eq!(counts(&catalog), vec![1, 2]);
+
+        let groups = catalog.groups(&[GroupKey::Album, GroupKey::ContentId]).unwrap();
+        assert_eq!(groups[1].label, "Synthwave Chronicles");
+        assert_eq!(groups[1].groups.iter().map(|group| group.count).collect::<Vec<_>>(), vec![1, 1]);
+    }
+
+    #[test]
+    fn test_catalog_stats() {
+        let stats = Catalog::load(DATA).compute_stats();
+        assert_eq!(stats.song_count, 3);
+        assert_eq!(stats.album_count, 2);
+        assert_eq!(stats.creator_friendly_count, 2);
+        assert_eq!(stats.content_id_count, 2);
+        assert_eq!(stats.release_types["Independent"], 2);
+        assert_eq!(stats.licenses["CC BY 4.0"], 1);
+        assert_eq!(stats.licenses[""], 1);
+    }
+
+    #[test]
+    fn test_catalog_from_parsed_value() {
+        let catalog = Catalog::load_value(serde_json::from_str(DATA).unwrap());
+        assert!(catalog.is_valid());
+        assert_eq!(catalog.songs().len(), 3);
+
+        let filter: CatalogFilter = serde_json::from_str(r#"{"query": "pulse", "releaseTypes": ["NCS"]}"#).unwrap();
+        assert_eq!(ids(&catalog.filter_songs(&filter)), vec!["song-003"]);
+
+        let filter: CatalogFilter = serde_json::from_str(r#"{"sort": {"by": [{"field": "title", "order": "desc"}]}}"#).unwrap();
+        assert_eq!(ids(&catalog.filter_songs(&filter)), vec!["song-002", "song-003", "song-001"]);
+
+        let catalog = Catalog::load_value(serde_json::json!({ "songs": "nope" }));
+        assert!(!catalog.is_valid());
+        assert_eq!(catalog.length(), 0);
+    }
+}
@@ -0,0 +1,292 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use crate::grouping::{group_value, AlbumInfo, AlbumResolver, GroupKey, GroupSort};
+use crate::js::{from_js, from_js_optional, to_js};
+use crate::policy::CreatorFriendlyPolicy;
+use crate::query::{QueryError, SongQuery};
+use crate::sorting::{sort_songs, SongSort};
+use crate::validation::Song;
+
+/// Facets counted when a `FacetQuery` doesn't name any
+pub const DEFAULT_FACETS: [GroupKey; 6] = [
+    GroupKey::ReleaseType,
+    GroupKey::LicenseFamily,
+    GroupKey::ReleaseYear,
+    GroupKey::Album,
+    GroupKey::ContentId,
+    GroupKey::CreatorFriendly,
+];
+
+/// Values ticked for one facet; a song passes if it has any of them
+/// Values are `FacetValue.value`s (the same as `SongGroup.value`s)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct FacetSelection {
+    pub key: GroupKey,
+    #[tsify(type = "(string | number | boolean | null)[]")]
+    pub values: Vec<Value>,
+}
+
+/// A faceted search: query, active filters and the facets to count
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FacetQuery {
+    /// Search text in the `SongQuery` syntax; empty matches every song
+    pub query: String,
+    /// Active filters; a song must pass every selection with values
+    pub selections: Vec<FacetSelection>,
+    /// Facets to count, in display order; `DEFAULT_FACETS` when empty
+    pub facets: Vec<GroupKey>,
+    /// Order of the matching songs; catalog order when absent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<SongSort>,
+}
+
+/// One option of a facet, e.g. "NCS (12)"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct FacetValue {
+    #[tsify(type = "string | number | boolean | null")]
+    pub value: Value,
+    /// Display text, as in `SongGroup.label`
+    pub label: String,
+    /// Songs that would match with this value ticked, counting the other
+    /// facets' selections but not this facet's own; 0 means the option can
+    /// be disabled
+    pub count: usize,
+    pub selected: bool,
+}
+
+/// Options of one facet, ordered like `group_songs_by` orders groups
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct FacetCounts {
+    pub key: GroupKey,
+    /// Every value some song in the catalog has, matching or not
+    pub values: Vec<FacetValue>,
+}
+
+/// Songs matching a `FacetQuery`, with per-facet counts
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+pub struct FacetResult {
+    pub songs: Vec<Song>,
+    pub facets: Vec<FacetCounts>,
+}
+
+/// A song's value for every facet that is counted or selected
+type SongValues = HashMap<GroupKey, (GroupSort, Value, String)>;
+
+/// Runs a faceted search over `songs`
+/// Values within a selection combine with OR and selections with AND. Each
+/// facet is counted as if its own selection were cleared, so ticking "NCS"
+/// still shows how many Monstercat songs there are. `declared` albums and
+/// `policy` are used as by `group_songs_by`
+pub fn facet_search(
+    songs: &[Song],
+    request: &FacetQuery,
+    declared: &[AlbumInfo],
+    policy: &CreatorFriendlyPolicy,
+) -> Result<FacetResult, QueryError> {
+    let query = SongQuery::parse(&request.query)?;
+    let facets: &[GroupKey] = if request.facets.is_empty() { &DEFAULT_FACETS } else { &request.facets };
+
+    let resolver = AlbumResolver::new(declared, songs);
+    let mut keys: Vec<GroupKey> = facets.to_vec();
+    for selection in &request.selections {
+        if !keys.contains(&selection.key) {
+            keys.push(selection.key);
+        }
+    }
+    let values: Vec<SongValues> = songs
+        .iter()
+        .map(|song| keys.iter().map(|&key| (key, group_value(key, song, &resolver, policy))).collect())
+        .collect();
+    let query_matches: Vec<bool> = songs.iter().map(|song| query.matches(song, policy)).collect();
+
+    // Whether song `i` passes the query and every selection except `except`'s
+    let passes = |i: usize, except: Option<GroupKey>| {
+        query_matches[i]
+            && request
+                .selections
+                .iter()
+                .filter(|selection| Some(selection.key) != except && !selection.values.is_empty())
+                .all(|selection| selection.values.contains(&values[i][&selection.key].1))
+    };
+
+    let facets = facets
+        .iter()
+        .map(|&key| {
+            let selected: Vec<&Value> =
+                request.selections.iter().filter(|selection| selection.key == key).flat_map(|selection| &selection.values).collect();
+            let mut options: BTreeMap<&GroupSort, FacetValue> = BTreeMap::new();
+            for (i, song_values) in values.iter().enumerate() {
+                let (sort, value, label) = &song_values[&key];
+                let option = options.entry(sort).or_insert_with(|| FacetValue {
+                    value: value.clone(),
+                    label: label.clone(),
+                    count: 0,
+                    selected: selected.contains(&value),
+                });
+                if passes(i, Some(key)) {
+                    option.count += 1;
+                }
+            }
+            FacetCounts { key, values: options.into_values().collect() }
+        })
+        .collect();
+
+    let mut matching: Vec<Song> = songs.iter().enumerate().filter(|&(i, _)| passes(i, None)).map(|(_, song)| song.clone()).collect();
+    if let Some(sort) = &request.sort {
+        sort_songs(&mut matching, sort);
+    }
+    Ok(FacetResult { songs: matching, facets })
+}
+
+/// Runs a faceted search: `request_json` is a `FacetQuery`, `policy_json` an
+/// optional `CreatorFriendlyPolicy` for the creator-friendly facet
+/// Returns JSON string of `{ songs, facets }`
+#[wasm_bindgen]
+pub fn facet_search_songs(songs_json: &str, request_json: &str, policy_json: Option<String>) -> String {
+    let songs: Vec<Song> = match serde_json::from_str(songs_json) {
+        Ok(s) => s,
+        Err(e) => return json!({ "error": format!("Invalid JSON: {}", e) }).to_string(),
+    };
+    let request: FacetQuery = match serde_json::from_str(request_json) {
+        Ok(request) => request,
+        Err(e) => return json!({ "error": format!("Invalid facet query: {}", e) }).to_string(),
+    };
+    let policy = match policy_json.as_deref().map(CreatorFriendlyPolicy::from_json).transpose() {
+        Ok(policy) => policy.unwrap_or_default(),
+        Err(e) => return json!({ "error": e }).to_string(),
+    };
+    match facet_search(&songs, &request, &[], &policy) {
+        Ok(result) => json!(result).to_string(),
+        Err(e) => json!({ "error": format!("Invalid query: {}", e) }).to_string(),
+    }
+}
+
+/// Runs a faceted search over an array of song objects, returning the matching
+/// songs and per-facet value counts
+/// `policy` is optional; leaving it out uses the default policy
+#[wasm_bindgen(js_name = facetSearch, unchecked_return_type = "FacetResult")]
+pub fn facet_search_js(
+    #[wasm_bindgen(unchecked_param_type = "Song[]")] songs: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "FacetQuery")] request: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "CreatorFriendlyPolicy")] policy: Option<JsValue>,
+) -> Result<JsValue, JsError> {
+    let songs: Vec<Song> = from_js(songs, "song list")?;
+    let request: FacetQuery = from_js(request, "facet query")?;
+    let policy: Option<CreatorFriendlyPolicy> = from_js_optional(policy, "policy")?;
+    let result = facet_search(&songs, &request, &[], &policy.unwrap_or_default())
+        .map_err(|e| JsError::new(&format!("Invalid query: {}", e)))?;
+    to_js(&result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use crate::validation::ReleaseType;
+
+    fn song(id: &str, album: &str, release_type: ReleaseType, content_id: bool, license: &str, year: Option<u32>) -> Song {
+        testing::song(id)
+            .title(&format!("Song {}", id))
+            .album(album)
+            .release_type(release_type)
+            .content_id(content_id)
+            .license(license)
+            .year(year)
+            .build()
+    }
+
+    fn catalog() -> Vec<Song> {
+        vec![
+            song("s1", "Synthwave", ReleaseType::Independent, false, "CC BY 4.0", Some(2023)),
+            song("s2", "Synthwave", ReleaseType::Independent, true, "All Rights Reserved", Some(2023)),
+            song("s3", "Pulse", ReleaseType::NCS, true, "", Some(2022)),
+            song("s4", "Pulse", ReleaseType::NCS, false, "", None),
+            song("s5", "Drive", ReleaseType::Monstercat, false, "BGML-P", Some(2024)),
+        ]
+    }
+
+    fn search(request: FacetQuery) -> FacetResult {
+        facet_search(&catalog(), &request, &[], &CreatorFriendlyPolicy::default()).unwrap()
+    }
+
+    fn selection(key: GroupKey, values: Value) -> FacetSelection {
+        FacetSelection { key, values: serde_json::from_value(values).unwrap() }
+    }
+
+    /// `label (count)` for each value of `key`, with `*` marking selected ones
+    fn counts(result: &FacetResult, key: GroupKey) -> Vec<String> {
+        let facet = result.facets.iter().find(|facet| facet.key == key).unwrap();
+        facet.values.iter().map(|value| format!("{}{} ({})", if value.selected { "*" } else { "" }, value.label, value.count)).collect()
+    }
+
+    fn ids(result: &FacetResult) -> Vec<&str> {
+        result.songs.iter().map(|song| song.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_facets_count_every_value() {
+        let result = search(FacetQuery::default());
+        assert_eq!(ids(&result), ["s1", "s2", "s3", "s4", "s5"]);
+        assert_eq!(result.facets.iter().map(|facet| facet.key).collect::<Vec<_>>(), DEFAULT_FACETS);
+        assert_eq!(counts(&result, GroupKey::ReleaseType), ["Independent (2)", "Monstercat (1)", "NCS (2)"]);
+        assert_eq!(counts(&result, GroupKey::ReleaseYear), ["2022 (1)", "2023 (2)", "2024 (1)", "Unknown year (1)"]);
+        assert_eq!(counts(&result, GroupKey::Album), ["Drive (1)", "Pulse (2)", "Synthwave (2)"]);
+        assert_eq!(counts(&result, GroupKey::ContentId), ["Content ID (2)", "No Content ID (3)"]);
+        assert_eq!(
+            counts(&result, GroupKey::LicenseFamily),
+            ["Creative Commons (1)", "BGML (1)", "All Rights Reserved (1)", "Unspecified (2)"]
+        );
+        assert_eq!(counts(&result, GroupKey::CreatorFriendly), ["Creator-friendly (4)", "Not creator-friendly (1)"]);
+    }
+
+    #[test]
+    fn test_selections_filter_and_keep_own_counts() {
+        let result = search(FacetQuery {
+            selections: vec![selection(GroupKey::ReleaseType, json!(["NCS", "Monstercat"])), selection(GroupKey::ContentId, json!([false]))],
+            facets: vec![GroupKey::ReleaseType, GroupKey::ContentId, GroupKey::ReleaseYear],
+            ..Default::default()
+        });
+        assert_eq!(ids(&result), ["s4", "s5"]);
+        // A facet's counts ignore its own selection but apply the others
+        assert_eq!(counts(&result, GroupKey::ReleaseType), ["Independent (1)", "*Monstercat (1)", "*NCS (1)"]);
+        assert_eq!(counts(&result, GroupKey::ContentId), ["Content ID (1)", "*No Content ID (2)"]);
+        // Values no matching song has stay listed, with a zero count
+        assert_eq!(counts(&result, GroupKey::ReleaseYear), ["2022 (0)", "2023 (0)", "2024 (1)", "Unknown year (1)"]);
+
+        let result = search(FacetQuery { selections: vec![selection(GroupKey::ReleaseYear, json!([null, 2023]))], ..Default::default() });
+        assert_eq!(ids(&result), ["s1", "s2", "s4"]);
+        // Empty selections filter nothing
+        let result = search(FacetQuery { selections: vec![selection(GroupKey::Album, json!([]))], ..Default::default() });
+        assert_eq!(result.songs.len(), 5);
+    }
+
+    #[test]
+    fn test_query_narrows_songs_and_counts() {
+        let result = search(FacetQuery {
+            query: "creator:yes".to_string(),
+            facets: vec![GroupKey::ReleaseType],
+            sort: Some(serde_json::from_value(json!({ "by": [{ "field": "year", "order": "desc" }] })).unwrap()),
+            ..Default::default()
+        });
+        assert_eq!(ids(&result), ["s5", "s1", "s3", "s4"]);
+        assert_eq!(counts(&result, GroupKey::ReleaseType), ["Independent (1)", "Monstercat (1)", "NCS (2)"]);
+
+        let error = facet_search(&catalog(), &FacetQuery { query: "(".to_string(), ..Default::default() }, &[], &CreatorFriendlyPolicy::default());
+        assert!(error.is_err());
+    }
+
+    #[test]
+    fn test_facet_search_songs() {
+        let songs = serde_json::to_string(&catalog()).unwrap();
+        let result: Value = serde_json::from_str(&facet_search_songs(&songs, r#"{"facets": ["contentId"]}"#, None)).unwrap();
+        assert_eq!(result["songs"].as_array().unwrap().len(), 5);
+        assert_eq!(result["facets"][0]["values"][0], json!({ "value": true, "label": "Content ID", "count": 2, "selected": false }));
+        assert!(facet_search_songs(&songs, r#"{"facets": ["nope"]}"#, None).contains("Invalid facet query"));
+        assert!(facet_search_songs(&songs, r#"{"query": "year:x"}"#, None).contains("Invalid query"));
+        assert!(facet_search_songs("{", "{}", None).contains("Invalid JSON"));
+    }
+}
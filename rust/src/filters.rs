@@ -1,31 +1,198 @@
 use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use serde_json::json;
+use crate::license::{BgmlVariant, License, LicenseExpression};
+use crate::js::{from_js, from_js_optional, to_js};
+use crate::policy::{license_matches, CreatorFriendlyPolicy};
 use crate::validation::{Song, ReleaseType};
-use regex::Regex;
-use lazy_static::lazy_static;
-
-// Compile regexes once at startup for better performance
-lazy_static! {
-    static ref CC_BY_REGEX: Regex = Regex::new(r"^CC BY( \d+\.\d+)?$").unwrap();
-    static ref CC_BY_SA_REGEX: Regex = Regex::new(r"^CC BY-SA( \d+\.\d+)?$").unwrap();
-    static ref CC0_REGEX: Regex = Regex::new(r"^CC0( \d+\.\d+)?$").unwrap();
-}
 
 /// Checks if license is a commercial Creative Commons license
-/// Matches: CC BY, CC BY-SA, CC0 (with optional version numbers)
+/// Matches: CC BY, CC BY-SA, CC0 (any valid version or port)
+/// Compound expressions are evaluated: "CC-BY-NC-4.0 OR CC-BY-4.0" matches
 #[wasm_bindgen]
 pub fn is_commercial_cc_license(license: &str) -> bool {
-    let license_upper = license.to_uppercase().trim().to_string();
-    
-    // Use pre-compiled regexes for better performance
-    CC_BY_REGEX.is_match(&license_upper) ||
-    CC_BY_SA_REGEX.is_match(&license_upper) ||
-    CC0_REGEX.is_match(&license_upper)
+    LicenseExpression::parse_lenient(license).satisfies(&License::is_commercial_cc)
 }
 
 /// Checks if license is BGML-P (Babafun Game Music License - Permissive)
+/// Also true for expressions that offer BGML-P, e.g. "CC-BY-NC-4.0 OR BGML-P"
 #[wasm_bindgen]
 pub fn is_bgml_p_license(license: &str) -> bool {
-    license.trim().eq_ignore_ascii_case("BGML-P")
+    LicenseExpression::parse_lenient(license).satisfies(&License::is_bgml_permissive)
+}
+
+/// The individual rules that can make a song creator-friendly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum CreatorFriendlyRule {
+    /// License is CC BY, CC BY-SA or CC0
+    CommercialCcLicense,
+    /// Released through NCS
+    NcsRelease,
+    /// License is BGML-P
+    BgmlPermissiveLicense,
+    /// Compound license whose every required part is creator-friendly
+    /// (only checked for SPDX expressions such as "CC-BY-4.0 AND BGML-P")
+    CompoundLicense,
+    /// License is one of the policy's extra licenses (other than BGML-P)
+    ListedLicense,
+    /// Release type is one of the policy's release types (other than NCS)
+    ListedReleaseType,
+    /// Song is registered with Content ID and the policy excludes it
+    /// (never matches; reported as failed only when it rules a song out)
+    ContentIdRegistered,
+}
+
+/// Outcome of one rule for one song
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct RuleCheck {
+    pub rule: CreatorFriendlyRule,
+    pub matched: bool,
+    pub reason: String,
+}
+
+/// Why a song is (or isn't) creator-friendly
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatorFriendlyVerdict {
+    pub creator_friendly: bool,
+    pub matched: Vec<RuleCheck>,
+    pub failed: Vec<RuleCheck>,
+    /// Matched reasons joined for display, `None` if the song isn't creator-friendly
+    pub summary: Option<String>,
+}
+
+/// Describes a license for messages, naming an empty one explicitly
+fn describe_license(expression: &LicenseExpression) -> String {
+    match expression {
+        LicenseExpression::License(License::Unspecified) => "No license".to_string(),
+        _ => format!("License '{}'", expression),
+    }
+}
+
+/// Checks every creator-friendly rule of the default policy against a song
+pub fn explain_creator_friendliness(song: &Song) -> CreatorFriendlyVerdict {
+    explain_creator_friendliness_with(song, &CreatorFriendlyPolicy::default())
+}
+
+/// Checks every rule `policy` enables against a song
+/// Rules the policy doesn't enable are left out of the verdict entirely
+pub fn explain_creator_friendliness_with(song: &Song, policy: &CreatorFriendlyPolicy) -> CreatorFriendlyVerdict {
+    let expression = song.license_expression();
+    let license = describe_license(&expression);
+    let mut checks = Vec::new();
+
+    if policy.commercial_cc {
+        let commercial_cc = expression.satisfies(&License::is_commercial_cc);
+        checks.push(RuleCheck {
+            rule: CreatorFriendlyRule::CommercialCcLicense,
+            matched: commercial_cc,
+            reason: if commercial_cc {
+                format!("Commercial CC license ({})", expression)
+            } else {
+                format!("{} is not a commercial Creative Commons license", license)
+            },
+        });
+    }
+
+    if policy.allows_release_type(&ReleaseType::NCS) {
+        let ncs = song.release_type == ReleaseType::NCS;
+        checks.push(RuleCheck {
+            rule: CreatorFriendlyRule::NcsRelease,
+            matched: ncs,
+            reason: if ncs {
+                "NCS release".to_string()
+            } else {
+                format!("Released as {:?}, not through NCS", song.release_type)
+            },
+        });
+    }
+
+    let bgml_p = License::Bgml(BgmlVariant::Permissive);
+    if policy.licenses.contains(&bgml_p) {
+        let bgml = expression.satisfies(&License::is_bgml_permissive);
+        checks.push(RuleCheck {
+            rule: CreatorFriendlyRule::BgmlPermissiveLicense,
+            matched: bgml,
+            reason: if bgml { "BGML-P license".to_string() } else { format!("{} is not BGML-P", license) },
+        });
+    }
+
+    let listed: Vec<&License> = policy.licenses.iter().filter(|pattern| **pattern != bgml_p).collect();
+    if !listed.is_empty() {
+        let matched = expression.satisfies(&|l: &License| listed.iter().any(|pattern| license_matches(pattern, l)));
+        checks.push(RuleCheck {
+            rule: CreatorFriendlyRule::ListedLicense,
+            matched,
+            reason: if matched {
+                format!("License '{}' is allowed by the policy", expression)
+            } else {
+                format!("{} is not in the policy's license list", license)
+            },
+        });
+    }
+
+    if policy.release_types.iter().any(|release_type| *release_type != ReleaseType::NCS) {
+        let matched = song.release_type != ReleaseType::NCS && policy.allows_release_type(&song.release_type);
+        checks.push(RuleCheck {
+            rule: CreatorFriendlyRule::ListedReleaseType,
+            matched,
+            reason: if matched {
+                format!("{:?} release", song.release_type)
+            } else {
+                format!("Released as {:?}, which the policy doesn't list", song.release_type)
+            },
+        });
+    }
+
+    if !matches!(expression, LicenseExpression::License(_)) {
+        // An AND of friendly licenses is friendly even though no single rule covers it
+        let compound = expression.satisfies(&|l: &License| policy.allows_license(l));
+        checks.push(RuleCheck {
+            rule: CreatorFriendlyRule::CompoundLicense,
+            matched: compound,
+            reason: if compound {
+                format!("Every required part of '{}' is creator-friendly", expression)
+            } else {
+                format!("'{}' requires a license that is not creator-friendly", expression)
+            },
+        });
+    }
+
+    let (matched, mut failed): (Vec<RuleCheck>, Vec<RuleCheck>) = checks.into_iter().partition(|check| check.matched);
+
+    // Content ID is a veto rather than a way in, so it only shows up when it applies
+    let content_id_excluded = song.has_content_id && !policy.allow_content_id;
+    if content_id_excluded {
+        failed.push(RuleCheck {
+            rule: CreatorFriendlyRule::ContentIdRegistered,
+            matched: false,
+            reason: "Registered with Content ID, which the policy excludes".to_string(),
+        });
+    }
+
+    let creator_friendly = !matched.is_empty() && !content_id_excluded;
+    let summary = if creator_friendly {
+        Some(matched.iter().map(|check| check.reason.as_str()).collect::<Vec<_>>().join(", "))
+    } else {
+        None
+    };
+
+    CreatorFriendlyVerdict { creator_friendly, matched, failed, summary }
+}
+
+/// Explains why a song is or isn't creator-friendly
+/// Returns JSON: `{ creatorFriendly, matched, failed, summary }` where
+/// `matched`/`failed` hold `{ rule, matched, reason }` entries
+#[wasm_bindgen]
+pub fn explain_creator_friendly(song_json: &str) -> String {
+    let song: Song = match serde_json::from_str(song_json) {
+        Ok(s) => s,
+        Err(e) => return json!({ "error": format!("Invalid JSON: {}", e) }).to_string(),
+    };
+
+    json!(explain_creator_friendliness(&song)).to_string()
 }
 
 /// Checks if a song is creator-friendly
@@ -48,47 +215,332 @@ pub fn is_creator_friendly_song(song_json: &str) -> bool {
 /// 1. Has a commercial CC license (CC BY, CC BY-SA, CC0)
 /// 2. Is an NCS release
 /// 3. Has BGML-P license
+///
+/// License conditions apply to SPDX expressions such as "CC-BY-4.0 OR BGML-P".
+/// This is the `creator_friendly` flag of `explain_creator_friendliness`
 pub fn is_creator_friendly(song: &Song) -> bool {
-    // Check for commercial CC license
-    if is_commercial_cc_license(&song.license) {
-        return true;
-    }
-    
-    // Check for NCS release
-    if song.release_type == ReleaseType::NCS {
-        return true;
-    }
-    
-    // Check for BGML-P license
-    if is_bgml_p_license(&song.license) {
-        return true;
-    }
-    
-    false
+    explain_creator_friendliness(song).creator_friendly
+}
+
+/// Checks if a song is creator-friendly under `policy`
+pub fn is_creator_friendly_with(song: &Song, policy: &CreatorFriendlyPolicy) -> bool {
+    explain_creator_friendliness_with(song, policy).creator_friendly
 }
 
 /// Filters songs to only creator-friendly ones
+/// `policy_json` is a `CreatorFriendlyPolicy` as JSON; omit it for the default policy
 /// Returns JSON string of filtered songs
 #[wasm_bindgen]
-pub fn filter_creator_friendly(songs_json: &str) -> String {
+pub fn filter_creator_friendly(songs_json: &str, policy_json: Option<String>) -> String {
     let songs: Vec<Song> = match serde_json::from_str(songs_json) {
         Ok(s) => s,
-        Err(e) => return format!("{{\"error\": \"Invalid JSON: {}\"}}", e),
+        Err(e) => return json!({ "error": format!("Invalid JSON: {}", e) }).to_string(),
+    };
+
+    let policy = match policy_json.as_deref().map(CreatorFriendlyPolicy::from_json).transpose() {
+        Ok(policy) => policy.unwrap_or_default(),
+        Err(e) => return json!({ "error": e }).to_string(),
     };
     
     let filtered: Vec<&Song> = songs.iter()
-        .filter(|song| is_creator_friendly(song))
+        .filter(|song| is_creator_friendly_with(song, &policy))
         .collect();
     
     match serde_json::to_string(&filtered) {
         Ok(json) => json,
-        Err(e) => format!("{{\"error\": \"Serialization error: {}\"}}", e),
+        Err(e) => json!({ "error": format!("Serialization error: {}", e) }).to_string(),
+    }
+}
+
+/// Explains why a song object is or isn't creator-friendly
+/// `policy` is optional; leaving it out or passing `null` uses the default policy
+/// Returns `{ creatorFriendly, matched, failed, summary }`
+#[wasm_bindgen(js_name = explainCreatorFriendly, unchecked_return_type = "CreatorFriendlyVerdict")]
+pub fn explain_creator_friendly_js(
+    #[wasm_bindgen(unchecked_param_type = "Song")] song: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "CreatorFriendlyPolicy")] policy: Option<JsValue>,
+) -> Result<JsValue, JsError> {
+    let song: Song = from_js(song, "song")?;
+    let policy: Option<CreatorFriendlyPolicy> = from_js_optional(policy, "policy")?;
+    to_js(&explain_creator_friendliness_with(&song, &policy.unwrap_or_default()))
+}
+
+/// Checks if a song object is creator-friendly under `policy` (or the default policy)
+#[wasm_bindgen(js_name = isCreatorFriendly)]
+pub fn is_creator_friendly_js(
+    #[wasm_bindgen(unchecked_param_type = "Song")] song: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "CreatorFriendlyPolicy")] policy: Option<JsValue>,
+) -> Result<bool, JsError> {
+    let song: Song = from_js(song, "song")?;
+    let policy: Option<CreatorFriendlyPolicy> = from_js_optional(policy, "policy")?;
+    Ok(is_creator_friendly_with(&song, &policy.unwrap_or_default()))
+}
+
+/// Filters an array of song objects to the creator-friendly ones
+/// `policy` is optional; leaving it out or passing `null` uses the default policy
+#[wasm_bindgen(js_name = filterCreatorFriendly, unchecked_return_type = "Song[]")]
+pub fn filter_creator_friendly_js(
+    #[wasm_bindgen(unchecked_param_type = "Song[]")] songs: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "CreatorFriendlyPolicy")] policy: Option<JsValue>,
+) -> Result<JsValue, JsError> {
+    let songs: Vec<Song> = from_js(songs, "song list")?;
+    let policy: Option<CreatorFriendlyPolicy> = from_js_optional(policy, "policy")?;
+    let policy = policy.unwrap_or_default();
+    let filtered: Vec<&Song> = songs.iter().filter(|song| is_creator_friendly_with(song, &policy)).collect();
+    to_js(&filtered)
+}
+
+/// Concrete ways a creator might want to use a song
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum UsageScenario {
+    /// Background music in a monetized YouTube (or similar) video
+    MonetizedVideo,
+    /// Played during a monetized live stream (Twitch, YouTube Live)
+    LiveStream,
+    /// Soundtrack of a game that is sold or monetized
+    CommercialGame,
+    /// Music in a paid advertisement
+    PaidAd,
+    /// Releasing a remix or edit of the song for free
+    NonCommercialRemix,
+    /// Selling or monetizing a remix or edit of the song
+    CommercialRemix,
+}
+
+impl UsageScenario {
+    pub const ALL: [UsageScenario; 6] = [
+        UsageScenario::MonetizedVideo,
+        UsageScenario::LiveStream,
+        UsageScenario::CommercialGame,
+        UsageScenario::PaidAd,
+        UsageScenario::NonCommercialRemix,
+        UsageScenario::CommercialRemix,
+    ];
+
+    /// Whether the use earns money (conflicts with NonCommercial terms)
+    fn is_commercial(&self) -> bool {
+        !matches!(self, UsageScenario::NonCommercialRemix)
+    }
+
+    /// Whether the use adapts the work; syncing music to moving images counts
+    /// as an adaptation under CC 4.0, live playback does not
+    fn is_derivative(&self) -> bool {
+        !matches!(self, UsageScenario::LiveStream)
+    }
+
+    /// Whether the use ends up on a platform that runs Content ID matching
+    fn is_content_id_platform(&self) -> bool {
+        matches!(self, UsageScenario::MonetizedVideo | UsageScenario::LiveStream)
+    }
+}
+
+/// Outcome of a usage check, ordered from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum UsageVerdict {
+    Allowed,
+    /// Allowed if the listed obligations are met
+    Conditional,
+    Forbidden,
+}
+
+/// Something the creator must do to use the song
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum Obligation {
+    /// Credit the artist
+    Attribution,
+    /// Release the resulting work under the same license
+    ShareAlike,
+    /// Use the track unmodified
+    NoDerivatives,
+    /// Expect Content ID claims and clear them (whitelist or dispute)
+    ClearContentId,
+}
+
+/// Answer to "can I use this song for X?"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct UsageEvaluation {
+    pub scenario: UsageScenario,
+    pub verdict: UsageVerdict,
+    pub obligations: Vec<Obligation>,
+    pub reasons: Vec<String>,
+}
+
+impl UsageEvaluation {
+    fn new(scenario: UsageScenario, verdict: UsageVerdict, obligations: Vec<Obligation>, reason: impl Into<String>) -> Self {
+        UsageEvaluation { scenario, verdict, obligations, reasons: vec![reason.into()] }
+    }
+
+    /// Lower is better: verdict first, then fewer obligations
+    fn rank(&self) -> (UsageVerdict, usize) {
+        (self.verdict, self.obligations.len())
+    }
+
+    /// Both evaluations must hold at once (license AND)
+    /// A forbidden use carries no obligations, whatever the other side asks for
+    fn combine(mut self, other: UsageEvaluation) -> Self {
+        self.verdict = self.verdict.max(other.verdict);
+        for obligation in other.obligations {
+            if !self.obligations.contains(&obligation) {
+                self.obligations.push(obligation);
+            }
+        }
+        if self.verdict == UsageVerdict::Forbidden {
+            self.obligations.clear();
+        }
+        self.reasons.extend(other.reasons);
+        self
+    }
+
+    /// Either evaluation may be relied on (license OR, release grants)
+    fn best(self, other: UsageEvaluation) -> Self {
+        if other.rank() < self.rank() { other } else { self }
+    }
+}
+
+/// Evaluates one license for a scenario
+fn evaluate_license_usage(license: &License, scenario: UsageScenario) -> UsageEvaluation {
+    use UsageVerdict::*;
+    let evaluation = |verdict, obligations, reason: String| UsageEvaluation::new(scenario, verdict, obligations, reason);
+
+    match license {
+        License::CreativeCommons(cc) => {
+            if scenario.is_commercial() && cc.elements.is_non_commercial() {
+                return evaluation(Forbidden, vec![], format!("{} does not allow commercial use", license));
+            }
+            if scenario.is_derivative() && cc.elements.is_no_derivatives() {
+                return evaluation(Forbidden, vec![], format!("{} does not allow adaptations, and this use adapts the track", license));
+            }
+
+            let mut obligations = vec![Obligation::Attribution];
+            if cc.elements.is_share_alike() && scenario.is_derivative() {
+                obligations.push(Obligation::ShareAlike);
+            }
+            if cc.elements.is_no_derivatives() {
+                obligations.push(Obligation::NoDerivatives);
+            }
+            evaluation(Conditional, obligations, format!("{} allows this use with the listed conditions", license))
+        }
+        License::Cc0 { .. } | License::PublicDomainMark { .. } => {
+            evaluation(Allowed, vec![], format!("{} places the track in the public domain", license))
+        }
+        License::Bgml(BgmlVariant::Permissive) => match scenario {
+            UsageScenario::MonetizedVideo | UsageScenario::LiveStream | UsageScenario::CommercialGame => {
+                evaluation(Allowed, vec![], "BGML-P allows use in games and creator content".to_string())
+            }
+            UsageScenario::PaidAd | UsageScenario::NonCommercialRemix | UsageScenario::CommercialRemix => {
+                evaluation(Forbidden, vec![], "BGML-P only covers games and creator content; contact for other uses".to_string())
+            }
+        },
+        License::Bgml(_) => evaluation(Forbidden, vec![], format!("{} is not a permissive license; contact for licensing", license)),
+        License::AllRightsReserved => evaluation(Forbidden, vec![], "All rights reserved; contact for licensing".to_string()),
+        License::Unspecified => evaluation(Forbidden, vec![], "No license given, so all rights are reserved".to_string()),
+        License::Custom(text) => evaluation(Forbidden, vec![], format!("Unrecognised license '{}'; check its terms", text)),
+    }
+}
+
+/// Evaluates a license expression: OR takes the best choice, AND needs every part
+fn evaluate_expression_usage(expression: &LicenseExpression, scenario: UsageScenario) -> UsageEvaluation {
+    match expression {
+        LicenseExpression::License(license) => evaluate_license_usage(license, scenario),
+        LicenseExpression::With(base, _) => evaluate_expression_usage(base, scenario),
+        LicenseExpression::And(left, right) => {
+            evaluate_expression_usage(left, scenario).combine(evaluate_expression_usage(right, scenario))
+        }
+        LicenseExpression::Or(left, right) => {
+            evaluate_expression_usage(left, scenario).best(evaluate_expression_usage(right, scenario))
+        }
+    }
+}
+
+/// Usage granted by the label a song was released on, independent of its license
+fn evaluate_release_usage(release_type: &ReleaseType, scenario: UsageScenario) -> Option<UsageEvaluation> {
+    match (release_type, scenario) {
+        (ReleaseType::NCS, UsageScenario::MonetizedVideo | UsageScenario::LiveStream) => Some(UsageEvaluation::new(
+            scenario,
+            UsageVerdict::Conditional,
+            vec![Obligation::Attribution],
+            "NCS releases are free for creator videos and streams with credit",
+        )),
+        (ReleaseType::NCS, _) => Some(UsageEvaluation::new(
+            scenario,
+            UsageVerdict::Forbidden,
+            vec![],
+            "NCS releases need a separate license for games, ads and remixes",
+        )),
+        (ReleaseType::Monstercat, _) => Some(UsageEvaluation::new(
+            scenario,
+            UsageVerdict::Forbidden,
+            vec![],
+            "Monstercat releases need a Monstercat creator license",
+        )),
+        (ReleaseType::Independent, _) => None,
+    }
+}
+
+/// Answers "can I use this song for `scenario`?" from its license, release type
+/// and Content ID status. The most permissive grant wins, and Content ID never
+/// forbids a use but does add a clearance step on platforms that scan for it
+pub fn evaluate_usage(song: &Song, scenario: UsageScenario) -> UsageEvaluation {
+    let mut evaluation = evaluate_expression_usage(&song.license_expression(), scenario);
+
+    if let Some(release) = evaluate_release_usage(&song.release_type, scenario) {
+        evaluation = match evaluation.verdict {
+            // Keep the label's explanation next to a forbidding license
+            UsageVerdict::Forbidden if release.verdict == UsageVerdict::Forbidden => {
+                let mut evaluation = evaluation;
+                evaluation.reasons.extend(release.reasons);
+                evaluation
+            }
+            _ => evaluation.best(release),
+        };
     }
+
+    if song.has_content_id && scenario.is_content_id_platform() && evaluation.verdict != UsageVerdict::Forbidden {
+        evaluation.verdict = UsageVerdict::Conditional;
+        evaluation.obligations.push(Obligation::ClearContentId);
+        evaluation.reasons.push("Track is registered with Content ID, so uploads may be claimed even though use is permitted".to_string());
+    }
+
+    evaluation
+}
+
+/// Evaluates a song for a usage scenario
+/// `scenario` is one of: monetizedVideo, liveStream, commercialGame, paidAd,
+/// nonCommercialRemix, commercialRemix
+/// Returns JSON: `{ scenario, verdict, obligations, reasons }`
+#[wasm_bindgen]
+pub fn evaluate_song_usage(song_json: &str, scenario: &str) -> String {
+    let song: Song = match serde_json::from_str(song_json) {
+        Ok(s) => s,
+        Err(e) => return json!({ "error": format!("Invalid JSON: {}", e) }).to_string(),
+    };
+    let scenario: UsageScenario = match serde_json::from_value(json!(scenario)) {
+        Ok(s) => s,
+        Err(_) => return json!({ "error": format!("Unknown usage scenario: {}", scenario) }).to_string(),
+    };
+
+    json!(evaluate_usage(&song, scenario)).to_string()
+}
+
+/// Evaluates a song object for a usage scenario such as `"monetizedVideo"`
+/// Returns `{ scenario, verdict, obligations, reasons }`
+#[wasm_bindgen(js_name = evaluateSongUsage, unchecked_return_type = "UsageEvaluation")]
+pub fn evaluate_song_usage_js(
+    #[wasm_bindgen(unchecked_param_type = "Song")] song: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "UsageScenario")] scenario: JsValue,
+) -> Result<JsValue, JsError> {
+    let song: Song = from_js(song, "song")?;
+    let scenario: UsageScenario = from_js(scenario, "usage scenario")?;
+    to_js(&evaluate_usage(&song, scenario))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing;
 
     #[test]
     fn test_is_commercial_cc_license() {
@@ -100,7 +552,12 @@ mod tests {
         assert!(is_commercial_cc_license("CC0 1.0"));
         assert!(is_commercial_cc_license("CC0"));
         
+        assert!(is_commercial_cc_license("CC-BY-SA-3.0-DE")); // jurisdiction port
+        assert!(!is_commercial_cc_license("Public Domain Mark 1.0")); // Marks a work, doesn't license it
+
         assert!(!is_commercial_cc_license("CC BY-NC 4.0")); // Non-commercial
+        assert!(!is_commercial_cc_license("CC BY-ND 4.0")); // No derivatives
+        assert!(!is_commercial_cc_license("CC BY 9.0")); // Not a real version
         assert!(!is_commercial_cc_license("All Rights Reserved"));
         assert!(!is_commercial_cc_license(""));
     }
@@ -119,51 +576,280 @@ mod tests {
     #[test]
     fn test_is_creator_friendly() {
         // Test CC license
-        let song_cc = Song {
-            id: "1".to_string(),
-            title: "Test".to_string(),
-            album_name: "Album".to_string(),
-            release_type: ReleaseType::Independent,
-            has_content_id: false,
-            streaming_link: "https://example.com".to_string(),
-            license: "CC BY 4.0".to_string(),
-        };
+        let song_cc = testing::song("1")
+            .release_type(ReleaseType::Independent)
+            .license("CC BY 4.0")
+            .build();
         assert!(is_creator_friendly(&song_cc));
 
         // Test NCS release
-        let song_ncs = Song {
-            id: "2".to_string(),
-            title: "Test".to_string(),
-            album_name: "Album".to_string(),
-            release_type: ReleaseType::NCS,
-            has_content_id: false,
-            streaming_link: "https://example.com".to_string(),
-            license: "".to_string(),
-        };
+        let song_ncs = testing::song("2").build();
         assert!(is_creator_friendly(&song_ncs));
 
         // Test BGML-P license
-        let song_bgml = Song {
-            id: "3".to_string(),
-            title: "Test".to_string(),
-            album_name: "Album".to_string(),
-            release_type: ReleaseType::Independent,
-            has_content_id: false,
-            streaming_link: "https://example.com".to_string(),
-            license: "BGML-P".to_string(),
-        };
+        let song_bgml = testing::song("3")
+            .release_type(ReleaseType::Independent)
+            .license("BGML-P")
+            .build();
         assert!(is_creator_friendly(&song_bgml));
 
         // Test non-creator-friendly
-        let song_not_friendly = Song {
-            id: "4".to_string(),
-            title: "Test".to_string(),
-            album_name: "Album".to_string(),
-            release_type: ReleaseType::Independent,
-            has_content_id: true,
-            streaming_link: "https://example.com".to_string(),
-            license: "All Rights Reserved".to_string(),
-        };
+        let song_not_friendly = testing::song("4")
+            .release_type(ReleaseType::Independent)
+            .content_id(true)
+            .license("All Rights Reserved")
+            .build();
         assert!(!is_creator_friendly(&song_not_friendly));
     }
+
+    #[test]
+    fn test_compound_license_checks() {
+        assert!(is_commercial_cc_license("CC-BY-NC-4.0 OR CC-BY-4.0"));
+        assert!(!is_commercial_cc_license("CC-BY-4.0 AND BGML-P"));
+        assert!(is_bgml_p_license("CC BY-NC 4.0 OR BGML-P"));
+        assert!(!is_bgml_p_license("CC BY 4.0"));
+    }
+
+    #[test]
+    fn test_is_creator_friendly_compound_licenses() {
+        let song = |license: &str| {
+            testing::song("1")
+                .release_type(ReleaseType::Independent)
+                .license(license)
+                .build()
+        };
+
+        // Dual-licensed: either choice is friendly
+        assert!(is_creator_friendly(&song("CC-BY-4.0 OR BGML-P")));
+        assert!(is_creator_friendly(&song("CC BY-NC 4.0 OR BGML-P")));
+        // Both parts must be honoured, and both are friendly
+        assert!(is_creator_friendly(&song("CC-BY-4.0 AND BGML-P")));
+        // One restrictive part poisons an AND
+        assert!(!is_creator_friendly(&song("CC-BY-4.0 AND CC-BY-NC-4.0")));
+        assert!(!is_creator_friendly(&song("CC-BY-NC-4.0 OR CC-BY-ND-4.0")));
+        assert!(is_creator_friendly(&song("(CC-BY-NC-4.0 OR CC0-1.0) AND BGML-P")));
+    }
+
+    #[test]
+    fn test_public_domain_keeps_baseline_verdicts() {
+        // None of these matched the original regex checks, and the default policy must agree
+        for license in ["Public Domain", "public domain", "Public Domain 1.0", "Public Domain Mark", "Public Domain Mark 1.0", "PDM"] {
+            assert!(!is_commercial_cc_license(license), "{}", license);
+            let song = testing::song("1").release_type(ReleaseType::Independent).license(license).build();
+            assert!(!is_creator_friendly(&song), "{}", license);
+        }
+    }
+
+    #[test]
+    fn test_filter_creator_friendly_preserves_all_fields() {
+        let friendly = r#"{"id":"song-001","title":"Digital Dreams","albumName":"Synthwave Chronicles","releaseType":"Independent","hasContentId":false,"streamingLink":"https://push.fm/digital-dreams","license":"CC BY 4.0","releaseYear":2023,"albumArtwork":"https://example.com/art.jpg","isrc":"QZ-ABC-23-00001"}"#;
+        let restricted = r#"{"id":"song-002","title":"Neon Nights","albumName":"Synthwave Chronicles","releaseType":"Independent","hasContentId":true,"streamingLink":"https://push.fm/neon-nights","license":"All Rights Reserved","releaseYear":2023}"#;
+
+        let result = filter_creator_friendly(&format!("[{},{}]", friendly, restricted), None);
+        assert_eq!(result, format!("[{}]", friendly));
+    }
+
+    fn usage_song(release_type: ReleaseType, license: &str, has_content_id: bool) -> Song {
+        testing::song("1")
+            .release_type(release_type)
+            .content_id(has_content_id)
+            .license(license)
+            .build()
+    }
+
+    #[test]
+    fn test_evaluate_usage_cc_licenses() {
+        let by = usage_song(ReleaseType::Independent, "CC BY 4.0", false);
+        let result = evaluate_usage(&by, UsageScenario::MonetizedVideo);
+        assert_eq!(result.verdict, UsageVerdict::Conditional);
+        assert_eq!(result.obligations, vec![Obligation::Attribution]);
+
+        let by_sa = usage_song(ReleaseType::Independent, "CC BY-SA 4.0", false);
+        let result = evaluate_usage(&by_sa, UsageScenario::NonCommercialRemix);
+        assert_eq!(result.obligations, vec![Obligation::Attribution, Obligation::ShareAlike]);
+
+        let by_nc = usage_song(ReleaseType::Independent, "CC BY-NC 4.0", false);
+        assert_eq!(evaluate_usage(&by_nc, UsageScenario::CommercialGame).verdict, UsageVerdict::Forbidden);
+        assert_eq!(evaluate_usage(&by_nc, UsageScenario::NonCommercialRemix).verdict, UsageVerdict::Conditional);
+        // Selling the remix is commercial use
+        assert_eq!(evaluate_usage(&by_nc, UsageScenario::CommercialRemix).verdict, UsageVerdict::Forbidden);
+
+        let by_nd = usage_song(ReleaseType::Independent, "CC BY-ND 4.0", false);
+        assert_eq!(evaluate_usage(&by_nd, UsageScenario::MonetizedVideo).verdict, UsageVerdict::Forbidden);
+        let result = evaluate_usage(&by_nd, UsageScenario::LiveStream);
+        assert_eq!(result.verdict, UsageVerdict::Conditional);
+        assert_eq!(result.obligations, vec![Obligation::Attribution, Obligation::NoDerivatives]);
+
+        let cc0 = usage_song(ReleaseType::Independent, "CC0", false);
+        for scenario in UsageScenario::ALL {
+            let result = evaluate_usage(&cc0, scenario);
+            assert_eq!(result.verdict, UsageVerdict::Allowed);
+            assert!(result.obligations.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_evaluate_usage_release_types() {
+        let ncs = usage_song(ReleaseType::NCS, "", false);
+        assert_eq!(evaluate_usage(&ncs, UsageScenario::LiveStream).verdict, UsageVerdict::Conditional);
+        assert_eq!(evaluate_usage(&ncs, UsageScenario::PaidAd).verdict, UsageVerdict::Forbidden);
+
+        // A CC license can grant more than the label does
+        let ncs_cc0 = usage_song(ReleaseType::NCS, "CC0", false);
+        assert_eq!(evaluate_usage(&ncs_cc0, UsageScenario::PaidAd).verdict, UsageVerdict::Allowed);
+
+        let monstercat = usage_song(ReleaseType::Monstercat, "All Rights Reserved", false);
+        let result = evaluate_usage(&monstercat, UsageScenario::MonetizedVideo);
+        assert_eq!(result.verdict, UsageVerdict::Forbidden);
+        assert_eq!(result.reasons.len(), 2);
+    }
+
+    #[test]
+    fn test_evaluate_usage_bgml_and_compound() {
+        let bgml = usage_song(ReleaseType::Independent, "BGML-P", false);
+        assert_eq!(evaluate_usage(&bgml, UsageScenario::CommercialGame).verdict, UsageVerdict::Allowed);
+        assert_eq!(evaluate_usage(&bgml, UsageScenario::PaidAd).verdict, UsageVerdict::Forbidden);
+
+        // OR picks the best option for each scenario
+        let dual = usage_song(ReleaseType::Independent, "CC-BY-4.0 OR BGML-P", false);
+        assert_eq!(evaluate_usage(&dual, UsageScenario::CommercialGame).verdict, UsageVerdict::Allowed);
+        assert_eq!(evaluate_usage(&dual, UsageScenario::PaidAd).verdict, UsageVerdict::Conditional);
+
+        // AND needs every part, collecting all obligations
+        let both = usage_song(ReleaseType::Independent, "CC-BY-SA-4.0 AND CC-BY-NC-4.0", false);
+        let result = evaluate_usage(&both, UsageScenario::NonCommercialRemix);
+        assert_eq!(result.verdict, UsageVerdict::Conditional);
+        assert_eq!(result.obligations, vec![Obligation::Attribution, Obligation::ShareAlike]);
+        assert_eq!(evaluate_usage(&both, UsageScenario::MonetizedVideo).verdict, UsageVerdict::Forbidden);
+
+        // A forbidden part drops the other part's obligations
+        let mixed = usage_song(ReleaseType::Independent, "CC BY 4.0 AND CC BY-NC 4.0", false);
+        let result = evaluate_usage(&mixed, UsageScenario::MonetizedVideo);
+        assert_eq!(result.verdict, UsageVerdict::Forbidden);
+        assert!(result.obligations.is_empty());
+        let result = evaluate_usage(&usage_song(ReleaseType::Independent, "CC BY-NC 4.0 AND CC BY 4.0", false), UsageScenario::MonetizedVideo);
+        assert!(result.obligations.is_empty());
+    }
+
+    #[test]
+    fn test_evaluate_usage_content_id() {
+        let song = usage_song(ReleaseType::Independent, "CC0", true);
+        let result = evaluate_usage(&song, UsageScenario::MonetizedVideo);
+        assert_eq!(result.verdict, UsageVerdict::Conditional);
+        assert_eq!(result.obligations, vec![Obligation::ClearContentId]);
+
+        // Games aren't scanned by Content ID
+        assert_eq!(evaluate_usage(&song, UsageScenario::CommercialGame).verdict, UsageVerdict::Allowed);
+    }
+
+    #[test]
+    fn test_evaluate_song_usage_json() {
+        let song = testing::song("1").release_type(ReleaseType::Independent).license("CC BY-SA 4.0").json().to_string();
+        let parsed: serde_json::Value = serde_json::from_str(&evaluate_song_usage(&song, "monetizedVideo")).unwrap();
+        assert_eq!(parsed["scenario"], "monetizedVideo");
+        assert_eq!(parsed["verdict"], "conditional");
+        assert_eq!(parsed["obligations"], json!(["attribution", "shareAlike"]));
+
+        let parsed: serde_json::Value = serde_json::from_str(&evaluate_song_usage(&song, "wedding")).unwrap();
+        assert!(parsed["error"].as_str().unwrap().contains("Unknown usage scenario"));
+    }
+
+    #[test]
+    fn test_explain_creator_friendliness() {
+        let verdict = explain_creator_friendliness(&usage_song(ReleaseType::NCS, "cc by 4.0", false));
+        assert!(verdict.creator_friendly);
+        let matched: Vec<CreatorFriendlyRule> = verdict.matched.iter().map(|c| c.rule).collect();
+        assert_eq!(matched, vec![CreatorFriendlyRule::CommercialCcLicense, CreatorFriendlyRule::NcsRelease]);
+        assert_eq!(verdict.failed.len(), 1);
+        assert_eq!(verdict.failed[0].rule, CreatorFriendlyRule::BgmlPermissiveLicense);
+        assert_eq!(verdict.summary.as_deref(), Some("Commercial CC license (CC BY 4.0), NCS release"));
+
+        let verdict = explain_creator_friendliness(&usage_song(ReleaseType::Independent, "", false));
+        assert!(!verdict.creator_friendly);
+        assert!(verdict.matched.is_empty());
+        assert_eq!(verdict.summary, None);
+        assert_eq!(verdict.failed[0].reason, "No license is not a commercial Creative Commons license");
+        assert_eq!(verdict.failed[1].reason, "Released as Independent, not through NCS");
+    }
+
+    #[test]
+    fn test_explain_compound_license() {
+        // Neither single-license rule covers an AND, the compound rule does
+        let verdict = explain_creator_friendliness(&usage_song(ReleaseType::Independent, "CC-BY-4.0 AND BGML-P", false));
+        assert!(verdict.creator_friendly);
+        assert_eq!(verdict.matched.len(), 1);
+        assert_eq!(verdict.matched[0].rule, CreatorFriendlyRule::CompoundLicense);
+
+        // Single licenses never report the compound rule
+        let verdict = explain_creator_friendliness(&usage_song(ReleaseType::Independent, "BGML-P", false));
+        assert!(verdict.failed.iter().all(|c| c.rule != CreatorFriendlyRule::CompoundLicense));
+    }
+
+    #[test]
+    fn test_explain_creator_friendly_json() {
+        let song = testing::song("1").release_type(ReleaseType::Independent).license("BGML-P").json().to_string();
+        let parsed: serde_json::Value = serde_json::from_str(&explain_creator_friendly(&song)).unwrap();
+        assert_eq!(parsed["creatorFriendly"], true);
+        assert_eq!(parsed["matched"][0]["rule"], "bgmlPermissiveLicense");
+        assert_eq!(parsed["failed"][0]["rule"], "commercialCcLicense");
+        assert_eq!(parsed["summary"], "BGML-P license");
+    }
+
+    #[test]
+    fn test_policy_content_id_veto() {
+        let policy = CreatorFriendlyPolicy { allow_content_id: false, ..Default::default() };
+
+        let verdict = explain_creator_friendliness_with(&usage_song(ReleaseType::Independent, "CC BY 4.0", true), &policy);
+        assert!(!verdict.creator_friendly);
+        assert_eq!(verdict.matched[0].rule, CreatorFriendlyRule::CommercialCcLicense);
+        assert_eq!(verdict.failed.last().unwrap().rule, CreatorFriendlyRule::ContentIdRegistered);
+        assert_eq!(verdict.summary, None);
+
+        let verdict = explain_creator_friendliness_with(&usage_song(ReleaseType::Independent, "CC BY 4.0", false), &policy);
+        assert!(verdict.creator_friendly);
+        assert!(verdict.failed.iter().all(|c| c.rule != CreatorFriendlyRule::ContentIdRegistered));
+
+        // The default policy keeps ignoring Content ID
+        assert!(is_creator_friendly(&usage_song(ReleaseType::Independent, "CC BY 4.0", true)));
+    }
+
+    #[test]
+    fn test_policy_listed_licenses_and_release_types() {
+        let policy = CreatorFriendlyPolicy::from_json(r#"{"commercialCc": false, "licenses": ["CC BY-NC"], "releaseTypes": ["Monstercat"]}"#).unwrap();
+
+        let verdict = explain_creator_friendliness_with(&usage_song(ReleaseType::Independent, "CC BY-NC 3.0", false), &policy);
+        assert!(verdict.creator_friendly);
+        assert_eq!(verdict.matched[0].rule, CreatorFriendlyRule::ListedLicense);
+        let failed: Vec<CreatorFriendlyRule> = verdict.failed.iter().map(|c| c.rule).collect();
+        assert_eq!(failed, vec![CreatorFriendlyRule::ListedReleaseType]);
+
+        assert!(!is_creator_friendly_with(&usage_song(ReleaseType::NCS, "CC BY 4.0", false), &policy));
+        assert!(!is_creator_friendly_with(&usage_song(ReleaseType::Independent, "BGML-P", false), &policy));
+
+        let verdict = explain_creator_friendliness_with(&usage_song(ReleaseType::Monstercat, "", false), &policy);
+        assert_eq!(verdict.summary.as_deref(), Some("Monstercat release"));
+
+        // Compound licenses are judged against the policy's licenses
+        assert!(is_creator_friendly_with(&usage_song(ReleaseType::Independent, "CC-BY-NC-4.0 AND CC-BY-NC-SA-4.0 OR CC-BY-NC-3.0", false), &policy));
+        assert!(!is_creator_friendly_with(&usage_song(ReleaseType::Independent, "CC-BY-NC-4.0 AND CC-BY-4.0", false), &policy));
+    }
+
+    #[test]
+    fn test_filter_creator_friendly_with_policy() {
+        let songs = json!([
+            testing::song("1").content_id(true).json(),
+            testing::song("2").release_type(ReleaseType::Independent).license("CC BY 4.0").json(),
+        ])
+        .to_string();
+
+        let all: serde_json::Value = serde_json::from_str(&filter_creator_friendly(&songs, None)).unwrap();
+        assert_eq!(all.as_array().unwrap().len(), 2);
+
+        let strict: serde_json::Value = serde_json::from_str(&filter_creator_friendly(&songs, Some(r#"{"allowContentId": false}"#.to_string()))).unwrap();
+        assert_eq!(strict.as_array().unwrap().len(), 1);
+        assert_eq!(strict[0]["id"], "2");
+
+        let error: serde_json::Value = serde_json::from_str(&filter_creator_friendly(&songs, Some("{\"bogus\": 1}".to_string()))).unwrap();
+        assert!(error["error"].as_str().unwrap().starts_with("Invalid policy JSON"));
+    }
 }
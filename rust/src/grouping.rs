@@ -1,51 +1,437 @@
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use serde_json::{json, Map, Value};
+use crate::filters::is_creator_friendly_with;
+use crate::js::{from_js, from_js_optional, to_js};
+use crate::policy::CreatorFriendlyPolicy;
+use crate::sorting::{sort_albums, Collation, CollationKey};
+use crate::text::fold_case;
 use crate::validation::Song;
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+/// Kind of release an album is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+pub enum AlbumType {
+    Single,
+    EP,
+    LP,
+    Compilation,
+}
+
+/// Optional album details, shared by declared and grouped albums
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct AlbumMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album_type: Option<AlbumType>,
+    /// URL of the cover art
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artwork: Option<String>,
+    /// ISO 8601 calendar date: "2023", "2023-06" or "2023-06-15"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Universal Product Code: 12 digits (UPC-A) or 13 (EAN-13)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upc: Option<String>,
+}
+
+/// An album declared in `music.json`
+/// Songs join it by setting their `albumId` to its `id`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct AlbumInfo {
+    /// Stable identifier, e.g. "album-001"
+    pub id: String,
+    pub name: String,
+    #[serde(flatten)]
+    pub metadata: AlbumMetadata,
+    /// Unmodelled keys, preserved verbatim
+    #[serde(flatten)]
+    #[tsify(type = "Record<string, unknown>")]
+    pub extra: Map<String, Value>,
+}
 
 /// Album structure for grouping songs
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Declared albums carry their `id` and metadata; albums only known from
+/// songs' `albumName` have just a name
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
 pub struct Album {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     pub name: String,
+    #[serde(flatten)]
+    pub metadata: AlbumMetadata,
     pub songs: Vec<Song>,
 }
 
-/// Groups songs by album name
-/// Returns JSON string of grouped albums
-#[wasm_bindgen]
-pub fn group_by_album(songs_json: &str) -> String {
-    let songs: Vec<Song> = match serde_json::from_str(songs_json) {
-        Ok(s) => s,
-        Err(e) => return format!("{{\"error\": \"Invalid JSON: {}\"}}", e),
-    };
-    
+impl Album {
+    /// An undeclared album, known only by name
+    pub fn named(name: impl Into<String>, songs: Vec<Song>) -> Self {
+        Album { id: None, name: name.into(), metadata: AlbumMetadata::default(), songs }
+    }
+
+    /// A declared album with its songs
+    pub fn declared(info: &AlbumInfo, songs: Vec<Song>) -> Self {
+        Album { id: Some(info.id.clone()), name: info.name.clone(), metadata: info.metadata.clone(), songs }
+    }
+}
+
+/// Groups songs into albums, sorted by album name (see `Collation`)
+/// Songs are ordered by disc and track; the rest keep their input order
+pub fn group_songs(songs: impl IntoIterator<Item = Song>) -> Vec<Album> {
+    group_songs_with(songs, &[])
+}
+
+/// Key under which album names count as the same album: case, spacing and
+/// compatibility forms are ignored, so "Synthwave Chronicles",
+/// "synthwave chronicles" and "Synthwave  Chronicles " are one album
+/// Accents and look-alike letters still tell names apart; the validator warns
+/// about those instead
+pub fn album_identity(name: &str) -> String {
+    fold_case(name)
+}
+
+/// What a song is grouped by: a declared album (by index) or the identity of
+/// its album name
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum AlbumKey {
+    Declared(usize),
+    Named(String),
+}
+
+/// Works out which album each song belongs to
+/// A song joins the album its `albumId` names, or else the declared album
+/// with its `albumName`; the rest are grouped by `albumName`. Names are
+/// matched by `album_identity`
+pub(crate) struct AlbumResolver<'a> {
+    declared: &'a [AlbumInfo],
+    by_id: HashMap<&'a str, usize>,
+    by_name: HashMap<String, usize>,
+    /// Display name of each undeclared album: the spelling most of its songs
+    /// use, or the first one seen on a tie
+    spellings: HashMap<String, String>,
+}
+
+impl<'a> AlbumResolver<'a> {
+    pub(crate) fn new(declared: &'a [AlbumInfo], songs: &[Song]) -> Self {
+        // First declaration wins when IDs or names repeat
+        let mut by_id = HashMap::new();
+        let mut by_name = HashMap::new();
+        for (index, album) in declared.iter().enumerate() {
+            by_id.entry(album.id.as_str()).or_insert(index);
+            by_name.entry(album_identity(&album.name)).or_insert(index);
+        }
+        let mut resolver = AlbumResolver { declared, by_id, by_name, spellings: HashMap::new() };
+
+        // (count, first seen) per spelling, per undeclared album
+        let mut counts: HashMap<String, HashMap<&str, (usize, usize)>> = HashMap::new();
+        for (index, song) in songs.iter().enumerate() {
+            if let AlbumKey::Named(identity) = resolver.key(song) {
+                counts.entry(identity).or_default().entry(song.album_name.as_str()).or_insert((0, index)).0 += 1;
+            }
+        }
+        resolver.spellings = counts
+            .into_iter()
+            .filter_map(|(identity, spellings)| {
+                let (spelling, _) = spellings.into_iter().max_by_key(|&(_, (count, first))| (count, std::cmp::Reverse(first)))?;
+                Some((identity, spelling.to_string()))
+            })
+            .collect();
+        resolver
+    }
+
+    pub(crate) fn key(&self, song: &Song) -> AlbumKey {
+        let identity = album_identity(&song.album_name);
+        let declared_index = song
+            .album_id
+            .as_deref()
+            .and_then(|id| self.by_id.get(id))
+            .or_else(|| self.by_name.get(&identity));
+        match declared_index {
+            Some(&index) => AlbumKey::Declared(index),
+            None => AlbumKey::Named(identity),
+        }
+    }
+
+    pub(crate) fn name<'s>(&'s self, key: &'s AlbumKey) -> &'s str {
+        match key {
+            AlbumKey::Declared(index) => &self.declared[*index].name,
+            AlbumKey::Named(identity) => self.spellings.get(identity).map_or(identity, String::as_str),
+        }
+    }
+
+    fn album(&self, key: AlbumKey, songs: Vec<Song>) -> Album {
+        let name = self.name(&key).to_string();
+        match key {
+            AlbumKey::Declared(index) => Album::declared(&self.declared[index], songs),
+            AlbumKey::Named(_) => Album::named(name, songs),
+        }
+    }
+}
+
+/// Order of songs within an album: numbered songs by disc and track, then
+/// unnumbered ones. Ties (and unnumbered songs) compare equal, so a stable sort
+/// keeps them in input order
+pub fn track_order(a: &Song, b: &Song) -> Ordering {
+    match (a.track_position(), b.track_position()) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Groups songs into albums, enriching them with the `declared` album metadata
+/// See `AlbumResolver` for how songs are matched to albums. Declared albums
+/// without songs are left out. Albums are sorted by name, naturally and
+/// ignoring case and accents, then by ID; songs by `track_order`
+pub fn group_songs_with(songs: impl IntoIterator<Item = Song>, declared: &[AlbumInfo]) -> Vec<Album> {
+    let songs: Vec<Song> = songs.into_iter().collect();
+    let resolver = AlbumResolver::new(declared, &songs);
+
     // Use HashMap for efficient grouping
-    let mut album_map: HashMap<String, Vec<Song>> = HashMap::new();
+    let mut album_map: HashMap<AlbumKey, Vec<Song>> = HashMap::new();
     
     for song in songs {
-        album_map
-            .entry(song.album_name.clone())
-            .or_insert_with(Vec::new)
-            .push(song);
+        album_map.entry(resolver.key(&song)).or_default().push(song);
     }
     
     // Convert to Album structs and sort by album name
     let mut albums: Vec<Album> = album_map
         .into_iter()
-        .map(|(name, songs)| Album { name, songs })
+        .map(|(key, mut songs)| {
+            songs.sort_by(track_order);
+            resolver.album(key, songs)
+        })
         .collect();
     
-    albums.sort_by(|a, b| a.name.cmp(&b.name));
+    sort_albums(&mut albums, &Collation::default());
+    albums
+}
+
+/// Song attribute that `group_songs_by` can group on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupKey {
+    /// Declared album, or album name for undeclared ones
+    Album,
+    ReleaseYear,
+    /// Release year rounded down to its decade
+    Decade,
+    ReleaseType,
+    /// Broad kind of license, e.g. Creative Commons or BGML
+    LicenseFamily,
+    ContentId,
+    CreatorFriendly,
+}
+
+/// Songs sharing one value of a group key
+/// Groups nest one level per key; only the innermost level lists its songs
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+pub struct SongGroup {
+    pub key: GroupKey,
+    /// The shared value: album ID (or name when undeclared), year, first year
+    /// of the decade, release type, license family or a flag; `null` for
+    /// songs without a release year
+    #[tsify(type = "string | number | boolean | null")]
+    pub value: Value,
+    /// Display text, e.g. "2020s" or "No Content ID"
+    pub label: String,
+    /// Number of songs in the group, subgroups included
+    pub count: usize,
+    /// Subgroups by the next key
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<SongGroup>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub songs: Vec<Song>,
+}
+
+/// Order of groups for one key; songs with equal `GroupSort`s share a group
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum GroupSort {
+    Number(u32),
+    /// Name, then album ID so declared albums sharing a name stay apart
+    Text(CollationKey, Option<String>),
+    /// No value, e.g. a song without a release year; sorts last
+    Missing,
+}
+
+/// A song's group for `key`: its sort position, value and label
+pub(crate) fn group_value(key: GroupKey, song: &Song, resolver: &AlbumResolver, policy: &CreatorFriendlyPolicy) -> (GroupSort, Value, String) {
+    let flag = |set: bool, yes: &str, no: &str| {
+        let label = if set { yes } else { no };
+        (GroupSort::Number(u32::from(!set)), Value::Bool(set), label.to_string())
+    };
+    match (key, song.release_year) {
+        (GroupKey::Album, _) => {
+            let album = resolver.key(song);
+            let name = resolver.name(&album).to_string();
+            match album {
+                AlbumKey::Declared(index) => {
+                    let id = resolver.declared[index].id.clone();
+                    (GroupSort::Text(Collation::default().key(&name), Some(id.clone())), Value::String(id), name)
+                }
+                AlbumKey::Named(_) => (GroupSort::Text(Collation::default().key(&name), None), Value::String(name.clone()), name),
+            }
+        }
+        (GroupKey::ReleaseYear, Some(year)) => (GroupSort::Number(year), json!(year), year.to_string()),
+        (GroupKey::Decade, Some(year)) => {
+            let decade = year / 10 * 10;
+            (GroupSort::Number(decade), json!(decade), format!("{}s", decade))
+        }
+        (GroupKey::ReleaseYear | GroupKey::Decade, None) => (GroupSort::Missing, Value::Null, "Unknown year".to_string()),
+        (GroupKey::ReleaseType, _) => {
+            let release_type = format!("{:?}", song.release_type);
+            (GroupSort::Text(Collation::default().key(&release_type), None), Value::String(release_type.clone()), release_type)
+        }
+        (GroupKey::LicenseFamily, _) => {
+            let family = song.license_expression().family();
+            (GroupSort::Number(family as u32), json!(family.as_str()), family.as_str().to_string())
+        }
+        (GroupKey::ContentId, _) => flag(song.has_content_id, "Content ID", "No Content ID"),
+        (GroupKey::CreatorFriendly, _) => flag(is_creator_friendly_with(song, policy), "Creator-friendly", "Not creator-friendly"),
+    }
+}
+
+/// `in_album` is set below an album group, whose songs then list in track order
+fn group_level(songs: Vec<Song>, keys: &[GroupKey], in_album: bool, resolver: &AlbumResolver, policy: &CreatorFriendlyPolicy) -> Vec<SongGroup> {
+    let Some((&key, rest)) = keys.split_first() else {
+        return Vec::new();
+    };
+    let in_album = in_album || key == GroupKey::Album;
+
+    let mut groups: BTreeMap<GroupSort, (Value, String, Vec<Song>)> = BTreeMap::new();
+    for song in songs {
+        let (sort, value, label) = group_value(key, &song, resolver, policy);
+        groups.entry(sort).or_insert_with(|| (value, label, Vec::new())).2.push(song);
+    }
+
+    groups
+        .into_values()
+        .map(|(value, label, mut songs)| {
+            let count = songs.len();
+            let (groups, songs) = if rest.is_empty() {
+                if in_album {
+                    songs.sort_by(track_order);
+                }
+                (Vec::new(), songs)
+            } else {
+                (group_level(songs, rest, in_album, resolver, policy), Vec::new())
+            };
+            SongGroup { key, value, label, count, groups, songs }
+        })
+        .collect()
+}
+
+/// Groups songs by one or more keys, nesting a level of groups per key
+/// Groups are ordered by value: albums by name, years ascending, release types
+/// alphabetically, flags set first, and songs without a year last. Songs within
+/// an album group are listed by `track_order`, like `group_songs_with`; others
+/// keep their input order. `declared` albums and `policy` are used by the album
+/// and creator-friendly keys
+pub fn group_songs_by(
+    songs: impl IntoIterator<Item = Song>,
+    keys: &[GroupKey],
+    declared: &[AlbumInfo],
+    policy: &CreatorFriendlyPolicy,
+) -> Result<Vec<SongGroup>, String> {
+    if keys.is_empty() {
+        return Err("At least one group key is required".to_string());
+    }
+    let songs: Vec<Song> = songs.into_iter().collect();
+    let resolver = AlbumResolver::new(declared, &songs);
+    Ok(group_level(songs, keys, false, &resolver, policy))
+}
+
+/// Parses optional album declarations (`AlbumInfo[]`); an empty string means none
+fn parse_declared_albums(albums_json: Option<&str>) -> Result<Vec<AlbumInfo>, String> {
+    match albums_json.map(str::trim) {
+        None | Some("") => Ok(Vec::new()),
+        Some(albums_json) => serde_json::from_str(albums_json).map_err(|e| format!("Invalid albums JSON: {}", e)),
+    }
+}
+
+/// Groups songs by album name
+/// `albums_json` optionally declares album metadata (`AlbumInfo[]`) to attach
+/// Returns JSON string of grouped albums
+#[wasm_bindgen]
+pub fn group_by_album(songs_json: &str, albums_json: Option<String>) -> String {
+    let songs: Vec<Song> = match serde_json::from_str(songs_json) {
+        Ok(s) => s,
+        Err(e) => return json!({ "error": format!("Invalid JSON: {}", e) }).to_string(),
+    };
+
+    let declared = match parse_declared_albums(albums_json.as_deref()) {
+        Ok(declared) => declared,
+        Err(e) => return json!({ "error": e }).to_string(),
+    };
+    
+    let albums = group_songs_with(songs, &declared);
     
     match serde_json::to_string(&albums) {
         Ok(json) => json,
-        Err(e) => format!("{{\"error\": \"Serialization error: {}\"}}", e),
+        Err(e) => json!({ "error": format!("Serialization error: {}", e) }).to_string(),
     }
 }
 
+/// Groups an array of song objects into albums sorted by name
+/// `albums` optionally declares album metadata to attach to the groups
+#[wasm_bindgen(js_name = groupByAlbum, unchecked_return_type = "Album[]")]
+pub fn group_by_album_js(
+    #[wasm_bindgen(unchecked_param_type = "Song[]")] songs: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "AlbumInfo[]")] albums: Option<JsValue>,
+) -> Result<JsValue, JsError> {
+    let songs: Vec<Song> = from_js(songs, "song list")?;
+    let declared: Option<Vec<AlbumInfo>> = from_js_optional(albums, "album list")?;
+    to_js(&group_songs_with(songs, &declared.unwrap_or_default()))
+}
+
+/// Groups songs by the keys in `keys_json`, e.g. `["decade", "releaseType"]`
+/// `albums_json` optionally declares album metadata (`AlbumInfo[]`)
+/// Returns JSON string of nested groups with counts
+#[wasm_bindgen]
+pub fn group_by_keys(songs_json: &str, keys_json: &str, albums_json: Option<String>) -> String {
+    let songs: Vec<Song> = match serde_json::from_str(songs_json) {
+        Ok(s) => s,
+        Err(e) => return json!({ "error": format!("Invalid JSON: {}", e) }).to_string(),
+    };
+    let keys: Vec<GroupKey> = match serde_json::from_str(keys_json) {
+        Ok(keys) => keys,
+        Err(e) => return json!({ "error": format!("Invalid group keys: {}", e) }).to_string(),
+    };
+
+    let groups = parse_declared_albums(albums_json.as_deref())
+        .and_then(|declared| group_songs_by(songs, &keys, &declared, &CreatorFriendlyPolicy::default()));
+    match groups.and_then(|groups| serde_json::to_string(&groups).map_err(|e| format!("Serialization error: {}", e))) {
+        Ok(json) => json,
+        Err(e) => json!({ "error": e }).to_string(),
+    }
+}
+
+/// Groups an array of song objects by one or more keys, nesting a level per key
+/// `albums` optionally declares album metadata for the album key
+#[wasm_bindgen(js_name = groupByKeys, unchecked_return_type = "SongGroup[]")]
+pub fn group_by_keys_js(
+    #[wasm_bindgen(unchecked_param_type = "Song[]")] songs: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "GroupKey[]")] keys: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "AlbumInfo[]")] albums: Option<JsValue>,
+) -> Result<JsValue, JsError> {
+    let songs: Vec<Song> = from_js(songs, "song list")?;
+    let keys: Vec<GroupKey> = from_js(keys, "group keys")?;
+    let declared: Option<Vec<AlbumInfo>> = from_js_optional(albums, "album list")?;
+    let groups = group_songs_by(songs, &keys, &declared.unwrap_or_default(), &CreatorFriendlyPolicy::default())
+        .map_err(|e| JsError::new(&e))?;
+    to_js(&groups)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing;
+    use crate::validation::ReleaseType;
 
     #[test]
     fn test_group_by_album() {
@@ -79,7 +465,7 @@ mod tests {
             }
         ]"#;
         
-        let result = group_by_album(json);
+        let result = group_by_album(json, None);
         assert!(!result.contains("error"));
         
         // Parse result to verify structure
@@ -118,7 +504,7 @@ mod tests {
             }
         ]"#;
         
-        let result = group_by_album(json);
+        let result = group_by_album(json, None);
         let albums: Vec<Album> = serde_json::from_str(&result).unwrap();
         
         // Count total songs
@@ -131,6 +517,191 @@ mod tests {
         assert!(all_songs.iter().any(|s| s.id == "song-002"));
     }
 
+    #[test]
+    fn test_group_by_album_error_is_valid_json() {
+        // serde's message quotes the offending value, which must be escaped
+        let parsed: serde_json::Value = serde_json::from_str(&group_by_album(r#""not songs""#, None)).unwrap();
+        assert!(parsed["error"].as_str().unwrap().contains("\"not songs\""));
+    }
+
+    #[test]
+    fn test_group_by_album_preserves_all_fields() {
+        let song_a = r#"{"id":"song-001","title":"Song 1","albumName":"Album A","releaseType":"Independent","hasContentId":false,"streamingLink":"https://example.com","license":"CC BY 4.0","releaseYear":2023,"albumArtwork":"https://example.com/a.jpg","featuring":["someone"]}"#;
+        let song_b = r#"{"id":"song-002","title":"Song 2","albumName":"Album B","releaseType":"NCS","hasContentId":false,"streamingLink":"https://example.com","license":"","releaseYear":2024}"#;
+
+        let result = group_by_album(&format!("[{},{}]", song_b, song_a), None);
+        assert_eq!(
+            result,
+            format!(r#"[{{"name":"Album A","songs":[{}]}},{{"name":"Album B","songs":[{}]}}]"#, song_a, song_b)
+        );
+    }
+
+    #[test]
+    fn test_group_by_album_with_declared_albums() {
+        let song = |id: &str, album_name: &str, album_id: Option<&str>| match album_id {
+            Some(album_id) => testing::song(id).album(album_name).album_id(album_id).json(),
+            None => testing::song(id).album(album_name).json(),
+        };
+        let songs = json!([
+            song("s1", "Old Name", Some("album-1")),
+            song("s2", "Nights", None),
+            song("s3", "Loose", None),
+            song("s4", "Loose", Some("album-404")),
+        ]);
+        let albums = json!([
+            { "id": "album-1", "name": "Nights", "albumType": "EP", "upc": "036000291452" },
+            { "id": "album-2", "name": "Unreleased" },
+        ]);
+
+        let result: Value = serde_json::from_str(&group_by_album(&songs.to_string(), Some(albums.to_string()))).unwrap();
+        assert_eq!(
+            result,
+            json!([
+                { "name": "Loose", "songs": [song("s3", "Loose", None), song("s4", "Loose", Some("album-404"))] },
+                {
+                    "id": "album-1",
+                    "name": "Nights",
+                    "albumType": "EP",
+                    "upc": "036000291452",
+                    "songs": [song("s1", "Old Name", Some("album-1")), song("s2", "Nights", None)],
+                },
+            ])
+        );
+
+        let parsed: Value = serde_json::from_str(&group_by_album("[]", Some("{".to_string()))).unwrap();
+        assert!(parsed["error"].as_str().unwrap().starts_with("Invalid albums JSON"));
+        assert_eq!(group_by_album("[]", Some(String::new())), "[]");
+    }
+
+    #[test]
+    fn test_group_by_album_orders_by_disc_and_track() {
+        let song = |id: &str, disc: Option<u32>, track: Option<u32>| testing::song(id).disc(disc).track(track).json();
+        let songs = json!([
+            song("bonus-b", None, None),
+            song("d2t1", Some(2), Some(1)),
+            song("bonus-a", None, None),
+            song("d1t2", None, Some(2)),
+            song("d1t1", Some(1), Some(1)),
+        ]);
+
+        let albums: Vec<Album> = serde_json::from_str(&group_by_album(&songs.to_string(), None)).unwrap();
+        let order: Vec<&str> = albums[0].songs.iter().map(|song| song.id.as_str()).collect();
+        // Unnumbered songs keep their input order rather than sorting by ID
+        assert_eq!(order, ["d1t1", "d1t2", "d2t1", "bonus-b", "bonus-a"]);
+
+        // Album groups from group_by_keys list their songs the same way
+        let ids = |group: &Value| -> Vec<String> { group["songs"].as_array().unwrap().iter().map(|song| song["id"].as_str().unwrap().to_string()).collect() };
+        let groups: Value = serde_json::from_str(&group_by_keys(&songs.to_string(), r#"["album"]"#, None)).unwrap();
+        assert_eq!(ids(&groups[0]), ["d1t1", "d1t2", "d2t1", "bonus-b", "bonus-a"]);
+        let groups: Value = serde_json::from_str(&group_by_keys(&songs.to_string(), r#"["album", "contentId"]"#, None)).unwrap();
+        assert_eq!(ids(&groups[0]["groups"][0]), ["d1t1", "d1t2", "d2t1", "bonus-b", "bonus-a"]);
+
+        // Songs outside album groups keep their input order
+        let groups: Value = serde_json::from_str(&group_by_keys(&songs.to_string(), r#"["contentId"]"#, None)).unwrap();
+        assert_eq!(ids(&groups[0]), ["bonus-b", "d2t1", "bonus-a", "d1t2", "d1t1"]);
+    }
+
+    #[test]
+    fn test_group_by_album_merges_name_variants() {
+        let song = |id: &str, album: &str| testing::song(id).album(album).json();
+        let songs = json!([
+            song("s1", "synthwave chronicles"),
+            song("s2", "Synthwave Chronicles"),
+            song("s3", "Synthwave  Chronicles "),
+            song("s4", "Synthwave Chronicles"),
+            song("s5", "Synthwave Chronicl\u{e9}s"),
+            song("s6", "nights"),
+        ]);
+        let albums = Some(r#"[{"id": "album-1", "name": "Nights"}]"#.to_string());
+
+        let albums: Vec<Album> = serde_json::from_str(&group_by_album(&songs.to_string(), albums)).unwrap();
+        let summary: Vec<(Option<&str>, &str, usize)> = albums.iter().map(|a| (a.id.as_deref(), a.name.as_str(), a.songs.len())).collect();
+        assert_eq!(summary, [
+            (Some("album-1"), "Nights", 1),
+            (None, "Synthwave Chronicles", 4),
+            (None, "Synthwave Chronicl\u{e9}s", 1),
+        ]);
+        assert_eq!(album_identity(" Synthwave  CHRONICLES"), "synthwave chronicles");
+    }
+
+    #[test]
+    fn test_group_by_keys() {
+        let song = |id: &str, year: Option<u32>, release_type: ReleaseType, license: &str, content_id: bool| {
+            testing::song(id)
+                .year(year)
+                .release_type(release_type)
+                .license(license)
+                .content_id(content_id)
+                .json()
+        };
+        let songs = json!([
+            song("s1", Some(2023), ReleaseType::NCS, "CC BY 4.0", false),
+            song("s2", None, ReleaseType::Independent, "All Rights Reserved", true),
+            song("s3", Some(2019), ReleaseType::Independent, "BGML-P", false),
+            song("s4", Some(2024), ReleaseType::Independent, "CC BY-SA 4.0", true),
+        ]);
+
+        let groups: Value = serde_json::from_str(&group_by_keys(&songs.to_string(), r#"["decade", "releaseType"]"#, None)).unwrap();
+        let summary: Vec<(Value, Value, Value)> = groups
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|group| (group["value"].clone(), group["label"].clone(), group["count"].clone()))
+            .collect();
+        assert_eq!(summary, [
+            (json!(2010), json!("2010s"), json!(1)),
+            (json!(2020), json!("2020s"), json!(2)),
+            (Value::Null, json!("Unknown year"), json!(1)),
+        ]);
+        assert_eq!(groups[1]["groups"], json!([
+            { "key": "releaseType", "value": "Independent", "label": "Independent", "count": 1, "songs": [song("s4", Some(2024), ReleaseType::Independent, "CC BY-SA 4.0", true)] },
+            { "key": "releaseType", "value": "NCS", "label": "NCS", "count": 1, "songs": [song("s1", Some(2023), ReleaseType::NCS, "CC BY 4.0", false)] },
+        ]));
+        assert!(groups[1].get("songs").is_none());
+
+        let songs: Vec<Song> = serde_json::from_value(songs).unwrap();
+        let policy = CreatorFriendlyPolicy::default();
+        let labels = |keys: &[GroupKey]| -> Vec<(String, usize)> {
+            let groups = group_songs_by(songs.clone(), keys, &[], &policy).unwrap();
+            groups.into_iter().map(|group| (group.label, group.count)).collect()
+        };
+        assert_eq!(labels(&[GroupKey::LicenseFamily]), [
+            ("Creative Commons".to_string(), 2),
+            ("BGML".to_string(), 1),
+            ("All Rights Reserved".to_string(), 1),
+        ]);
+        assert_eq!(labels(&[GroupKey::ContentId]), [("Content ID".to_string(), 2), ("No Content ID".to_string(), 2)]);
+        assert_eq!(labels(&[GroupKey::CreatorFriendly]), [("Creator-friendly".to_string(), 3), ("Not creator-friendly".to_string(), 1)]);
+        assert_eq!(group_songs_by(songs, &[], &[], &policy).unwrap_err(), "At least one group key is required");
+    }
+
+    #[test]
+    fn test_group_by_keys_albums_and_errors() {
+        let songs = json!([
+            testing::song("s1").album("Nights").album_id("album-1").json(),
+            testing::song("s2").album("Loose").json(),
+        ])
+        .to_string();
+        let albums = Some(r#"[{"id": "album-1", "name": "Nights"}]"#.to_string());
+
+        let groups: Value = serde_json::from_str(&group_by_keys(&songs, r#"["album"]"#, albums)).unwrap();
+        assert_eq!((&groups[0]["value"], &groups[0]["label"]), (&json!("Loose"), &json!("Loose")));
+        assert_eq!((&groups[1]["value"], &groups[1]["label"]), (&json!("album-1"), &json!("Nights")));
+
+        let error = |keys: &str| serde_json::from_str::<Value>(&group_by_keys(&songs, keys, None)).unwrap()["error"].clone();
+        assert!(error(r#"["label"]"#).as_str().unwrap().starts_with("Invalid group keys"));
+        assert_eq!(error("[]"), "At least one group key is required");
+    }
+
+    #[test]
+    fn test_album_info_round_trip() {
+        let json = r#"{"id":"album-001","name":"Synthwave Chronicles","albumType":"LP","artwork":"https://example.com/a.jpg","releaseDate":"2023-06-15","description":"Retro","upc":"036000291452","label":"self"}"#;
+        let album: AlbumInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(album.metadata.album_type, Some(AlbumType::LP));
+        assert_eq!(album.extra.keys().collect::<Vec<_>>(), ["label"]);
+        assert_eq!(serde_json::to_string(&album).unwrap(), json);
+    }
+
     #[test]
     fn test_group_by_album_comprehensive_demo() {
         println!("\n=== Album Grouping Functionality Demo ===");
@@ -168,7 +739,7 @@ mod tests {
         println!("Input songs JSON:");
         println!("{}", json);
         
-        let result = group_by_album(json);
+        let result = group_by_album(json, None);
         println!("\nGrouped albums result:");
         println!("{}", result);
         
@@ -209,7 +780,7 @@ mod tests {
     #[test]
     fn test_group_by_album_empty() {
         let json = r#"[]"#;
-        let result = group_by_album(json);
+        let result = group_by_album(json, None);
         let albums: Vec<Album> = serde_json::from_str(&result).unwrap();
         assert_eq!(albums.len(), 0);
     }
@@ -238,7 +809,7 @@ mod tests {
         
         // Time the grouping operation
         let start = std::time::Instant::now();
-        let result = group_by_album(&json);
+        let result = group_by_album(&json, None);
         let duration = start.elapsed();
         
         println!("Grouped 100 songs into albums in {:?}", duration);
@@ -252,9 +823,10 @@ mod tests {
             assert_eq!(album.songs.len(), 10);
         }
         
-        // Verify sorting
-        let is_sorted = albums.windows(2).all(|w| w[0].name <= w[1].name);
-        assert!(is_sorted);
+        // Verify natural sorting: "Album 2" before "Album 10"
+        let names: Vec<&str> = albums.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names[..3], ["Album 1", "Album 2", "Album 3"]);
+        assert_eq!(names[9], "Album 10");
         
         // Verify total song count
         let total_songs: usize = albums.iter().map(|a| a.songs.len()).sum();
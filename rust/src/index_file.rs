@@ -0,0 +1,381 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use crate::search::{Posting, SearchField, SearchIndex};
+use crate::validation::Song;
+
+/// First bytes of every serialized search index
+pub const INDEX_MAGIC: [u8; 4] = *b"BSIX";
+/// Version of the serialized layout and of the tokenizer that produced the
+/// terms; bump it whenever either changes so older files are rejected
+pub const INDEX_FORMAT_VERSION: u16 = 1;
+
+/// Magic, format version and fingerprint
+const HEADER_LENGTH: usize = 4 + 2 + 8;
+/// Checksum of everything before it
+const TRAILER_LENGTH: usize = 8;
+
+/// Why a serialized search index was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexError {
+    /// The bytes don't start with `INDEX_MAGIC`
+    NotAnIndex,
+    /// Written by another version of the crate
+    UnsupportedVersion { found: u16, expected: u16 },
+    /// Truncated or damaged file
+    Corrupt(String),
+    /// Built from songs other than the ones being loaded
+    Stale,
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexError::NotAnIndex => write!(f, "Not a search index file"),
+            IndexError::UnsupportedVersion { found, expected } => write!(
+                f,
+                "Search index format version {} is not supported (expected {}); rebuild it with `musicctl index`",
+                found, expected
+            ),
+            IndexError::Corrupt(reason) => write!(f, "Corrupt search index: {}", reason),
+            IndexError::Stale => write!(f, "Search index is stale: it was built from different songs; rebuild it with `musicctl index`"),
+        }
+    }
+}
+
+/// 64-bit FNV-1a hash, used for fingerprints and checksums
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Writes a length-prefixed string, so field boundaries can't shift
+    fn write_str(&mut self, text: &str) {
+        self.write(&(text.len() as u64).to_le_bytes());
+        self.write(text.as_bytes());
+    }
+}
+
+fn checksum(bytes: &[u8]) -> u64 {
+    let mut hash = Fnv::new();
+    hash.write(bytes);
+    hash.0
+}
+
+/// Hash of everything a search index is built from: song IDs and indexed
+/// fields, in order. Edits to other fields (links, artwork) keep it unchanged
+pub fn songs_fingerprint(songs: &[Song]) -> u64 {
+    let mut hash = Fnv::new();
+    hash.write(&(songs.len() as u64).to_le_bytes());
+    for song in songs {
+        hash.write_str(&song.id);
+        for field in SearchField::ALL {
+            hash.write_str(&field.text(song));
+        }
+    }
+    hash.0
+}
+
+/// Appends `value` as an unsigned LEB128 varint
+fn write_varint(out: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn corrupt(reason: impl Into<String>) -> IndexError {
+    IndexError::Corrupt(reason.into())
+}
+
+/// Cursor over the body of a serialized index
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], IndexError> {
+        let end = self.pos.checked_add(length).filter(|&end| end <= self.bytes.len()).ok_or_else(|| corrupt("unexpected end of data"))?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, IndexError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u32, IndexError> {
+        let mut value: u32 = 0;
+        for shift in (0..35).step_by(7) {
+            let byte = self.byte()?;
+            let bits = u32::from(byte & 0x7f);
+            if shift == 28 && bits > 0x0f {
+                break;
+            }
+            value |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(corrupt("number out of range"))
+    }
+
+    /// A count of items that take at least one byte each, checked against the
+    /// bytes left so damaged counts can't cause huge allocations
+    fn count(&mut self) -> Result<usize, IndexError> {
+        let count = self.varint()? as usize;
+        if count > self.bytes.len() - self.pos {
+            return Err(corrupt("count exceeds the data"));
+        }
+        Ok(count)
+    }
+
+    fn string(&mut self, what: &str) -> Result<String, IndexError> {
+        let length = self.count()?;
+        String::from_utf8(self.take(length)?.to_vec()).map_err(|_| corrupt(format!("{} is not UTF-8", what)))
+    }
+}
+
+impl SearchIndex {
+    /// `songs_fingerprint` of the songs the index was built from
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// Whether the index was built from exactly these songs' indexed fields
+    pub fn is_current_for(&self, songs: &[Song]) -> bool {
+        self.fingerprint == songs_fingerprint(songs)
+    }
+
+    /// Serializes the index into a compact binary artifact, so it can be built
+    /// ahead of time (see `musicctl index`) and loaded without tokenizing
+    ///
+    /// Layout, little-endian, counts and numbers as LEB128 varints:
+    /// - header: `INDEX_MAGIC`, `INDEX_FORMAT_VERSION` (u16), fingerprint (u64)
+    /// - songs: count, then each ID (length, UTF-8) and its four field lengths
+    /// - terms in sorted order: count, then each term front-coded against the
+    ///   previous one (shared byte length, suffix length, suffix) and its
+    ///   postings (count, then song delta, field, occurrences)
+    /// - trailer: FNV-1a checksum (u64) of everything before it
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&INDEX_MAGIC);
+        out.extend_from_slice(&INDEX_FORMAT_VERSION.to_le_bytes());
+        out.extend_from_slice(&self.fingerprint.to_le_bytes());
+
+        write_varint(&mut out, self.ids.len() as u32);
+        for (id, lengths) in self.ids.iter().zip(&self.field_lengths) {
+            write_varint(&mut out, id.len() as u32);
+            out.extend_from_slice(id.as_bytes());
+            for &length in lengths {
+                write_varint(&mut out, length);
+            }
+        }
+
+        write_varint(&mut out, self.postings.len() as u32);
+        let mut previous: &[u8] = &[];
+        for (term, postings) in &self.postings {
+            let term = term.as_bytes();
+            let shared = previous.iter().zip(term).take_while(|(a, b)| a == b).count();
+            write_varint(&mut out, shared as u32);
+            write_varint(&mut out, (term.len() - shared) as u32);
+            out.extend_from_slice(&term[shared..]);
+            previous = term;
+
+            write_varint(&mut out, postings.len() as u32);
+            let mut last_song = 0;
+            for posting in postings {
+                write_varint(&mut out, posting.song - last_song);
+                out.push(posting.field as u8);
+                write_varint(&mut out, posting.count);
+                last_song = posting.song;
+            }
+        }
+
+        let checksum = checksum(&out);
+        out.extend_from_slice(&checksum.to_le_bytes());
+        out
+    }
+
+    /// Loads an index written by `to_bytes`, checking its magic, format
+    /// version and checksum
+    pub fn from_bytes(bytes: &[u8]) -> Result<SearchIndex, IndexError> {
+        if !bytes.starts_with(&INDEX_MAGIC) {
+            return Err(IndexError::NotAnIndex);
+        }
+        if bytes.len() < HEADER_LENGTH + TRAILER_LENGTH {
+            return Err(corrupt("file is too short"));
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != INDEX_FORMAT_VERSION {
+            return Err(IndexError::UnsupportedVersion { found: version, expected: INDEX_FORMAT_VERSION });
+        }
+        let (content, trailer) = bytes.split_at(bytes.len() - TRAILER_LENGTH);
+        if checksum(content).to_le_bytes() != trailer {
+            return Err(corrupt("checksum mismatch"));
+        }
+
+        let mut fingerprint = [0; 8];
+        fingerprint.copy_from_slice(&content[6..HEADER_LENGTH]);
+        let mut index = SearchIndex { fingerprint: u64::from_le_bytes(fingerprint), ..Default::default() };
+        let mut reader = Reader { bytes: content, pos: HEADER_LENGTH };
+
+        let song_count = reader.count()?;
+        for _ in 0..song_count {
+            index.ids.push(reader.string("song ID")?);
+            let mut lengths = [0; 4];
+            for length in &mut lengths {
+                *length = reader.varint()?;
+            }
+            index.field_lengths.push(lengths);
+        }
+
+        let term_count = reader.count()?;
+        let mut postings = BTreeMap::new();
+        let mut previous: Vec<u8> = Vec::new();
+        for _ in 0..term_count {
+            let shared = reader.varint()? as usize;
+            if shared > previous.len() {
+                return Err(corrupt("term shares more than the previous term"));
+            }
+            let suffix_length = reader.count()?;
+            let mut term = previous[..shared].to_vec();
+            term.extend_from_slice(reader.take(suffix_length)?);
+            if term <= previous && !postings.is_empty() {
+                return Err(corrupt("terms are out of order"));
+            }
+
+            let posting_count = reader.count()?;
+            let mut term_postings = Vec::with_capacity(posting_count);
+            let mut song: u32 = 0;
+            for _ in 0..posting_count {
+                song = song.checked_add(reader.varint()?).filter(|&song| (song as usize) < song_count).ok_or_else(|| corrupt("posting for an unknown song"))?;
+                let field = *SearchField::ALL.get(usize::from(reader.byte()?)).ok_or_else(|| corrupt("unknown field"))?;
+                term_postings.push(Posting { song, field, count: reader.varint()? });
+            }
+
+            let text = String::from_utf8(term.clone()).map_err(|_| corrupt("term is not UTF-8"))?;
+            postings.insert(text, term_postings);
+            previous = term;
+        }
+        if reader.pos != content.len() {
+            return Err(corrupt("unexpected data after the terms"));
+        }
+
+        index.postings = postings;
+        index.compute_average_lengths();
+        Ok(index)
+    }
+
+    /// Loads an index like `from_bytes`, rejecting it unless it was built
+    /// from `songs`
+    pub fn from_bytes_for(bytes: &[u8], songs: &[Song]) -> Result<SearchIndex, IndexError> {
+        let index = SearchIndex::from_bytes(bytes)?;
+        if !index.is_current_for(songs) {
+            return Err(IndexError::Stale);
+        }
+        Ok(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::Catalog;
+    use crate::validation::MusicData;
+
+    const SITE_DATA: &str = include_str!("../../src/data/music.json");
+
+    fn site_songs() -> Vec<Song> {
+        serde_json::from_str::<MusicData>(SITE_DATA).unwrap().songs
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let songs = site_songs();
+        let index = SearchIndex::build(&songs);
+        let bytes = index.to_bytes();
+        assert!(bytes.starts_with(b"BSIX\x01\x00"));
+
+        let loaded = SearchIndex::from_bytes_for(&bytes, &songs).unwrap();
+        assert_eq!(loaded, index);
+        assert_eq!(loaded.search("synth"), index.search("synth"));
+        assert_eq!(loaded.to_bytes(), bytes);
+
+        let empty = SearchIndex::build(&[]);
+        assert_eq!(SearchIndex::from_bytes_for(&empty.to_bytes(), &[]).unwrap(), empty);
+    }
+
+    #[test]
+    fn test_artifact_is_compact() {
+        let songs = site_songs();
+        let bytes = SearchIndex::build(&songs).to_bytes();
+        assert!(bytes.len() < serde_json::to_string(&songs).unwrap().len() / 2, "index is {} bytes", bytes.len());
+    }
+
+    #[test]
+    fn test_rejects_other_files_and_versions() {
+        let bytes = SearchIndex::build(&site_songs()).to_bytes();
+        assert_eq!(SearchIndex::from_bytes(b"{\"songs\": []}"), Err(IndexError::NotAnIndex));
+        assert_eq!(SearchIndex::from_bytes(b""), Err(IndexError::NotAnIndex));
+
+        let mut newer = bytes.clone();
+        newer[4] = 2;
+        let error = SearchIndex::from_bytes(&newer).unwrap_err();
+        assert_eq!(error, IndexError::UnsupportedVersion { found: 2, expected: INDEX_FORMAT_VERSION });
+        assert!(error.to_string().contains("rebuild it with `musicctl index`"));
+    }
+
+    #[test]
+    fn test_rejects_damaged_files() {
+        let bytes = SearchIndex::build(&site_songs()).to_bytes();
+        let mut flipped = bytes.clone();
+        flipped[HEADER_LENGTH + 3] ^= 0x20;
+        assert_eq!(SearchIndex::from_bytes(&flipped), Err(corrupt("checksum mismatch")));
+        assert_eq!(SearchIndex::from_bytes(&bytes[..bytes.len() - 1]), Err(corrupt("checksum mismatch")));
+        assert_eq!(SearchIndex::from_bytes(&bytes[..10]), Err(corrupt("file is too short")));
+
+        // A valid checksum over a truncated body still fails to parse
+        let mut truncated = bytes[..bytes.len() - TRAILER_LENGTH - 4].to_vec();
+        let sum = checksum(&truncated);
+        truncated.extend_from_slice(&sum.to_le_bytes());
+        assert!(matches!(SearchIndex::from_bytes(&truncated), Err(IndexError::Corrupt(_))));
+    }
+
+    #[test]
+    fn test_rejects_stale_index() {
+        let mut songs = site_songs();
+        let bytes = SearchIndex::build(&songs).to_bytes();
+
+        // Fields the index doesn't cover don't make it stale
+        songs[0].streaming_link = "https://example.com/moved".to_string();
+        assert!(SearchIndex::from_bytes_for(&bytes, &songs).is_ok());
+
+        songs[0].title.push_str(" (Remix)");
+        assert_eq!(SearchIndex::from_bytes_for(&bytes, &songs), Err(IndexError::Stale));
+        assert_eq!(SearchIndex::from_bytes_for(&bytes, &site_songs()[1..]), Err(IndexError::Stale));
+    }
+
+    #[test]
+    fn test_published_index_is_current() {
+        // Regenerate with `cargo run --bin musicctl -- index --output ../public/search-index.bin ../src/data/music.json`
+        // or `UPDATE_SEARCH_INDEX=1 cargo test published_index`
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../public/search-index.bin");
+        let expected = Catalog::load(SITE_DATA).search_index().to_bytes();
+        if std::env::var_os("UPDATE_SEARCH_INDEX").is_some() {
+            std::fs::write(path, &expected).unwrap();
+        }
+        let published = std::fs::read(path).unwrap_or_default();
+        assert!(published == expected, "public/search-index.bin is out of date; rerun with UPDATE_SEARCH_INDEX=1");
+    }
+}
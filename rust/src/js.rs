@@ -0,0 +1,25 @@
+use wasm_bindgen::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Converts a JS value into `T`, naming `what` in the thrown error
+/// `undefined` and `null` become `None` when `T` is an `Option`
+pub fn from_js<T: DeserializeOwned>(value: JsValue, what: &str) -> Result<T, JsError> {
+    serde_wasm_bindgen::from_value(value).map_err(|e| JsError::new(&format!("Invalid {}: {}", what, e)))
+}
+
+/// Like `from_js`, for trailing parameters JavaScript callers may leave out
+pub fn from_js_optional<T: DeserializeOwned>(value: Option<JsValue>, what: &str) -> Result<Option<T>, JsError> {
+    match value {
+        Some(value) => from_js(value, what),
+        None => Ok(None),
+    }
+}
+
+/// Converts `value` into plain JS objects and arrays
+/// Maps become objects (not `Map`s) and `None` becomes `null`, matching `JSON.parse`
+pub fn to_js<T: Serialize + ?Sized>(value: &T) -> Result<JsValue, JsError> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| JsError::new(&format!("Serialization error: {}", e)))
+}
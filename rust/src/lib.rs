@@ -4,11 +4,45 @@ use wasm_bindgen::prelude::*;
 pub mod validation;
 pub mod filters;
 pub mod grouping;
+pub mod attribution;
+pub mod license;
+pub mod report;
+pub mod source_map;
+pub mod policy;
+pub mod catalog;
+pub mod js;
+pub mod schema;
+pub mod normalize;
+pub mod text;
+pub mod sorting;
+pub mod search;
+pub mod suggest;
+pub mod query;
+pub mod facets;
+pub mod index_file;
+#[cfg(test)]
+mod testing;
 
 // Re-export main functions for WASM bindings
 pub use validation::*;
 pub use filters::*;
 pub use grouping::*;
+pub use attribution::*;
+pub use license::*;
+pub use report::*;
+pub use source_map::*;
+pub use policy::*;
+pub use catalog::*;
+pub use js::*;
+pub use schema::*;
+pub use normalize::*;
+pub use text::*;
+pub use sorting::*;
+pub use search::*;
+pub use suggest::*;
+pub use query::*;
+pub use facets::*;
+pub use index_file::*;
 
 // When the `dev` feature is enabled, we can call the
 // `set_panic_hook` function at least once during initialization, and then
@@ -0,0 +1,946 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+/// Creative Commons license versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CcVersion {
+    V1_0,
+    V2_0,
+    V2_1,
+    V2_5,
+    V3_0,
+    V4_0,
+}
+
+impl CcVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CcVersion::V1_0 => "1.0",
+            CcVersion::V2_0 => "2.0",
+            CcVersion::V2_1 => "2.1",
+            CcVersion::V2_5 => "2.5",
+            CcVersion::V3_0 => "3.0",
+            CcVersion::V4_0 => "4.0",
+        }
+    }
+
+    fn parse(token: &str) -> Option<Self> {
+        match token {
+            "1.0" | "1" => Some(CcVersion::V1_0),
+            "2.0" | "2" => Some(CcVersion::V2_0),
+            "2.1" => Some(CcVersion::V2_1),
+            "2.5" => Some(CcVersion::V2_5),
+            "3.0" | "3" => Some(CcVersion::V3_0),
+            "4.0" | "4" => Some(CcVersion::V4_0),
+            _ => None,
+        }
+    }
+}
+
+/// The six Creative Commons license element combinations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CcElements {
+    By,
+    BySa,
+    ByNc,
+    ByNd,
+    ByNcSa,
+    ByNcNd,
+}
+
+impl CcElements {
+    pub const ALL: [CcElements; 6] = [
+        CcElements::By,
+        CcElements::BySa,
+        CcElements::ByNc,
+        CcElements::ByNd,
+        CcElements::ByNcSa,
+        CcElements::ByNcNd,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CcElements::By => "BY",
+            CcElements::BySa => "BY-SA",
+            CcElements::ByNc => "BY-NC",
+            CcElements::ByNd => "BY-ND",
+            CcElements::ByNcSa => "BY-NC-SA",
+            CcElements::ByNcNd => "BY-NC-ND",
+        }
+    }
+
+    /// Builds the combination from its individual elements
+    /// ShareAlike and NoDerivatives are mutually exclusive
+    fn from_flags(non_commercial: bool, no_derivatives: bool, share_alike: bool) -> Option<Self> {
+        match (non_commercial, no_derivatives, share_alike) {
+            (false, false, false) => Some(CcElements::By),
+            (false, false, true) => Some(CcElements::BySa),
+            (true, false, false) => Some(CcElements::ByNc),
+            (false, true, false) => Some(CcElements::ByNd),
+            (true, false, true) => Some(CcElements::ByNcSa),
+            (true, true, false) => Some(CcElements::ByNcNd),
+            (_, true, true) => None,
+        }
+    }
+
+    pub fn is_non_commercial(&self) -> bool {
+        matches!(self, CcElements::ByNc | CcElements::ByNcSa | CcElements::ByNcNd)
+    }
+
+    pub fn is_no_derivatives(&self) -> bool {
+        matches!(self, CcElements::ByNd | CcElements::ByNcNd)
+    }
+
+    pub fn is_share_alike(&self) -> bool {
+        matches!(self, CcElements::BySa | CcElements::ByNcSa)
+    }
+}
+
+/// An attribution-based Creative Commons license, e.g. `CC BY-SA 3.0 DE`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CcLicense {
+    pub elements: CcElements,
+    pub version: Option<CcVersion>,
+    /// Jurisdiction port (uppercase code such as `DE` or `IGO`); only exists before 4.0
+    pub jurisdiction: Option<String>,
+}
+
+/// Members of the Babafun Game Music License family
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BgmlVariant {
+    /// Plain `BGML`
+    Standard,
+    /// `BGML-P` (Permissive)
+    Permissive,
+    /// Any other `BGML-<suffix>` variant, suffix stored uppercase
+    Other(String),
+}
+
+/// A parsed song license
+/// Parsing never fails: anything unrecognised is kept as `Custom` text
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum License {
+    /// Empty license field
+    Unspecified,
+    CreativeCommons(CcLicense),
+    /// CC0 public domain dedication
+    Cc0 { version: Option<CcVersion> },
+    PublicDomainMark { version: Option<CcVersion> },
+    Bgml(BgmlVariant),
+    AllRightsReserved,
+    /// Free-form license text, kept as written (trimmed)
+    Custom(String),
+}
+
+/// Broad kind of license, for grouping songs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LicenseFamily {
+    /// Attribution-based Creative Commons licenses
+    CreativeCommons,
+    /// CC0 and the Public Domain Mark
+    PublicDomain,
+    Bgml,
+    AllRightsReserved,
+    Custom,
+    /// Expression combining licenses from different families
+    Mixed,
+    Unspecified,
+}
+
+impl LicenseFamily {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LicenseFamily::CreativeCommons => "Creative Commons",
+            LicenseFamily::PublicDomain => "Public Domain",
+            LicenseFamily::Bgml => "BGML",
+            LicenseFamily::AllRightsReserved => "All Rights Reserved",
+            LicenseFamily::Custom => "Custom",
+            LicenseFamily::Mixed => "Mixed",
+            LicenseFamily::Unspecified => "Unspecified",
+        }
+    }
+}
+
+impl License {
+    /// Parses a license string, case-insensitively
+    /// Accepts short (`CC BY-SA 4.0`), SPDX-like (`CC-BY-SA-4.0`) and long
+    /// (`Creative Commons Attribution-ShareAlike 4.0 International`) spellings
+    pub fn parse(text: &str) -> License {
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return License::Unspecified;
+        }
+
+        let upper = trimmed.to_uppercase();
+        let tokens = license_tokens(&upper);
+
+        parse_creative_commons(&tokens)
+            .or_else(|| parse_public_domain(&tokens))
+            .or_else(|| parse_bgml(&tokens))
+            .or_else(|| parse_all_rights_reserved(&tokens))
+            .unwrap_or_else(|| License::Custom(trimmed.to_string()))
+    }
+
+    /// Commercial Creative Commons license: CC BY, CC BY-SA or CC0
+    /// The Public Domain Mark labels a work rather than licensing it, so it isn't one
+    pub fn is_commercial_cc(&self) -> bool {
+        match self {
+            License::CreativeCommons(cc) => !cc.elements.is_non_commercial() && !cc.elements.is_no_derivatives(),
+            License::Cc0 { .. } => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_bgml_permissive(&self) -> bool {
+        matches!(self, License::Bgml(BgmlVariant::Permissive))
+    }
+
+    pub fn family(&self) -> LicenseFamily {
+        match self {
+            License::Unspecified => LicenseFamily::Unspecified,
+            License::CreativeCommons(_) => LicenseFamily::CreativeCommons,
+            License::Cc0 { .. } | License::PublicDomainMark { .. } => LicenseFamily::PublicDomain,
+            License::Bgml(_) => LicenseFamily::Bgml,
+            License::AllRightsReserved => LicenseFamily::AllRightsReserved,
+            License::Custom(_) => LicenseFamily::Custom,
+        }
+    }
+
+    /// Canonical spelling, identical to the `Display` output
+    pub fn canonical(&self) -> String {
+        self.to_string()
+    }
+
+    /// Link to the official license deed, when there is one
+    pub fn url(&self) -> Option<String> {
+        match self {
+            License::CreativeCommons(cc) => {
+                let mut url = format!(
+                    "https://creativecommons.org/licenses/{}/{}/",
+                    cc.elements.as_str().to_lowercase(),
+                    cc.version?.as_str()
+                );
+                if let Some(jurisdiction) = &cc.jurisdiction {
+                    url.push_str(&jurisdiction.to_lowercase());
+                    url.push('/');
+                }
+                Some(url)
+            }
+            License::Cc0 { .. } => Some("https://creativecommons.org/publicdomain/zero/1.0/".to_string()),
+            License::PublicDomainMark { .. } => Some("https://creativecommons.org/publicdomain/mark/1.0/".to_string()),
+            _ => None,
+        }
+    }
+
+    /// SPDX identifier, e.g. `CC-BY-SA-4.0` or `LicenseRef-BGML-P`
+    /// Returns `None` for licenses SPDX can't name (unversioned CC, all rights reserved, custom text)
+    pub fn spdx_id(&self) -> Option<String> {
+        match self {
+            License::CreativeCommons(cc) => {
+                let mut id = format!("CC-{}-{}", cc.elements.as_str(), cc.version?.as_str());
+                if let Some(jurisdiction) = &cc.jurisdiction {
+                    id.push('-');
+                    id.push_str(jurisdiction);
+                }
+                Some(id)
+            }
+            License::Cc0 { .. } => Some("CC0-1.0".to_string()),
+            License::Bgml(_) => Some(format!("LicenseRef-{}", self)),
+            _ => None,
+        }
+    }
+}
+
+/// Splits an uppercased license name into words, treating `-` and `_` as spaces
+fn license_tokens(upper: &str) -> Vec<&str> {
+    upper.split(|c: char| c.is_whitespace() || c == '-' || c == '_').filter(|t| !t.is_empty()).collect()
+}
+
+/// Strips a `CC` / `Creative Commons` prefix, returning the remaining tokens
+fn strip_cc_prefix<'a, 'b>(tokens: &'a [&'b str]) -> Option<&'a [&'b str]> {
+    match tokens {
+        ["CC", rest @ ..] => Some(rest),
+        ["CREATIVE", "COMMONS", rest @ ..] => Some(rest),
+        _ => None,
+    }
+}
+
+/// Consumes a trailing optional version plus an optional jurisdiction / edition word
+fn parse_version_and_port(tokens: &[&str]) -> Option<(Option<CcVersion>, Option<String>)> {
+    let (version, rest) = match tokens.split_first() {
+        Some((first, rest)) if first.starts_with(|c: char| c.is_ascii_digit()) => (Some(CcVersion::parse(first)?), rest),
+        _ => (None, tokens),
+    };
+
+    let jurisdiction = match rest {
+        [] | ["UNPORTED"] | ["INTERNATIONAL"] | ["GENERIC"] | ["UNIVERSAL"] => None,
+        [port] if (2..=10).contains(&port.len()) && port.chars().all(|c| c.is_ascii_alphabetic()) => Some(port.to_string()),
+        _ => return None,
+    };
+
+    Some((version, jurisdiction))
+}
+
+fn parse_creative_commons(tokens: &[&str]) -> Option<License> {
+    // CC0 is written either as one token or as "CC Zero"
+    let rest = match tokens {
+        ["CC0", rest @ ..] => Some(rest),
+        _ => match strip_cc_prefix(tokens)? {
+            ["ZERO", rest @ ..] | ["0", rest @ ..] => Some(rest),
+            _ => None,
+        },
+    };
+    if let Some(rest) = rest {
+        return match parse_version_and_port(rest)? {
+            (version @ (None | Some(CcVersion::V1_0)), None) => Some(License::Cc0 { version }),
+            _ => None,
+        };
+    }
+
+    // Attribution always comes first, the other elements may follow in any order
+    let mut rest = match strip_cc_prefix(tokens)? {
+        ["BY" | "ATTRIBUTION", rest @ ..] => rest,
+        _ => return None,
+    };
+    let (mut non_commercial, mut no_derivatives, mut share_alike) = (false, false, false);
+    loop {
+        let (flag, consumed) = match rest {
+            ["NC" | "NONCOMMERCIAL", ..] => (&mut non_commercial, 1),
+            ["NON", "COMMERCIAL", ..] => (&mut non_commercial, 2),
+            ["ND" | "NODERIVATIVES" | "NODERIVS", ..] => (&mut no_derivatives, 1),
+            ["NO", "DERIVATIVES" | "DERIVS", ..] => (&mut no_derivatives, 2),
+            ["SA" | "SHAREALIKE", ..] => (&mut share_alike, 1),
+            ["SHARE", "ALIKE", ..] => (&mut share_alike, 2),
+            _ => break,
+        };
+        if *flag {
+            return None;
+        }
+        *flag = true;
+        rest = &rest[consumed..];
+    }
+
+    let elements = CcElements::from_flags(non_commercial, no_derivatives, share_alike)?;
+    let (version, jurisdiction) = parse_version_and_port(rest)?;
+
+    // Ports were discontinued with 4.0, and a port without a version is meaningless
+    if jurisdiction.is_some() && matches!(version, None | Some(CcVersion::V4_0)) {
+        return None;
+    }
+
+    Some(License::CreativeCommons(CcLicense { elements, version, jurisdiction }))
+}
+
+fn parse_public_domain(tokens: &[&str]) -> Option<License> {
+    let rest = match tokens {
+        ["PDM", rest @ ..] => rest,
+        ["PUBLIC", "DOMAIN", "MARK", rest @ ..] => rest,
+        _ => return None,
+    };
+    match rest {
+        [] => Some(License::PublicDomainMark { version: None }),
+        ["1.0" | "1"] => Some(License::PublicDomainMark { version: Some(CcVersion::V1_0) }),
+        _ => None,
+    }
+}
+
+fn parse_bgml(tokens: &[&str]) -> Option<License> {
+    // SPDX documents refer to non-listed licenses as `LicenseRef-<name>`
+    let tokens = match tokens {
+        ["LICENSEREF", rest @ ..] => rest,
+        _ => tokens,
+    };
+    match tokens {
+        ["BGML"] => Some(License::Bgml(BgmlVariant::Standard)),
+        ["BGML", "P"] => Some(License::Bgml(BgmlVariant::Permissive)),
+        ["BGML", suffix] if suffix.chars().all(|c| c.is_ascii_alphanumeric()) => Some(License::Bgml(BgmlVariant::Other(suffix.to_string()))),
+        _ => None,
+    }
+}
+
+fn parse_all_rights_reserved(tokens: &[&str]) -> Option<License> {
+    match tokens {
+        ["ALL", "RIGHTS", "RESERVED"] | ["ARR"] => Some(License::AllRightsReserved),
+        _ => None,
+    }
+}
+
+impl fmt::Display for License {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            License::Unspecified => Ok(()),
+            License::CreativeCommons(cc) => {
+                write!(f, "CC {}", cc.elements.as_str())?;
+                if let Some(version) = cc.version {
+                    write!(f, " {}", version.as_str())?;
+                }
+                if let Some(jurisdiction) = &cc.jurisdiction {
+                    write!(f, " {}", jurisdiction)?;
+                }
+                Ok(())
+            }
+            License::Cc0 { version } => match version {
+                Some(version) => write!(f, "CC0 {}", version.as_str()),
+                None => write!(f, "CC0"),
+            },
+            License::PublicDomainMark { version } => match version {
+                Some(version) => write!(f, "Public Domain Mark {}", version.as_str()),
+                None => write!(f, "Public Domain Mark"),
+            },
+            License::Bgml(BgmlVariant::Standard) => write!(f, "BGML"),
+            License::Bgml(BgmlVariant::Permissive) => write!(f, "BGML-P"),
+            License::Bgml(BgmlVariant::Other(suffix)) => write!(f, "BGML-{}", suffix),
+            License::AllRightsReserved => write!(f, "All Rights Reserved"),
+            License::Custom(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+impl FromStr for License {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(License::parse(s))
+    }
+}
+
+/// Licenses serialize as their canonical string so JSON stays human-editable
+impl Serialize for License {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for License {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Ok(License::parse(&text))
+    }
+}
+
+/// A license expression: a single license or an SPDX-style compound of licenses
+/// Operands may be SPDX ids (`CC-BY-4.0`) or legacy names (`CC BY 4.0`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LicenseExpression {
+    License(License),
+    /// License plus an SPDX exception id, e.g. `GPL-2.0 WITH Classpath-exception-2.0`
+    With(Box<LicenseExpression>, String),
+    /// Both sides apply at once
+    And(Box<LicenseExpression>, Box<LicenseExpression>),
+    /// The licensee may pick either side
+    Or(Box<LicenseExpression>, Box<LicenseExpression>),
+}
+
+/// Lexical tokens of a license expression
+#[derive(Debug, Clone, PartialEq)]
+enum ExprToken {
+    /// Run of words between operators, e.g. `CC BY 4.0`
+    Term(String),
+    And,
+    Or,
+    With,
+    Open,
+    Close,
+}
+
+/// Splits an expression into tokens; operators are case-sensitive as in SPDX
+fn lex_expression(text: &str) -> Vec<ExprToken> {
+    let spaced = text.replace('(', " ( ").replace(')', " ) ");
+    let mut tokens = Vec::new();
+    let mut term: Vec<&str> = Vec::new();
+    for word in spaced.split_whitespace() {
+        let token = match word {
+            "AND" => ExprToken::And,
+            "OR" => ExprToken::Or,
+            "WITH" => ExprToken::With,
+            "(" => ExprToken::Open,
+            ")" => ExprToken::Close,
+            _ => {
+                term.push(word);
+                continue;
+            }
+        };
+        if !term.is_empty() {
+            tokens.push(ExprToken::Term(term.join(" ")));
+            term.clear();
+        }
+        tokens.push(token);
+    }
+    if !term.is_empty() {
+        tokens.push(ExprToken::Term(term.join(" ")));
+    }
+    tokens
+}
+
+/// Deepest parenthesis nesting accepted in an expression
+const MAX_EXPRESSION_DEPTH: usize = 16;
+/// Most licenses accepted in one expression; operator chains nest as deeply
+/// as they are long, so this also bounds the depth of the parsed tree
+const MAX_EXPRESSION_LICENSES: usize = 64;
+
+/// Recursive descent parser; AND binds tighter than OR, WITH tighter than both
+struct ExprParser {
+    tokens: Vec<ExprToken>,
+    pos: usize,
+    /// Parentheses currently open
+    depth: usize,
+}
+
+impl ExprParser {
+    fn next(&mut self) -> Option<ExprToken> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, expected: &ExprToken) -> bool {
+        if self.tokens.get(self.pos) == Some(expected) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn or_expr(&mut self) -> Result<LicenseExpression, String> {
+        let mut left = self.and_expr()?;
+        while self.eat(&ExprToken::Or) {
+            left = LicenseExpression::Or(Box::new(left), Box::new(self.and_expr()?));
+        }
+        Ok(left)
+    }
+
+    fn and_expr(&mut self) -> Result<LicenseExpression, String> {
+        let mut left = self.with_expr()?;
+        while self.eat(&ExprToken::And) {
+            left = LicenseExpression::And(Box::new(left), Box::new(self.with_expr()?));
+        }
+        Ok(left)
+    }
+
+    fn with_expr(&mut self) -> Result<LicenseExpression, String> {
+        let base = self.primary()?;
+        if !self.eat(&ExprToken::With) {
+            return Ok(base);
+        }
+        match self.next() {
+            Some(ExprToken::Term(exception)) => Ok(LicenseExpression::With(Box::new(base), exception)),
+            _ => Err("Expected an exception id after WITH".to_string()),
+        }
+    }
+
+    fn primary(&mut self) -> Result<LicenseExpression, String> {
+        match self.next() {
+            Some(ExprToken::Term(term)) => Ok(LicenseExpression::License(License::parse(&term))),
+            Some(ExprToken::Open) => {
+                if self.depth == MAX_EXPRESSION_DEPTH {
+                    return Err(format!("Parentheses nested more than {} deep", MAX_EXPRESSION_DEPTH));
+                }
+                self.depth += 1;
+                let inner = self.or_expr()?;
+                self.depth -= 1;
+                if !self.eat(&ExprToken::Close) {
+                    return Err("Missing closing parenthesis".to_string());
+                }
+                Ok(inner)
+            }
+            Some(ExprToken::Close) => Err("Unexpected closing parenthesis".to_string()),
+            Some(_) => Err("Expected a license before operator".to_string()),
+            None => Err("Expected a license at end of expression".to_string()),
+        }
+    }
+}
+
+impl LicenseExpression {
+    /// Parses an SPDX-style expression, returning a readable message on syntax errors
+    /// A string without operators is a single license (and never fails)
+    pub fn parse(text: &str) -> Result<LicenseExpression, String> {
+        let tokens = lex_expression(text);
+        if tokens.is_empty() {
+            return Ok(LicenseExpression::License(License::Unspecified));
+        }
+
+        if tokens.iter().filter(|token| matches!(token, ExprToken::Term(_))).count() > MAX_EXPRESSION_LICENSES {
+            return Err(format!("More than {} licenses in one expression", MAX_EXPRESSION_LICENSES));
+        }
+
+        let mut parser = ExprParser { tokens, pos: 0, depth: 0 };
+        let expression = parser.or_expr()?;
+        if parser.pos < parser.tokens.len() {
+            return Err(format!("Unexpected {:?} after complete expression", parser.tokens[parser.pos]));
+        }
+        Ok(expression)
+    }
+
+    /// Parses like `parse`, but treats malformed expressions, and compounds
+    /// with unrecognised operands, as a single custom license text
+    pub fn parse_lenient(text: &str) -> LicenseExpression {
+        match LicenseExpression::parse(text) {
+            Ok(expression @ LicenseExpression::License(_)) => expression,
+            Ok(expression) if !expression.licenses().iter().any(|l| matches!(l, License::Custom(_))) => expression,
+            _ => LicenseExpression::License(License::parse(text)),
+        }
+    }
+
+    /// Every license mentioned in the expression, left to right
+    pub fn licenses(&self) -> Vec<&License> {
+        match self {
+            LicenseExpression::License(license) => vec![license],
+            LicenseExpression::With(base, _) => base.licenses(),
+            LicenseExpression::And(left, right) | LicenseExpression::Or(left, right) => {
+                let mut licenses = left.licenses();
+                licenses.extend(right.licenses());
+                licenses
+            }
+        }
+    }
+
+    /// Family shared by every license in the expression, or `Mixed`
+    pub fn family(&self) -> LicenseFamily {
+        let mut families = self.licenses().into_iter().map(License::family);
+        let first = families.next().unwrap_or(LicenseFamily::Unspecified);
+        if families.all(|family| family == first) { first } else { LicenseFamily::Mixed }
+    }
+
+    /// Evaluates a per-license predicate over the expression:
+    /// OR needs either side, AND needs both, WITH is judged on its base license
+    /// (exceptions only ever grant extra permissions)
+    pub fn satisfies(&self, predicate: &impl Fn(&License) -> bool) -> bool {
+        match self {
+            LicenseExpression::License(license) => predicate(license),
+            LicenseExpression::With(base, _) => base.satisfies(predicate),
+            LicenseExpression::And(left, right) => left.satisfies(predicate) && right.satisfies(predicate),
+            LicenseExpression::Or(left, right) => left.satisfies(predicate) || right.satisfies(predicate),
+        }
+    }
+
+    /// SPDX form of the expression with legacy names mapped to SPDX ids
+    /// Returns `None` if any operand has no SPDX id
+    pub fn to_spdx(&self) -> Option<String> {
+        self.render(&|license| license.spdx_id())
+    }
+
+    fn render(&self, leaf: &impl Fn(&License) -> Option<String>) -> Option<String> {
+        // OR inside AND (and any compound inside WITH) needs parentheses
+        let wrap = |expr: &LicenseExpression, in_and: bool| -> Option<String> {
+            let text = expr.render(leaf)?;
+            Some(match expr {
+                LicenseExpression::Or(..) if in_and => format!("({})", text),
+                _ => text,
+            })
+        };
+        match self {
+            LicenseExpression::License(license) => leaf(license),
+            LicenseExpression::With(base, exception) => match **base {
+                LicenseExpression::License(_) => Some(format!("{} WITH {}", base.render(leaf)?, exception)),
+                _ => Some(format!("({}) WITH {}", base.render(leaf)?, exception)),
+            },
+            LicenseExpression::And(left, right) => Some(format!("{} AND {}", wrap(left, true)?, wrap(right, true)?)),
+            LicenseExpression::Or(left, right) => Some(format!("{} OR {}", wrap(left, false)?, wrap(right, false)?)),
+        }
+    }
+}
+
+impl fmt::Display for LicenseExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = self.render(&|license| Some(license.to_string())).unwrap_or_default();
+        f.write_str(&text)
+    }
+}
+
+impl Serialize for LicenseExpression {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for LicenseExpression {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Ok(LicenseExpression::parse_lenient(&text))
+    }
+}
+
+// `License` and `LicenseExpression` serialize as their canonical strings
+#[wasm_bindgen(typescript_custom_section)]
+const LICENSE_TS: &'static str = r#"
+/** A single license in canonical form, e.g. "CC BY 4.0" */
+export type License = string;
+/** A license expression in canonical form, e.g. "CC BY 4.0 OR BGML-P" */
+export type LicenseExpression = string;
+"#;
+
+/// Returns the canonical spelling of a license string or expression
+/// e.g. "cc by-sa 4.0" becomes "CC BY-SA 4.0"; unknown text is only trimmed,
+/// and so is text whose canonical spelling would parse differently
+#[wasm_bindgen]
+pub fn canonicalize_license(license: &str) -> String {
+    let expression = LicenseExpression::parse_lenient(license);
+    let canonical = expression.to_string();
+    if LicenseExpression::parse_lenient(&canonical) != expression {
+        return license.trim().to_string();
+    }
+    canonical
+}
+
+/// Converts a license string or expression to SPDX form
+/// e.g. "CC BY 4.0 OR BGML-P" becomes "CC-BY-4.0 OR LicenseRef-BGML-P";
+/// returns an empty string if some license has no SPDX id
+#[wasm_bindgen]
+pub fn license_to_spdx(license: &str) -> String {
+    LicenseExpression::parse_lenient(license).to_spdx().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cc(elements: CcElements, version: Option<CcVersion>, jurisdiction: Option<&str>) -> License {
+        License::CreativeCommons(CcLicense { elements, version, jurisdiction: jurisdiction.map(str::to_string) })
+    }
+
+    #[test]
+    fn test_parse_all_cc_variants() {
+        assert_eq!(License::parse("CC BY 4.0"), cc(CcElements::By, Some(CcVersion::V4_0), None));
+        assert_eq!(License::parse("CC BY-SA 3.0"), cc(CcElements::BySa, Some(CcVersion::V3_0), None));
+        assert_eq!(License::parse("CC BY-NC 2.5"), cc(CcElements::ByNc, Some(CcVersion::V2_5), None));
+        assert_eq!(License::parse("CC BY-ND 2.0"), cc(CcElements::ByNd, Some(CcVersion::V2_0), None));
+        assert_eq!(License::parse("CC BY-NC-SA 1.0"), cc(CcElements::ByNcSa, Some(CcVersion::V1_0), None));
+        assert_eq!(License::parse("CC BY-NC-ND 2.1"), cc(CcElements::ByNcNd, Some(CcVersion::V2_1), None));
+        assert_eq!(License::parse("CC BY"), cc(CcElements::By, None, None));
+    }
+
+    #[test]
+    fn test_parse_alternative_spellings() {
+        let by_sa = cc(CcElements::BySa, Some(CcVersion::V4_0), None);
+        assert_eq!(License::parse("cc by-sa 4.0"), by_sa);
+        assert_eq!(License::parse("  CC  BY-SA   4.0 "), by_sa);
+        assert_eq!(License::parse("CC-BY-SA-4.0"), by_sa);
+        assert_eq!(License::parse("CC BY-SA 4.0 International"), by_sa);
+        assert_eq!(License::parse("Creative Commons Attribution-ShareAlike 4.0 International"), by_sa);
+        assert_eq!(License::parse("CC SA-BY 4.0"), License::Custom("CC SA-BY 4.0".to_string()));
+        assert_eq!(
+            License::parse("Creative Commons Attribution-NonCommercial-NoDerivatives 4.0"),
+            cc(CcElements::ByNcNd, Some(CcVersion::V4_0), None)
+        );
+        assert_eq!(License::parse("CC BY-NC-SA 3.0 Unported"), cc(CcElements::ByNcSa, Some(CcVersion::V3_0), None));
+    }
+
+    #[test]
+    fn test_parse_jurisdiction_ports() {
+        assert_eq!(License::parse("CC BY 3.0 DE"), cc(CcElements::By, Some(CcVersion::V3_0), Some("DE")));
+        assert_eq!(License::parse("cc-by-sa-2.0-fr"), cc(CcElements::BySa, Some(CcVersion::V2_0), Some("FR")));
+        assert_eq!(License::parse("CC BY 3.0 IGO"), cc(CcElements::By, Some(CcVersion::V3_0), Some("IGO")));
+
+        // No ports exist for 4.0, and a port needs a version
+        assert!(matches!(License::parse("CC BY 4.0 DE"), License::Custom(_)));
+        assert!(matches!(License::parse("CC BY DE"), License::Custom(_)));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_cc() {
+        for text in ["CC BY 5.0", "CC BY-SA-ND 4.0", "CC BY-BY 4.0", "CC NC 4.0", "CC BY 4.0 extra words"] {
+            assert_eq!(License::parse(text), License::Custom(text.to_string()), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_public_domain() {
+        assert_eq!(License::parse("CC0"), License::Cc0 { version: None });
+        assert_eq!(License::parse("CC0 1.0"), License::Cc0 { version: Some(CcVersion::V1_0) });
+        assert_eq!(License::parse("cc0-1.0"), License::Cc0 { version: Some(CcVersion::V1_0) });
+        assert_eq!(License::parse("CC0 1.0 Universal"), License::Cc0 { version: Some(CcVersion::V1_0) });
+        assert_eq!(License::parse("CC Zero"), License::Cc0 { version: None });
+        assert!(matches!(License::parse("CC0 2.0"), License::Custom(_)));
+
+        assert_eq!(License::parse("Public Domain Mark 1.0"), License::PublicDomainMark { version: Some(CcVersion::V1_0) });
+        assert_eq!(License::parse("PDM"), License::PublicDomainMark { version: None });
+
+        // Bare "Public Domain" could mean the mark or a dedication such as CC0
+        assert_eq!(License::parse("public domain"), License::Custom("public domain".to_string()));
+        assert_eq!(License::parse("Public Domain 1.0"), License::Custom("Public Domain 1.0".to_string()));
+        assert_eq!(canonicalize_license(" public domain "), "public domain");
+        assert_eq!(canonicalize_license("Public Domain OR cc by 4.0"), "Public Domain OR cc by 4.0");
+        assert_eq!(canonicalize_license("public domain mark 1.0"), "Public Domain Mark 1.0");
+    }
+
+    #[test]
+    fn test_parse_bgml_and_other() {
+        assert_eq!(License::parse("BGML-P"), License::Bgml(BgmlVariant::Permissive));
+        assert_eq!(License::parse(" bgml-p "), License::Bgml(BgmlVariant::Permissive));
+        assert_eq!(License::parse("BGML"), License::Bgml(BgmlVariant::Standard));
+        assert_eq!(License::parse("BGML-R"), License::Bgml(BgmlVariant::Other("R".to_string())));
+
+        assert_eq!(License::parse("All Rights Reserved"), License::AllRightsReserved);
+        assert_eq!(License::parse("all rights reserved"), License::AllRightsReserved);
+        assert_eq!(License::parse(""), License::Unspecified);
+        assert_eq!(License::parse("   "), License::Unspecified);
+        assert_eq!(License::parse(" Free for streams only "), License::Custom("Free for streams only".to_string()));
+    }
+
+    #[test]
+    fn test_canonical_display_round_trips() {
+        for (input, canonical) in [
+            ("cc by 4.0", "CC BY 4.0"),
+            ("CC-BY-NC-SA-3.0-de", "CC BY-NC-SA 3.0 DE"),
+            ("Creative Commons Attribution 3.0 Unported", "CC BY 3.0"),
+            ("cc0 1.0 universal", "CC0 1.0"),
+            ("pdm 1.0", "Public Domain Mark 1.0"),
+            ("bgml-p", "BGML-P"),
+            ("bgml-x2", "BGML-X2"),
+            ("ALL RIGHTS RESERVED", "All Rights Reserved"),
+            ("", ""),
+            ("My own license", "My own license"),
+        ] {
+            let license = License::parse(input);
+            assert_eq!(license.to_string(), canonical);
+            assert_eq!(License::parse(canonical), license);
+            assert_eq!(canonicalize_license(input), canonical);
+        }
+    }
+
+    #[test]
+    fn test_serde_uses_canonical_string() {
+        let license: License = serde_json::from_str("\"cc by-sa 3.0\"").unwrap();
+        assert_eq!(license, cc(CcElements::BySa, Some(CcVersion::V3_0), None));
+        assert_eq!(serde_json::to_string(&license).unwrap(), "\"CC BY-SA 3.0\"");
+    }
+
+    #[test]
+    fn test_license_properties() {
+        assert!(License::parse("CC BY 4.0").is_commercial_cc());
+        assert!(License::parse("CC BY-SA").is_commercial_cc());
+        assert!(License::parse("CC0").is_commercial_cc());
+        assert!(!License::parse("Public Domain Mark 1.0").is_commercial_cc());
+        assert!(!License::parse("CC BY-NC 4.0").is_commercial_cc());
+        assert!(!License::parse("CC BY-ND 4.0").is_commercial_cc());
+        assert!(!License::parse("BGML-P").is_commercial_cc());
+
+        assert!(License::parse("BGML-P").is_bgml_permissive());
+        assert!(!License::parse("BGML").is_bgml_permissive());
+    }
+
+    #[test]
+    fn test_license_urls() {
+        assert_eq!(License::parse("CC BY-SA 4.0").url().as_deref(), Some("https://creativecommons.org/licenses/by-sa/4.0/"));
+        assert_eq!(License::parse("CC BY 3.0 DE").url().as_deref(), Some("https://creativecommons.org/licenses/by/3.0/de/"));
+        assert_eq!(License::parse("CC0 1.0").url().as_deref(), Some("https://creativecommons.org/publicdomain/zero/1.0/"));
+        assert_eq!(License::parse("CC BY").url(), None);
+        assert_eq!(License::parse("BGML-P").url(), None);
+    }
+
+    #[test]
+    fn test_spdx_ids() {
+        assert_eq!(License::parse("CC BY 4.0").spdx_id().as_deref(), Some("CC-BY-4.0"));
+        assert_eq!(License::parse("CC BY-NC-SA 3.0 DE").spdx_id().as_deref(), Some("CC-BY-NC-SA-3.0-DE"));
+        assert_eq!(License::parse("CC0").spdx_id().as_deref(), Some("CC0-1.0"));
+        assert_eq!(License::parse("BGML-P").spdx_id().as_deref(), Some("LicenseRef-BGML-P"));
+        assert_eq!(License::parse("LicenseRef-BGML-P"), License::Bgml(BgmlVariant::Permissive));
+        assert_eq!(License::parse("CC BY").spdx_id(), None);
+        assert_eq!(License::parse("All Rights Reserved").spdx_id(), None);
+    }
+
+    #[test]
+    fn test_parse_compound_expressions() {
+        let by = LicenseExpression::License(License::parse("CC BY 4.0"));
+        let bgml = LicenseExpression::License(License::Bgml(BgmlVariant::Permissive));
+
+        assert_eq!(
+            LicenseExpression::parse("CC-BY-4.0 OR BGML-P").unwrap(),
+            LicenseExpression::Or(Box::new(by.clone()), Box::new(bgml.clone()))
+        );
+        // Legacy names work as operands too
+        assert_eq!(
+            LicenseExpression::parse("CC BY 4.0 OR BGML-P").unwrap(),
+            LicenseExpression::Or(Box::new(by.clone()), Box::new(bgml.clone()))
+        );
+
+        // AND binds tighter than OR
+        let expr = LicenseExpression::parse("CC0-1.0 OR CC-BY-4.0 AND BGML-P").unwrap();
+        assert!(matches!(&expr, LicenseExpression::Or(_, right) if matches!(**right, LicenseExpression::And(..))));
+
+        let expr = LicenseExpression::parse("(CC0-1.0 OR CC-BY-4.0) AND BGML-P").unwrap();
+        assert!(matches!(&expr, LicenseExpression::And(left, _) if matches!(**left, LicenseExpression::Or(..))));
+
+        let expr = LicenseExpression::parse("GPL-2.0-only WITH Classpath-exception-2.0").unwrap();
+        assert!(matches!(&expr, LicenseExpression::With(_, exception) if exception == "Classpath-exception-2.0"));
+    }
+
+    #[test]
+    fn test_parse_expression_errors() {
+        for text in ["CC-BY-4.0 OR", "AND BGML-P", "(CC-BY-4.0 OR BGML-P", "CC-BY-4.0)", "MIT WITH", "CC-BY-4.0 OR OR BGML-P"] {
+            assert!(LicenseExpression::parse(text).is_err(), "{}", text);
+        }
+
+        // Lenient parsing falls back to one custom license
+        assert_eq!(
+            LicenseExpression::parse_lenient("CC-BY-4.0 OR"),
+            LicenseExpression::License(License::Custom("CC-BY-4.0 OR".to_string()))
+        );
+        assert_eq!(
+            LicenseExpression::parse_lenient("Free for streams OR videos"),
+            LicenseExpression::License(License::Custom("Free for streams OR videos".to_string()))
+        );
+        // Lowercase words are not operators
+        assert_eq!(
+            LicenseExpression::parse_lenient("free for streams and videos"),
+            LicenseExpression::License(License::Custom("free for streams and videos".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_expression_limits() {
+        let nested = format!("{}MIT{}", "(".repeat(MAX_EXPRESSION_DEPTH), ")".repeat(MAX_EXPRESSION_DEPTH));
+        assert!(LicenseExpression::parse(&nested).is_ok());
+        let nested = format!("({})", nested);
+        assert!(LicenseExpression::parse(&nested).unwrap_err().contains("nested"));
+
+        let chain = vec!["MIT"; MAX_EXPRESSION_LICENSES + 1].join(" OR ");
+        assert!(LicenseExpression::parse(&chain).unwrap_err().contains("licenses"));
+
+        // Hostile input is kept as custom text rather than overflowing the stack
+        let hostile = "(".repeat(100_000) + "MIT";
+        assert_eq!(canonicalize_license(&hostile), hostile);
+        let hostile = vec!["MIT"; 100_000].join(" OR ");
+        assert_eq!(canonicalize_license(&hostile), hostile);
+    }
+
+    #[test]
+    fn test_expression_satisfies() {
+        let commercial = |l: &License| l.is_commercial_cc();
+        assert!(LicenseExpression::parse_lenient("CC-BY-NC-4.0 OR CC-BY-4.0").satisfies(&commercial));
+        assert!(!LicenseExpression::parse_lenient("CC-BY-NC-4.0 AND CC-BY-4.0").satisfies(&commercial));
+        assert!(LicenseExpression::parse_lenient("CC-BY-4.0 WITH Some-exception").satisfies(&commercial));
+        assert!(!LicenseExpression::parse_lenient("All Rights Reserved").satisfies(&commercial));
+    }
+
+    #[test]
+    fn test_license_families() {
+        let family = |text: &str| LicenseExpression::parse_lenient(text).family();
+        assert_eq!(family("CC BY-NC 4.0"), LicenseFamily::CreativeCommons);
+        assert_eq!(family("CC0"), LicenseFamily::PublicDomain);
+        assert_eq!(family("bgml-p"), LicenseFamily::Bgml);
+        assert_eq!(family("All Rights Reserved"), LicenseFamily::AllRightsReserved);
+        assert_eq!(family("free for streams"), LicenseFamily::Custom);
+        assert_eq!(family(""), LicenseFamily::Unspecified);
+        assert_eq!(family("CC BY 4.0 OR CC BY-SA 4.0"), LicenseFamily::CreativeCommons);
+        assert_eq!(family("CC BY 4.0 OR BGML-P"), LicenseFamily::Mixed);
+        assert_eq!(LicenseFamily::Bgml.as_str(), "BGML");
+    }
+
+    #[test]
+    fn test_expression_canonical_and_spdx_forms() {
+        assert_eq!(canonicalize_license("cc-by-4.0 OR bgml-p"), "CC BY 4.0 OR BGML-P");
+        assert_eq!(canonicalize_license("(cc0 OR cc by 4.0) AND bgml-p"), "(CC0 OR CC BY 4.0) AND BGML-P");
+        assert_eq!(canonicalize_license("cc by 4.0"), "CC BY 4.0");
+
+        assert_eq!(license_to_spdx("CC BY 4.0"), "CC-BY-4.0");
+        assert_eq!(license_to_spdx("CC BY 4.0 OR BGML-P"), "CC-BY-4.0 OR LicenseRef-BGML-P");
+        assert_eq!(license_to_spdx("(CC0 1.0 OR CC BY-SA 3.0) AND BGML-P"), "(CC0-1.0 OR CC-BY-SA-3.0) AND LicenseRef-BGML-P");
+        assert_eq!(license_to_spdx("All Rights Reserved"), "");
+
+        let expr: LicenseExpression = serde_json::from_str("\"cc-by-4.0 OR bgml-p\"").unwrap();
+        assert_eq!(serde_json::to_string(&expr).unwrap(), "\"CC BY 4.0 OR BGML-P\"");
+    }
+}
@@ -0,0 +1,242 @@
+use wasm_bindgen::prelude::*;
+use serde_json::{Map, Value};
+use crate::grouping::AlbumInfo;
+use crate::license::canonicalize_license;
+use crate::sorting::{Collation, CollationKey};
+use crate::validation::{check_music_data, check_songs, MusicData, Song};
+
+/// Trims every string in `value` and sorts the keys of every object, recursively
+fn normalize_value(value: &mut Value) {
+    match value {
+        Value::String(text) => trim_in_place(text),
+        Value::Array(items) => items.iter_mut().for_each(normalize_value),
+        Value::Object(map) => normalize_map(map),
+        _ => {}
+    }
+}
+
+fn normalize_map(map: &mut Map<String, Value>) {
+    map.sort_keys();
+    map.values_mut().for_each(normalize_value);
+}
+
+fn trim_in_place(text: &mut String) {
+    if text.trim().len() != text.len() {
+        *text = text.trim().to_string();
+    }
+}
+
+/// Rewrites a song into canonical form
+/// Strings are trimmed, the license is respelled canonically ("cc by 4.0"
+/// becomes "CC BY 4.0") unless that could change its meaning, and unmodelled
+/// keys are sorted
+pub fn normalize_song(song: &mut Song) {
+    trim_in_place(&mut song.id);
+    trim_in_place(&mut song.title);
+    trim_in_place(&mut song.album_name);
+    if let Some(album_id) = &mut song.album_id {
+        trim_in_place(album_id);
+    }
+    trim_in_place(&mut song.streaming_link);
+    song.license = canonicalize_license(&song.license);
+    if let Some(artwork) = &mut song.album_artwork {
+        trim_in_place(artwork);
+    }
+    normalize_map(&mut song.extra);
+}
+
+/// Sort key for canonical order: IDs compared naturally, so "song-2" comes
+/// before "song-10", then byte for byte to keep the order total
+fn id_order(id: &str) -> (CollationKey, String) {
+    (Collation::default().key(id), id.to_string())
+}
+
+/// Normalizes every song and orders them by ID, naturally
+pub fn normalize_songs(songs: &mut [Song]) {
+    songs.iter_mut().for_each(normalize_song);
+    songs.sort_by_cached_key(|song| id_order(&song.id));
+}
+
+/// Rewrites a declared album into canonical form: strings are trimmed and
+/// unmodelled keys sorted
+pub fn normalize_album(album: &mut AlbumInfo) {
+    trim_in_place(&mut album.id);
+    trim_in_place(&mut album.name);
+    let metadata = &mut album.metadata;
+    for text in [&mut metadata.artwork, &mut metadata.release_date, &mut metadata.description, &mut metadata.upc].into_iter().flatten() {
+        trim_in_place(text);
+    }
+    normalize_map(&mut album.extra);
+}
+
+/// Rewrites music data into canonical form
+/// Songs and albums are normalized and ordered by ID, naturally, and unmodelled
+/// top-level keys are sorted
+pub fn normalize_music_data(data: &mut MusicData) {
+    normalize_songs(&mut data.songs);
+    data.albums.iter_mut().for_each(normalize_album);
+    data.albums.sort_by_cached_key(|album| id_order(&album.id));
+    normalize_map(&mut data.extra);
+}
+
+/// Pretty-prints `value` the way `music.json` is stored: two-space indents and
+/// a trailing newline
+fn to_pretty_json(value: &Value) -> String {
+    format!("{}\n", serde_json::to_string_pretty(value).unwrap_or_default())
+}
+
+/// Formats `music.json` (or a bare array of songs) canonically
+/// Fails if the input doesn't pass validation, since only valid data can be
+/// normalized. An empty `albums` list is left out, as it is when omitted, and
+/// a `$schema` key comes first, where editors expect it
+pub fn format_music_json(text: &str) -> Result<String, String> {
+    let is_song_array = text.trim_start().starts_with('[');
+    let report = if is_song_array { check_songs(text) } else { check_music_data(text) };
+    if let Some(error) = report.first_error() {
+        return Err(format!("Cannot format invalid music data: {}", error.message));
+    }
+
+    let value = if is_song_array {
+        let mut songs: Vec<Song> = serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+        normalize_songs(&mut songs);
+        serde_json::to_value(songs)
+    } else {
+        let mut data: MusicData = serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+        normalize_music_data(&mut data);
+        serde_json::to_value(&data).map(|value| {
+            let Value::Object(mut object) = value else { return value };
+            if data.albums.is_empty() {
+                object.shift_remove("albums");
+            }
+            let mut ordered = Map::new();
+            if let Some(schema) = object.shift_remove("$schema") {
+                ordered.insert("$schema".to_string(), schema);
+            }
+            ordered.extend(object);
+            Value::Object(ordered)
+        })
+    };
+
+    value.map(|value| to_pretty_json(&value)).map_err(|e| format!("Serialization error: {}", e))
+}
+
+/// Whether `text` is already exactly what `format_music_json` would produce
+pub fn is_canonical_music_json(text: &str) -> Result<bool, String> {
+    format_music_json(text).map(|formatted| formatted == text)
+}
+
+/// Formats `music.json` text canonically: trimmed strings, canonical license
+/// spellings, songs ordered by ID, stable key order and pretty-printing
+/// Throws if the data is invalid
+#[wasm_bindgen(js_name = formatMusicJson)]
+pub fn format_music_json_js(text: &str) -> Result<String, JsError> {
+    format_music_json(text).map_err(|e| JsError::new(&e))
+}
+
+/// Whether `music.json` text is already canonically formatted
+/// Throws if the data is invalid
+#[wasm_bindgen(js_name = isCanonicalMusicJson)]
+pub fn is_canonical_music_json_js(text: &str) -> Result<bool, JsError> {
+    is_canonical_music_json(text).map_err(|e| JsError::new(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song_json(id: &str, title: &str, license: &str) -> String {
+        format!(
+            r#"{{"license": "{}", "id": "{}", "title": "{}", "albumName": " Album ", "releaseType": "NCS", "hasContentId": false, "streamingLink": "https://example.com"}}"#,
+            license, id, title
+        )
+    }
+
+    #[test]
+    fn test_normalize_song() {
+        let mut song: Song = serde_json::from_str(&song_json(" b ", "  Title ", "cc-by-sa-4.0")).unwrap();
+        song.extra.insert("zeta".to_string(), Value::from(" z "));
+        song.extra.insert("alpha".to_string(), serde_json::json!({ "y": 1, "x": [" x "] }));
+        normalize_song(&mut song);
+
+        assert_eq!((song.id.as_str(), song.title.as_str(), song.album_name.as_str()), ("b", "Title", "Album"));
+        assert_eq!(song.license, "CC BY-SA 4.0");
+        assert_eq!(song.extra.keys().collect::<Vec<_>>(), ["alpha", "zeta"]);
+        assert_eq!(serde_json::to_string(&song.extra).unwrap(), r#"{"alpha":{"x":["x"],"y":1},"zeta":"z"}"#);
+    }
+
+    #[test]
+    fn test_normalize_keeps_ambiguous_license() {
+        // Respelling "Public Domain" as "Public Domain Mark" would name a different instrument
+        let mut song: Song = serde_json::from_str(&song_json("a", "T", " Public Domain ")).unwrap();
+        normalize_song(&mut song);
+        assert_eq!(song.license, "Public Domain");
+    }
+
+    #[test]
+    fn test_format_music_json() {
+        let text = format!(r#"{{"$schema": "x", "songs": [{}, {}]}}"#, song_json("song-002", "B", "cc by 4.0"), song_json("song-001", "A", " "));
+        let formatted = format_music_json(&text).unwrap();
+
+        let value: Value = serde_json::from_str(&formatted).unwrap();
+        assert_eq!(value.as_object().unwrap().keys().collect::<Vec<_>>(), ["$schema", "songs"]);
+        assert_eq!(value["songs"][0]["id"], "song-001");
+        assert_eq!(value["songs"][0]["license"], "");
+        assert_eq!(value["songs"][1]["license"], "CC BY 4.0");
+        assert_eq!(
+            value["songs"][0].as_object().unwrap().keys().collect::<Vec<_>>(),
+            ["id", "title", "albumName", "releaseType", "hasContentId", "streamingLink", "license"]
+        );
+        assert!(formatted.starts_with("{\n  \"$schema\": \"x\",\n  \"songs\": [\n    {\n      \"id\": \"song-001\",\n"));
+        assert!(formatted.ends_with("}\n"));
+
+        // Formatting is idempotent
+        assert_eq!(format_music_json(&formatted).unwrap(), formatted);
+        assert!(is_canonical_music_json(&formatted).unwrap());
+        assert!(!is_canonical_music_json(&text).unwrap());
+    }
+
+    #[test]
+    fn test_format_albums_and_song_arrays() {
+        let text = format!(
+            r#"{{"songs": [{}], "albums": [{{"upc": "036000291452", "name": "Z", "id": "album-2"}}, {{"id": "album-1 ", "name": " A", "albumType": "EP"}}]}}"#,
+            song_json("a", "T", "")
+        );
+        let formatted = format_music_json(&text).unwrap();
+        let value: Value = serde_json::from_str(&formatted).unwrap();
+        assert_eq!(value["albums"], serde_json::json!([
+            { "id": "album-1", "name": "A", "albumType": "EP" },
+            { "id": "album-2", "name": "Z", "upc": "036000291452" },
+        ]));
+        assert!(formatted.contains("{\n      \"id\": \"album-2\",\n      \"name\": \"Z\",\n      \"upc\""));
+
+        let text = format!("[{}, {}]", song_json("b", "T", "bgml-p"), song_json("a", "T", ""));
+        let value: Value = serde_json::from_str(&format_music_json(&text).unwrap()).unwrap();
+        assert_eq!(value[0]["id"], "a");
+        assert_eq!(value[1]["license"], "BGML-P");
+    }
+
+    #[test]
+    fn test_format_orders_ids_naturally() {
+        let songs: Vec<String> = ["song-10", "song-2", "song-1", "Song-2"].iter().map(|id| song_json(id, "T", "")).collect();
+        let text = format!(r#"{{"songs": [{}], "albums": [{{"id": "album-10", "name": "B"}}, {{"id": "album-9", "name": "A"}}]}}"#, songs.join(", "));
+        let value: Value = serde_json::from_str(&format_music_json(&text).unwrap()).unwrap();
+
+        let ids: Vec<&str> = value["songs"].as_array().unwrap().iter().map(|song| song["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, ["song-1", "song-2", "Song-2", "song-10"]);
+        assert_eq!(value["albums"][0]["id"], "album-9");
+    }
+
+    #[test]
+    fn test_format_rejects_invalid_data() {
+        let error = format_music_json(r#"{"songs": [{"id": "a"}]}"#).unwrap_err();
+        assert_eq!(error, "Cannot format invalid music data: Song at index 0 is invalid: Missing required field: title");
+        assert!(format_music_json("{").unwrap_err().starts_with("Cannot format invalid music data: Invalid JSON"));
+        assert!(is_canonical_music_json("[1]").is_err());
+    }
+
+    #[test]
+    fn test_site_data_is_canonical() {
+        let text = include_str!("../../src/data/music.json");
+        assert!(is_canonical_music_json(text).unwrap(), "src/data/music.json isn't canonical; run `musicctl fmt --write`");
+    }
+}
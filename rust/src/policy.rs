@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use crate::license::{BgmlVariant, License};
+use crate::validation::ReleaseType;
+
+/// Declares what counts as creator-friendly
+/// A song qualifies if its license OR its release type is allowed, and it
+/// isn't excluded by its Content ID state. The default policy is the site's
+/// built-in rule set: commercial CC, BGML-P or an NCS release, Content ID ignored
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct CreatorFriendlyPolicy {
+    /// Allow every commercial Creative Commons license (CC BY, CC BY-SA, CC0)
+    pub commercial_cc: bool,
+    /// Additional allowed licenses. A pattern without a version (e.g. "CC BY-NC")
+    /// matches every version, and one without a port matches every port
+    pub licenses: Vec<License>,
+    /// Release types that are creator-friendly regardless of license
+    pub release_types: Vec<ReleaseType>,
+    /// Whether songs registered with Content ID can be creator-friendly
+    pub allow_content_id: bool,
+}
+
+impl Default for CreatorFriendlyPolicy {
+    fn default() -> Self {
+        CreatorFriendlyPolicy {
+            commercial_cc: true,
+            licenses: vec![License::Bgml(BgmlVariant::Permissive)],
+            release_types: vec![ReleaseType::NCS],
+            allow_content_id: true,
+        }
+    }
+}
+
+impl CreatorFriendlyPolicy {
+    /// Loads a policy from JSON; missing keys keep their default values
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid policy JSON: {}", e))
+    }
+
+    /// Loads a policy from TOML; missing keys keep their default values
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|e| format!("Invalid policy TOML: {}", e))
+    }
+
+    /// Whether one (non-compound) license is allowed by this policy
+    pub fn allows_license(&self, license: &License) -> bool {
+        (self.commercial_cc && license.is_commercial_cc()) || self.licenses.iter().any(|pattern| license_matches(pattern, license))
+    }
+
+    pub fn allows_release_type(&self, release_type: &ReleaseType) -> bool {
+        self.release_types.contains(release_type)
+    }
+}
+
+/// Matches a license against a policy pattern, treating missing version or
+/// jurisdiction in the pattern as wildcards
+pub fn license_matches(pattern: &License, license: &License) -> bool {
+    match (pattern, license) {
+        (License::CreativeCommons(pattern), License::CreativeCommons(cc)) => {
+            pattern.elements == cc.elements
+                && pattern.version.is_none_or(|v| cc.version == Some(v))
+                && pattern.jurisdiction.as_ref().is_none_or(|j| cc.jurisdiction.as_ref() == Some(j))
+        }
+        (License::Cc0 { version: pattern }, License::Cc0 { version })
+        | (License::PublicDomainMark { version: pattern }, License::PublicDomainMark { version }) => {
+            pattern.is_none_or(|v| *version == Some(v))
+        }
+        _ => pattern == license,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_matches_builtin_rules() {
+        let policy = CreatorFriendlyPolicy::default();
+        assert!(policy.allows_license(&License::parse("CC BY 4.0")));
+        assert!(policy.allows_license(&License::parse("CC0")));
+        assert!(policy.allows_license(&License::parse("BGML-P")));
+        assert!(!policy.allows_license(&License::parse("CC BY-NC 4.0")));
+        assert!(!policy.allows_license(&License::parse("All Rights Reserved")));
+        assert!(policy.allows_release_type(&ReleaseType::NCS));
+        assert!(!policy.allows_release_type(&ReleaseType::Monstercat));
+        assert!(policy.allow_content_id);
+    }
+
+    #[test]
+    fn test_license_patterns() {
+        let by_nc = License::parse("CC BY-NC");
+        assert!(license_matches(&by_nc, &License::parse("CC BY-NC 4.0")));
+        assert!(license_matches(&by_nc, &License::parse("CC BY-NC 3.0 DE")));
+        assert!(!license_matches(&by_nc, &License::parse("CC BY-NC-SA 4.0")));
+
+        let by_nc_3 = License::parse("CC BY-NC 3.0");
+        assert!(license_matches(&by_nc_3, &License::parse("CC BY-NC 3.0 DE")));
+        assert!(!license_matches(&by_nc_3, &License::parse("CC BY-NC 4.0")));
+        assert!(!license_matches(&License::parse("CC BY-NC 3.0 DE"), &License::parse("CC BY-NC 3.0")));
+
+        assert!(license_matches(&License::parse("CC0"), &License::parse("CC0 1.0")));
+        assert!(license_matches(&License::parse("bgml"), &License::parse("BGML")));
+        assert!(!license_matches(&License::parse("BGML"), &License::parse("BGML-P")));
+    }
+
+    #[test]
+    fn test_policy_from_json() {
+        let policy = CreatorFriendlyPolicy::from_json(r#"{"licenses": ["BGML-P", "CC BY-NC"], "allowContentId": false}"#).unwrap();
+        assert!(policy.commercial_cc); // default kept
+        assert!(policy.allows_license(&License::parse("CC BY-NC 4.0")));
+        assert!(!policy.allow_content_id);
+        assert_eq!(policy.release_types, vec![ReleaseType::NCS]);
+
+        let error = CreatorFriendlyPolicy::from_json(r#"{"commercialCC": false}"#).unwrap_err();
+        assert!(error.contains("unknown field"));
+    }
+
+    #[test]
+    fn test_policy_from_toml() {
+        let policy = CreatorFriendlyPolicy::from_toml(
+            r#"
+            commercialCc = false
+            licenses = ["CC0", "BGML-P"]
+            releaseTypes = ["NCS", "Monstercat"]
+            allowContentId = false
+            "#,
+        )
+        .unwrap();
+        assert!(!policy.allows_license(&License::parse("CC BY 4.0")));
+        assert!(policy.allows_license(&License::parse("CC0 1.0")));
+        assert!(policy.allows_release_type(&ReleaseType::Monstercat));
+
+        assert!(CreatorFriendlyPolicy::from_toml("releaseTypes = [\"Bandcamp\"]").is_err());
+    }
+
+    #[test]
+    fn test_policy_round_trips_through_json() {
+        let policy = CreatorFriendlyPolicy::default();
+        let json = serde_json::to_string(&policy).unwrap();
+        assert_eq!(json, r#"{"commercialCc":true,"licenses":["BGML-P"],"releaseTypes":["NCS"],"allowContentId":true}"#);
+        assert_eq!(CreatorFriendlyPolicy::from_json(&json).unwrap(), policy);
+    }
+}
@@ -0,0 +1,614 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use serde_json::json;
+use std::fmt;
+use crate::filters::is_creator_friendly_with;
+use crate::js::{from_js, from_js_optional, to_js};
+use crate::license::{License, LicenseFamily};
+use crate::policy::{license_matches, CreatorFriendlyPolicy};
+use crate::search::{tokenize, SearchField};
+use crate::text::{edit_distance, fold};
+use crate::validation::{ReleaseType, Song};
+
+/// Field names accepted before a `:`, aliases aside
+const FIELDS: [&str; 8] = ["title", "album", "year", "type", "license", "contentid", "creator", "id"];
+
+/// Deepest nesting of parentheses and NOTs a query may use; parsing, matching
+/// and dropping a query recurse once per level
+const MAX_QUERY_DEPTH: usize = 64;
+
+/// Inclusive range of release years; either end may be open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+pub struct YearRange {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub from: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub to: Option<u32>,
+}
+
+impl YearRange {
+    pub fn contains(&self, year: u32) -> bool {
+        self.from.is_none_or(|from| year >= from) && self.to.is_none_or(|to| year <= to)
+    }
+}
+
+/// A single test against a song
+/// Text is matched word by word, ignoring case and accents; the last word may
+/// be the beginning of a word, so `title:neo` matches "Neon Nights"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+#[serde(tag = "field", content = "value", rename_all = "camelCase")]
+pub enum QueryPredicate {
+    /// Words (or a quoted phrase) in the title, album, license or release type
+    Text(String),
+    Title(String),
+    Album(String),
+    Year(YearRange),
+    ReleaseType(ReleaseType),
+    /// License as typed: a license name such as `cc-by` (matching every
+    /// version, like policy patterns), a family (`cc`, `pd`, `bgml`, `arr`,
+    /// `custom`, `none`) or other text the license contains
+    License(String),
+    ContentId(bool),
+    /// Creator-friendly under the policy the query is evaluated with
+    CreatorFriendly(bool),
+    Id(String),
+}
+
+/// Parsed search query: predicates combined with NOT, AND and OR
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum SongQuery {
+    /// Empty query; matches every song
+    All,
+    Match { predicate: QueryPredicate },
+    Not { query: Box<SongQuery> },
+    And { queries: Vec<SongQuery> },
+    Or { queries: Vec<SongQuery> },
+}
+
+/// Why a query didn't parse, and where
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    pub message: String,
+    /// 1-based, counted in characters
+    pub column: usize,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (column {})", self.message, self.column)
+    }
+}
+
+fn error(message: impl Into<String>, column: usize) -> QueryError {
+    QueryError { message: message.into(), column }
+}
+
+/// Whether `phrase` (folded words) appears in `text` as consecutive words; its
+/// last word may be the beginning of a word. An empty phrase is always found
+fn contains_phrase(text: &str, phrase: &[String]) -> bool {
+    let Some((last, rest)) = phrase.split_last() else { return true };
+    tokenize(text).windows(phrase.len()).any(|window| window[..rest.len()] == *rest && window[rest.len()].starts_with(last.as_str()))
+}
+
+/// License family a `license:` value names, if it is a family keyword
+fn license_family(value: &str) -> Option<LicenseFamily> {
+    match fold(value).as_str() {
+        "cc" | "creative commons" | "creative-commons" => Some(LicenseFamily::CreativeCommons),
+        "pd" | "public domain" | "public-domain" => Some(LicenseFamily::PublicDomain),
+        "bgml" => Some(LicenseFamily::Bgml),
+        "arr" => Some(LicenseFamily::AllRightsReserved),
+        "custom" => Some(LicenseFamily::Custom),
+        "none" => Some(LicenseFamily::Unspecified),
+        _ => None,
+    }
+}
+
+impl QueryPredicate {
+    pub fn matches(&self, song: &Song, policy: &CreatorFriendlyPolicy) -> bool {
+        match self {
+            QueryPredicate::Text(text) => {
+                let phrase = tokenize(text);
+                SearchField::ALL.iter().any(|field| contains_phrase(&field.text(song), &phrase))
+            }
+            QueryPredicate::Title(text) => contains_phrase(&song.title, &tokenize(text)),
+            QueryPredicate::Album(text) => contains_phrase(&song.album_name, &tokenize(text)),
+            QueryPredicate::Year(range) => song.release_year.is_some_and(|year| range.contains(year)),
+            QueryPredicate::ReleaseType(release_type) => song.release_type == *release_type,
+            QueryPredicate::License(value) => {
+                let expression = song.license_expression();
+                let licenses = expression.licenses();
+                if let Some(family) = license_family(value) {
+                    return licenses.iter().any(|license| license.family() == family);
+                }
+                match License::parse(value) {
+                    License::Custom(text) => contains_phrase(&song.license, &tokenize(&text)),
+                    pattern => licenses.iter().any(|license| license_matches(&pattern, license)),
+                }
+            }
+            QueryPredicate::ContentId(value) => song.has_content_id == *value,
+            QueryPredicate::CreatorFriendly(value) => is_creator_friendly_with(song, policy) == *value,
+            QueryPredicate::Id(id) => song.id == *id,
+        }
+    }
+}
+
+impl SongQuery {
+    /// Parses the search box syntax:
+    /// - words and `"quoted phrases"` match the title, album, license or release type
+    /// - `field:value` (or `field:"quoted value"`) tests one field: `title`,
+    ///   `album`, `year` (`2023`, `2020..2023`, `2020..`, `..2023`), `type`
+    ///   (alias `release`), `license`, `contentid` (alias `cid`), `creator` (both
+    ///   `yes`/`no`) and `id`; bare `contentid` and `creator` mean `:yes`
+    /// - `-term` or `NOT term` negates, terms side by side must all match,
+    ///   `OR` matches either side (and binds looser than `AND`), parentheses group
+    /// - parentheses and NOTs nest at most `MAX_QUERY_DEPTH` deep
+    pub fn parse(text: &str) -> Result<SongQuery, QueryError> {
+        let tokens = lex(text)?;
+        if tokens.is_empty() {
+            return Ok(SongQuery::All);
+        }
+        let mut parser = Parser { tokens, pos: 0, end: text.chars().count() + 1, depth: 0 };
+        let query = parser.or_expr()?;
+        match parser.tokens.get(parser.pos) {
+            Some(token) => Err(error("Unexpected ')' without a matching '('", token.column)),
+            None => Ok(query),
+        }
+    }
+
+    /// Whether `song` matches, judging `creator:` by `policy`
+    pub fn matches(&self, song: &Song, policy: &CreatorFriendlyPolicy) -> bool {
+        match self {
+            SongQuery::All => true,
+            SongQuery::Match { predicate } => predicate.matches(song, policy),
+            SongQuery::Not { query } => !query.matches(song, policy),
+            SongQuery::And { queries } => queries.iter().all(|query| query.matches(song, policy)),
+            SongQuery::Or { queries } => queries.iter().any(|query| query.matches(song, policy)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Term { field: Option<String>, value: String, quoted: bool },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    kind: TokenKind,
+    /// 1-based character column where the token starts
+    column: usize,
+}
+
+/// Reads a quoted string starting at `chars[start]` (the opening quote)
+/// Returns its contents and the position after the closing quote
+fn lex_quoted(chars: &[char], start: usize) -> Result<(String, usize), QueryError> {
+    let length = chars[start + 1..].iter().position(|&c| c == '"').ok_or_else(|| error("Unclosed quote", start + 1))?;
+    Ok((chars[start + 1..start + 1 + length].iter().collect(), start + length + 2))
+}
+
+fn lex(text: &str) -> Result<Vec<Token>, QueryError> {
+    let chars: Vec<char> = text.chars().collect();
+    let ends_bare = |c: char| c.is_whitespace() || matches!(c, '(' | ')' | '"');
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let column = i + 1;
+        let kind = match chars[i] {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => {
+                i += 1;
+                TokenKind::Open
+            }
+            ')' => {
+                i += 1;
+                TokenKind::Close
+            }
+            '-' if chars.get(i + 1).is_some_and(|&next| !ends_bare(next) || next == '(' || next == '"') => {
+                i += 1;
+                TokenKind::Not
+            }
+            '"' => {
+                let (value, next) = lex_quoted(&chars, i)?;
+                i = next;
+                TokenKind::Term { field: None, value, quoted: true }
+            }
+            _ => {
+                // A word, or a field name up to the first ':'
+                let start = i;
+                while i < chars.len() && !ends_bare(chars[i]) && (i == start || chars[i] != ':') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                if chars.get(i) == Some(&':') {
+                    i += 1;
+                    let (value, quoted) = if chars.get(i) == Some(&'"') {
+                        let (value, next) = lex_quoted(&chars, i)?;
+                        i = next;
+                        (value, true)
+                    } else {
+                        let value_start = i;
+                        while i < chars.len() && !ends_bare(chars[i]) {
+                            i += 1;
+                        }
+                        (chars[value_start..i].iter().collect(), false)
+                    };
+                    TokenKind::Term { field: Some(word), value, quoted }
+                } else {
+                    match word.as_str() {
+                        "AND" => TokenKind::And,
+                        "OR" => TokenKind::Or,
+                        "NOT" => TokenKind::Not,
+                        _ => TokenKind::Term { field: None, value: word, quoted: false },
+                    }
+                }
+            }
+        };
+        tokens.push(Token { kind, column });
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Column just past the end of the query
+    end: usize,
+    /// Parentheses and NOTs open around the current token
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|token| &token.kind)
+    }
+
+    fn or_expr(&mut self) -> Result<SongQuery, QueryError> {
+        let mut queries = vec![self.and_expr()?];
+        while self.peek() == Some(&TokenKind::Or) {
+            self.pos += 1;
+            queries.push(self.and_expr()?);
+        }
+        Ok(if queries.len() == 1 { queries.remove(0) } else { SongQuery::Or { queries } })
+    }
+
+    fn and_expr(&mut self) -> Result<SongQuery, QueryError> {
+        let mut queries = vec![self.unary()?];
+        loop {
+            match self.peek() {
+                None | Some(TokenKind::Or | TokenKind::Close) => break,
+                Some(TokenKind::And) => self.pos += 1,
+                _ => {}
+            }
+            queries.push(self.unary()?);
+        }
+        Ok(if queries.len() == 1 { queries.remove(0) } else { SongQuery::And { queries } })
+    }
+
+    fn unary(&mut self) -> Result<SongQuery, QueryError> {
+        if self.peek() == Some(&TokenKind::Not) {
+            let column = self.column();
+            self.pos += 1;
+            let query = self.nested(column, Self::unary)?;
+            return Ok(SongQuery::Not { query: Box::new(query) });
+        }
+        self.primary()
+    }
+
+    /// Runs `parse` one level deeper, failing at `column` past `MAX_QUERY_DEPTH`
+    fn nested(&mut self, column: usize, parse: fn(&mut Self) -> Result<SongQuery, QueryError>) -> Result<SongQuery, QueryError> {
+        if self.depth == MAX_QUERY_DEPTH {
+            return Err(error(format!("Query nested more than {} deep", MAX_QUERY_DEPTH), column));
+        }
+        self.depth += 1;
+        let query = parse(self);
+        self.depth -= 1;
+        query
+    }
+
+    fn primary(&mut self) -> Result<SongQuery, QueryError> {
+        let Some(token) = self.tokens.get(self.pos).cloned() else {
+            return Err(error("Expected a search term, found the end of the query", self.end));
+        };
+        self.pos += 1;
+        match token.kind {
+            TokenKind::Open => {
+                let query = self.nested(token.column, Self::or_expr)?;
+                if self.peek() != Some(&TokenKind::Close) {
+                    return Err(error(format!("Expected ')' to close the '(' at column {}", token.column), self.column()));
+                }
+                self.pos += 1;
+                Ok(query)
+            }
+            TokenKind::Term { field, value, quoted } => {
+                let predicate = predicate(field.as_deref(), &value, quoted, token.column)?;
+                Ok(SongQuery::Match { predicate })
+            }
+            TokenKind::Close => Err(error("Expected a search term, found ')'", token.column)),
+            TokenKind::And => Err(error("Expected a search term before 'AND'", token.column)),
+            TokenKind::Or => Err(error("Expected a search term before 'OR'", token.column)),
+            TokenKind::Not => unreachable!("handled by unary"),
+        }
+    }
+
+    /// Column of the next token, or the end of the query
+    fn column(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |token| token.column)
+    }
+}
+
+fn parse_bool(field: &str, value: &str, column: usize) -> Result<bool, QueryError> {
+    match value.to_lowercase().as_str() {
+        "yes" | "true" => Ok(true),
+        "no" | "false" => Ok(false),
+        _ => Err(error(format!("Expected yes or no after '{}:', found '{}'", field, value), column)),
+    }
+}
+
+fn parse_year_range(value: &str, column: usize) -> Result<YearRange, QueryError> {
+    let invalid = || error(format!("Invalid year '{}'; expected a year like 2023 or a range like 2020..2023", value), column);
+    let year = |text: &str| -> Result<Option<u32>, QueryError> {
+        if text.is_empty() { Ok(None) } else { text.parse().map(Some).map_err(|_| invalid()) }
+    };
+    let range = match value.split_once("..") {
+        Some((from, to)) => YearRange { from: year(from)?, to: year(to)? },
+        None => {
+            let year = year(value)?;
+            YearRange { from: year, to: year }
+        }
+    };
+    match range {
+        YearRange { from: None, to: None } => Err(invalid()),
+        YearRange { from: Some(from), to: Some(to) } if from > to => {
+            Err(error(format!("Year range {}..{} ends before it starts", from, to), column))
+        }
+        range => Ok(range),
+    }
+}
+
+fn parse_release_type(value: &str, column: usize) -> Result<ReleaseType, QueryError> {
+    match value.to_lowercase().as_str() {
+        "independent" => Ok(ReleaseType::Independent),
+        "ncs" => Ok(ReleaseType::NCS),
+        "monstercat" => Ok(ReleaseType::Monstercat),
+        _ => Err(error(format!("Unknown release type '{}'; expected Independent, NCS or Monstercat", value), column)),
+    }
+}
+
+/// Builds the predicate for a term, checking field names and values
+fn predicate(field: Option<&str>, value: &str, quoted: bool, column: usize) -> Result<QueryPredicate, QueryError> {
+    let Some(field) = field else {
+        return Ok(match value.to_lowercase().as_str() {
+            "contentid" if !quoted => QueryPredicate::ContentId(true),
+            "creator" if !quoted => QueryPredicate::CreatorFriendly(true),
+            _ => QueryPredicate::Text(value.to_string()),
+        });
+    };
+    if value.is_empty() {
+        return Err(error(format!("Expected a value after '{}:'", field), column));
+    }
+    let value_column = column + field.chars().count() + 1;
+    Ok(match field.to_lowercase().as_str() {
+        "title" => QueryPredicate::Title(value.to_string()),
+        "album" => QueryPredicate::Album(value.to_string()),
+        "year" => QueryPredicate::Year(parse_year_range(value, value_column)?),
+        "type" | "release" => QueryPredicate::ReleaseType(parse_release_type(value, value_column)?),
+        "license" => QueryPredicate::License(value.to_string()),
+        "contentid" | "cid" => QueryPredicate::ContentId(parse_bool(field, value, value_column)?),
+        "creator" => QueryPredicate::CreatorFriendly(parse_bool(field, value, value_column)?),
+        "id" => QueryPredicate::Id(value.to_string()),
+        name => {
+            let closest = FIELDS.iter().min_by_key(|known| edit_distance(name, known)).filter(|known| edit_distance(name, known) <= 1);
+            let message = match closest {
+                Some(known) => format!("Unknown field '{}'; did you mean '{}'?", field, known),
+                None => format!("Unknown field '{}'; expected one of {}.", field, FIELDS.join(", ")),
+            };
+            return Err(error(message + " Put text containing ':' in quotes to search for it", column));
+        }
+    })
+}
+
+/// Filters songs with a search query (see `SongQuery::parse` for the syntax)
+/// `policy_json` is a `CreatorFriendlyPolicy` for `creator:`; omit it for the default policy
+/// Returns JSON string of the matching songs, in input order
+#[wasm_bindgen]
+pub fn query_songs(songs_json: &str, query: &str, policy_json: Option<String>) -> String {
+    let songs: Vec<Song> = match serde_json::from_str(songs_json) {
+        Ok(s) => s,
+        Err(e) => return json!({ "error": format!("Invalid JSON: {}", e) }).to_string(),
+    };
+    let policy = match policy_json.as_deref().map(CreatorFriendlyPolicy::from_json).transpose() {
+        Ok(policy) => policy.unwrap_or_default(),
+        Err(e) => return json!({ "error": e }).to_string(),
+    };
+    let query = match SongQuery::parse(query) {
+        Ok(query) => query,
+        Err(e) => return json!({ "error": format!("Invalid query: {}", e) }).to_string(),
+    };
+    json!(songs.iter().filter(|song| query.matches(song, &policy)).collect::<Vec<_>>()).to_string()
+}
+
+/// Parses a search query into its syntax tree, throwing a readable error
+/// (with a column) if it is malformed
+#[wasm_bindgen(js_name = parseQuery, unchecked_return_type = "SongQuery")]
+pub fn parse_query_js(query: &str) -> Result<JsValue, JsError> {
+    let query = SongQuery::parse(query).map_err(|e| JsError::new(&format!("Invalid query: {}", e)))?;
+    to_js(&query)
+}
+
+/// Filters an array of song objects with a search query, keeping input order
+/// `policy` (used by `creator:`) is optional; leaving it out uses the default policy
+#[wasm_bindgen(js_name = querySongs, unchecked_return_type = "Song[]")]
+pub fn query_songs_js(
+    #[wasm_bindgen(unchecked_param_type = "Song[]")] songs: JsValue,
+    query: &str,
+    #[wasm_bindgen(unchecked_param_type = "CreatorFriendlyPolicy")] policy: Option<JsValue>,
+) -> Result<JsValue, JsError> {
+    let songs: Vec<Song> = from_js(songs, "song list")?;
+    let policy: Option<CreatorFriendlyPolicy> = from_js_optional(policy, "policy")?;
+    let policy = policy.unwrap_or_default();
+    let query = SongQuery::parse(query).map_err(|e| JsError::new(&format!("Invalid query: {}", e)))?;
+    to_js(&songs.iter().filter(|song| query.matches(song, &policy)).collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use crate::validation::ReleaseType;
+
+    fn song(id: &str, title: &str, album: &str, release_type: ReleaseType, content_id: bool, license: &str, year: u32) -> Song {
+        testing::song(id)
+            .title(title)
+            .album(album)
+            .release_type(release_type)
+            .content_id(content_id)
+            .license(license)
+            .year(year)
+            .build()
+    }
+
+    fn catalog() -> Vec<Song> {
+        vec![
+            song("s1", "Digital Dreams", "Electronic Fusion", ReleaseType::Independent, false, "CC BY 4.0", 2023),
+            song("s2", "Neon Nights", "Electronic Fusion", ReleaseType::Independent, true, "CC BY 3.0", 2024),
+            song("s3", "Electric Pulse", "Pulse", ReleaseType::NCS, true, "", 2022),
+            song("s4", "Café Drive", "Night Drive", ReleaseType::Monstercat, false, "BGML-P OR CC BY-NC 4.0", 2025),
+        ]
+    }
+
+    fn matching(query: &str) -> Vec<String> {
+        let query = SongQuery::parse(query).unwrap();
+        catalog().into_iter().filter(|song| query.matches(song, &CreatorFriendlyPolicy::default())).map(|song| song.id).collect()
+    }
+
+    fn text(value: &str) -> SongQuery {
+        SongQuery::Match { predicate: QueryPredicate::Text(value.to_string()) }
+    }
+
+    #[test]
+    fn test_parse_builds_tree() {
+        assert_eq!(SongQuery::parse("  ").unwrap(), SongQuery::All);
+        assert_eq!(
+            SongQuery::parse(r#"album:"Electronic Fusion" year:2023..2024 license:cc-by -contentid"#).unwrap(),
+            SongQuery::And {
+                queries: vec![
+                    SongQuery::Match { predicate: QueryPredicate::Album("Electronic Fusion".to_string()) },
+                    SongQuery::Match { predicate: QueryPredicate::Year(YearRange { from: Some(2023), to: Some(2024) }) },
+                    SongQuery::Match { predicate: QueryPredicate::License("cc-by".to_string()) },
+                    SongQuery::Not { query: Box::new(SongQuery::Match { predicate: QueryPredicate::ContentId(true) }) },
+                ]
+            }
+        );
+        // OR binds looser than AND; NOT binds tightest
+        assert_eq!(
+            SongQuery::parse("a b OR NOT (c)").unwrap(),
+            SongQuery::Or {
+                queries: vec![SongQuery::And { queries: vec![text("a"), text("b")] }, SongQuery::Not { query: Box::new(text("c")) }]
+            }
+        );
+        assert_eq!(SongQuery::parse("a AND b").unwrap(), SongQuery::parse("a b").unwrap());
+        assert_eq!(SongQuery::parse("8-bit - x").unwrap(), SongQuery::And { queries: vec![text("8-bit"), text("-"), text("x")] });
+        assert_eq!(SongQuery::parse(r#""creator""#).unwrap(), text("creator"));
+    }
+
+    #[test]
+    fn test_parse_errors_are_readable() {
+        let message = |query: &str| SongQuery::parse(query).unwrap_err().to_string();
+        assert_eq!(message("(neon OR pulse"), "Expected ')' to close the '(' at column 1 (column 15)");
+        assert_eq!(message("neon)"), "Unexpected ')' without a matching '(' (column 5)");
+        assert_eq!(message("OR neon"), "Expected a search term before 'OR' (column 1)");
+        assert_eq!(message("neon NOT"), "Expected a search term, found the end of the query (column 9)");
+        assert_eq!(message(r#"title:"neon"#), "Unclosed quote (column 7)");
+        assert_eq!(message("album:"), "Expected a value after 'album:' (column 1)");
+        assert_eq!(message("year:2024..2023"), "Year range 2024..2023 ends before it starts (column 6)");
+        assert_eq!(message("year:soon"), "Invalid year 'soon'; expected a year like 2023 or a range like 2020..2023 (column 6)");
+        assert_eq!(message("creator:maybe"), "Expected yes or no after 'creator:', found 'maybe' (column 9)");
+        assert_eq!(message("type:indie"), "Unknown release type 'indie'; expected Independent, NCS or Monstercat (column 6)");
+        assert_eq!(message("yaer:2023"), "Unknown field 'yaer'; did you mean 'year'? Put text containing ':' in quotes to search for it (column 1)");
+        assert!(message("re:zero").ends_with("expected one of title, album, year, type, license, contentid, creator, id. Put text containing ':' in quotes to search for it (column 1)"));
+    }
+
+    #[test]
+    fn test_parse_nesting_limit() {
+        let songs = catalog();
+        let policy = CreatorFriendlyPolicy::default();
+        let parenthesized = |depth: usize| format!("{}neon{}", "(".repeat(depth), ")".repeat(depth));
+        let negated = |depth: usize| format!("{}neon", "-".repeat(depth));
+
+        let query = SongQuery::parse(&parenthesized(MAX_QUERY_DEPTH)).unwrap();
+        assert!(query.matches(&songs[1], &policy));
+        let query = SongQuery::parse(&negated(MAX_QUERY_DEPTH)).unwrap();
+        assert!(query.matches(&songs[1], &policy));
+
+        let message = format!("Query nested more than {} deep (column {})", MAX_QUERY_DEPTH, MAX_QUERY_DEPTH + 1);
+        assert_eq!(SongQuery::parse(&parenthesized(MAX_QUERY_DEPTH + 1)).unwrap_err().to_string(), message);
+        assert_eq!(SongQuery::parse(&negated(MAX_QUERY_DEPTH + 1)).unwrap_err().to_string(), message);
+        assert!(SongQuery::parse(&"(".repeat(100_000)).is_err());
+        assert!(SongQuery::parse(&negated(100_000)).is_err());
+    }
+
+    #[test]
+    fn test_query_matches_songs() {
+        assert_eq!(matching(r#"album:"Electronic Fusion" year:2023..2024 license:cc-by -contentid"#), ["s1"]);
+        assert_eq!(matching("neon OR pulse"), ["s2", "s3"]);
+        assert_eq!(matching(r#""night drive""#), ["s4"]);
+        assert_eq!(matching("title:cafe"), ["s4"]);
+        assert_eq!(matching("title:dri"), ["s4"]);
+        assert_eq!(matching("year:2024.."), ["s2", "s4"]);
+        assert_eq!(matching("year:..2022"), ["s3"]);
+        assert_eq!(matching("type:ncs OR type:monstercat"), ["s3", "s4"]);
+        assert_eq!(matching("-(electronic OR pulse)"), ["s4"]);
+        assert_eq!(matching("id:s2"), ["s2"]);
+        assert_eq!(matching("cid:no"), ["s1", "s4"]);
+        assert_eq!(matching(""), ["s1", "s2", "s3", "s4"]);
+    }
+
+    #[test]
+    fn test_license_and_creator_predicates() {
+        assert_eq!(matching("license:cc-by-4.0"), ["s1"]);
+        // Expressions match when any of their licenses does
+        assert_eq!(matching("license:bgml-p"), ["s4"]);
+        assert_eq!(matching("license:cc"), ["s1", "s2", "s4"]);
+        assert_eq!(matching("license:none"), ["s3"]);
+        // NCS releases and commercial CC are creator-friendly by default
+        assert_eq!(matching("creator"), ["s1", "s2", "s3", "s4"]);
+        assert_eq!(matching("creator:no"), Vec::<String>::new());
+
+        let strict = CreatorFriendlyPolicy { allow_content_id: false, ..Default::default() };
+        let query = SongQuery::parse("creator:yes").unwrap();
+        let ids: Vec<_> = catalog().into_iter().filter(|song| query.matches(song, &strict)).map(|song| song.id).collect();
+        assert_eq!(ids, ["s1", "s4"]);
+    }
+
+    #[test]
+    fn test_query_songs() {
+        let songs = serde_json::to_string(&catalog()).unwrap();
+        let result: serde_json::Value = serde_json::from_str(&query_songs(&songs, "type:ncs", None)).unwrap();
+        assert_eq!(result.as_array().unwrap().len(), 1);
+        assert_eq!(result[0]["id"], "s3");
+        assert!(query_songs(&songs, "(", None).starts_with(r#"{"error":"Invalid query: "#));
+        assert!(query_songs("{", "x", None).contains("error"));
+        assert!(query_songs(&songs, "x", Some("{".to_string())).contains("Invalid policy JSON"));
+
+        let tree = serde_json::to_value(SongQuery::parse("-year:2023 album:x").unwrap()).unwrap();
+        assert_eq!(
+            tree,
+            json!({ "op": "and", "queries": [
+                { "op": "not", "query": { "op": "match", "predicate": { "field": "year", "value": { "from": 2023, "to": 2023 } } } },
+                { "op": "match", "predicate": { "field": "album", "value": "x" } }
+            ] })
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use serde_json::{json, Value};
+use crate::source_map::{SourceMap, SourceSpan};
+
+/// How serious a diagnostic is
+/// Errors make a document invalid, warnings are advisory only
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Stable machine-readable diagnostic codes
+/// The serialized (kebab-case) names are part of the public API and must not change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiagnosticCode {
+    /// Input is not syntactically valid JSON
+    InvalidJson,
+    /// Value has the wrong JSON type (e.g. string instead of boolean)
+    InvalidType,
+    /// Required field is absent
+    MissingField,
+    /// Required string field is empty
+    EmptyField,
+    /// Value has the right type but is not one of the allowed values
+    InvalidValue,
+    /// Song or album ID is used more than once
+    DuplicateId,
+    /// Document passed field checks but still doesn't match the data model
+    InvalidStructure,
+    /// Albums are listed but there are no songs
+    AlbumsWithoutSongs,
+    /// Streaming link is not an http(s) URL
+    NonHttpLink,
+    /// License looks like an SPDX expression but doesn't parse as one
+    InvalidLicenseExpression,
+    /// Song's `albumId` doesn't match any declared album
+    UnknownAlbum,
+    /// Song's `albumName` differs from the name of the album it references
+    AlbumNameMismatch,
+    /// Two songs in an album share a disc and track number
+    DuplicateTrack,
+    /// An album's track or disc numbers skip a position
+    TrackGap,
+    /// Song has no `trackNumber` although others in its album do
+    MissingTrackNumber,
+    /// Album name looks like a misspelling of another album's name
+    SimilarAlbumName,
+}
+
+impl DiagnosticCode {
+    /// Returns the stable string form of the code (same as its JSON form)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticCode::InvalidJson => "invalid-json",
+            DiagnosticCode::InvalidType => "invalid-type",
+            DiagnosticCode::MissingField => "missing-field",
+            DiagnosticCode::EmptyField => "empty-field",
+            DiagnosticCode::InvalidValue => "invalid-value",
+            DiagnosticCode::DuplicateId => "duplicate-id",
+            DiagnosticCode::InvalidStructure => "invalid-structure",
+            DiagnosticCode::AlbumsWithoutSongs => "albums-without-songs",
+            DiagnosticCode::NonHttpLink => "non-http-link",
+            DiagnosticCode::InvalidLicenseExpression => "invalid-license-expression",
+            DiagnosticCode::UnknownAlbum => "unknown-album",
+            DiagnosticCode::AlbumNameMismatch => "album-name-mismatch",
+            DiagnosticCode::DuplicateTrack => "duplicate-track",
+            DiagnosticCode::TrackGap => "track-gap",
+            DiagnosticCode::MissingTrackNumber => "missing-track-number",
+            DiagnosticCode::SimilarAlbumName => "similar-album-name",
+        }
+    }
+}
+
+/// A single validation problem
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct Diagnostic {
+    /// JSON pointer (RFC 6901) to the offending value, e.g. `/songs/14/releaseType`
+    pub path: String,
+    pub code: DiagnosticCode,
+    pub severity: Severity,
+    pub message: String,
+    /// Where the offending value sits in the original JSON text, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<SourceSpan>,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+impl Diagnostic {
+    /// Formats the diagnostic rustc-style, with a snippet of `source` pointing
+    /// at the offending value. `origin` is the file name shown in the header
+    pub fn render(&self, source: &str, origin: &str) -> String {
+        let mut out = format!("{}[{}]: {}", self.severity.as_str(), self.code.as_str(), self.message);
+        let Some(span) = self.span else { return out };
+
+        let line_text = source.lines().nth(span.line - 1).unwrap_or("");
+        let line_width = line_text.chars().count();
+        let underline_end = if span.end_line == span.line { span.end_column } else { line_width + 1 };
+        let underline = underline_end.saturating_sub(span.column).max(1);
+        let gutter = " ".repeat(span.line.to_string().len());
+
+        out.push_str(&format!("\n{}--> {}:{}:{}", gutter, origin, span.line, span.column));
+        out.push_str(&format!("\n{} |", gutter));
+        out.push_str(&format!("\n{} | {}", span.line, line_text));
+        out.push_str(&format!("\n{} | {}{}", gutter, " ".repeat(span.column - 1), "^".repeat(underline)));
+        out
+    }
+}
+
+/// A report as handed to JavaScript, with the counts precomputed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationSummary {
+    pub valid: bool,
+    pub error_count: usize,
+    pub warning_count: usize,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Every problem found in a document, in the order they were found
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct ValidationReport {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ValidationReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an error at `path`
+    pub fn error(&mut self, path: &str, code: DiagnosticCode, message: impl Into<String>) {
+        self.push(path, code, Severity::Error, message);
+    }
+
+    /// Records a warning at `path`
+    pub fn warning(&mut self, path: &str, code: DiagnosticCode, message: impl Into<String>) {
+        self.push(path, code, Severity::Warning, message);
+    }
+
+    fn push(&mut self, path: &str, code: DiagnosticCode, severity: Severity, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            path: path.to_string(),
+            code,
+            severity,
+            message: message.into(),
+            span: None,
+        });
+    }
+
+    /// Fills in the source span of every diagnostic that doesn't have one yet
+    pub fn attach_spans(&mut self, source: &SourceMap) {
+        for diagnostic in &mut self.diagnostics {
+            if diagnostic.span.is_none() {
+                diagnostic.span = source.locate(&diagnostic.path);
+            }
+        }
+    }
+
+    /// A report is valid when it contains no errors (warnings are allowed)
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Warning)
+    }
+
+    pub fn first_error(&self) -> Option<&Diagnostic> {
+        self.errors().next()
+    }
+
+    /// Structured form handed to JavaScript
+    pub fn summary(&self) -> ValidationSummary {
+        ValidationSummary {
+            valid: self.is_valid(),
+            error_count: self.errors().count(),
+            warning_count: self.warnings().count(),
+            diagnostics: self.diagnostics.clone(),
+        }
+    }
+
+    /// `summary` as a JSON value
+    pub fn to_json(&self) -> Value {
+        json!(self.summary())
+    }
+}
+
+/// Appends one reference token to a JSON pointer, escaping `~` and `/`
+pub fn pointer_join(base: &str, token: &str) -> String {
+    format!("{}/{}", base, token.replace('~', "~0").replace('/', "~1"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pointer_join_escapes_tokens() {
+        assert_eq!(pointer_join("", "songs"), "/songs");
+        assert_eq!(pointer_join("/songs", "14"), "/songs/14");
+        assert_eq!(pointer_join("", "a/b~c"), "/a~1b~0c");
+    }
+
+    #[test]
+    fn test_report_validity_ignores_warnings() {
+        let mut report = ValidationReport::new();
+        assert!(report.is_valid());
+
+        report.warning("/songs/0/streamingLink", DiagnosticCode::NonHttpLink, "not http");
+        assert!(report.is_valid());
+        assert!(report.first_error().is_none());
+
+        report.error("/songs/0/id", DiagnosticCode::EmptyField, "empty");
+        assert!(!report.is_valid());
+        assert_eq!(report.first_error().unwrap().path, "/songs/0/id");
+    }
+
+    #[test]
+    fn test_report_json_shape() {
+        let mut report = ValidationReport::new();
+        report.error("/songs/14/releaseType", DiagnosticCode::InvalidValue, "bad release type");
+
+        let json = report.to_json();
+        assert_eq!(json["valid"], false);
+        assert_eq!(json["errorCount"], 1);
+        assert_eq!(json["warningCount"], 0);
+        assert_eq!(json["diagnostics"][0]["path"], "/songs/14/releaseType");
+        assert_eq!(json["diagnostics"][0]["code"], "invalid-value");
+        assert_eq!(json["diagnostics"][0]["severity"], "error");
+        assert_eq!(json["diagnostics"][0]["message"], "bad release type");
+    }
+
+    #[test]
+    fn test_render_points_at_value() {
+        let source = "{\n  \"songs\": [\n    {\"releaseType\": \"Label\"}\n  ]\n}";
+        let mut report = ValidationReport::new();
+        report.error("/songs/0/releaseType", DiagnosticCode::InvalidValue, "bad release type");
+        report.attach_spans(&SourceMap::new(source));
+
+        let rendered = report.diagnostics[0].render(source, "music.json");
+        assert_eq!(
+            rendered,
+            "error[invalid-value]: bad release type\n --> music.json:3:21\n  |\n3 |     {\"releaseType\": \"Label\"}\n  |                     ^^^^^^^"
+        );
+    }
+
+    #[test]
+    fn test_render_without_span_is_header_only() {
+        let mut report = ValidationReport::new();
+        report.warning("", DiagnosticCode::NonHttpLink, "not http");
+        assert_eq!(report.diagnostics[0].render("", "music.json"), "warning[non-http-link]: not http");
+    }
+
+    #[test]
+    fn test_code_as_str_matches_serde() {
+        for code in [
+            DiagnosticCode::InvalidJson,
+            DiagnosticCode::InvalidType,
+            DiagnosticCode::MissingField,
+            DiagnosticCode::EmptyField,
+            DiagnosticCode::InvalidValue,
+            DiagnosticCode::DuplicateId,
+            DiagnosticCode::InvalidStructure,
+            DiagnosticCode::AlbumsWithoutSongs,
+            DiagnosticCode::NonHttpLink,
+            DiagnosticCode::InvalidLicenseExpression,
+            DiagnosticCode::UnknownAlbum,
+            DiagnosticCode::AlbumNameMismatch,
+            DiagnosticCode::DuplicateTrack,
+            DiagnosticCode::TrackGap,
+            DiagnosticCode::MissingTrackNumber,
+            DiagnosticCode::SimilarAlbumName,
+        ] {
+            assert_eq!(serde_json::to_value(code).unwrap(), code.as_str());
+        }
+    }
+}
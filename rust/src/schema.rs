@@ -0,0 +1,329 @@
+use wasm_bindgen::prelude::*;
+use serde_json::{json, Value};
+use crate::license::{BgmlVariant, CcElements, CcLicense, CcVersion, License};
+use crate::validation::{ALBUM_TYPES, RELEASE_TYPES, REQUIRED_ALBUM_FIELDS, REQUIRED_SONG_FIELDS};
+
+/// Dialect of the generated schema
+pub const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Where the published schema lives, used as its `$id`
+pub const SCHEMA_ID: &str = "https://babafun.github.io/music.schema.json";
+
+/// Canonical spellings of the licenses the parser recognises, offered as completions
+fn license_examples() -> Vec<String> {
+    let mut licenses = vec![License::Unspecified];
+    licenses.extend(CcElements::ALL.map(|elements| {
+        License::CreativeCommons(CcLicense { elements, version: Some(CcVersion::V4_0), jurisdiction: None })
+    }));
+    licenses.extend([
+        License::Cc0 { version: Some(CcVersion::V1_0) },
+        License::PublicDomainMark { version: Some(CcVersion::V1_0) },
+        License::Bgml(BgmlVariant::Standard),
+        License::Bgml(BgmlVariant::Permissive),
+        License::AllRightsReserved,
+    ]);
+    licenses.iter().map(License::to_string).collect()
+}
+
+/// A string the validator requires to be non-empty
+fn non_empty_string(description: &str) -> Value {
+    json!({ "type": "string", "minLength": 1, "description": description })
+}
+
+/// A 1-based position that must fit in a `u32`
+fn position(description: &str) -> Value {
+    json!({ "type": "integer", "minimum": 1, "maximum": u32::MAX, "description": description })
+}
+
+/// JSON Schema (draft 2020-12) for `music.json`
+/// Mirrors the errors `check_music_data_value` reports, except what JSON Schema
+/// can't express: unique song and album IDs, `albumId` references, unique and
+/// gapless track positions, impossible dates (e.g. February 30th) and UPC
+/// check digits. Warnings (non-http links,
+/// unparseable licenses, album name mismatches) are left to the validator
+pub fn music_data_schema() -> Value {
+    json!({
+        "$schema": SCHEMA_DIALECT,
+        "$id": SCHEMA_ID,
+        "title": "MusicData",
+        "description": "The music catalog: every song, plus optional album declarations",
+        "type": "object",
+        "required": ["songs"],
+        "properties": {
+            "songs": {
+                "type": "array",
+                "description": "All songs; each `id` must be unique",
+                "items": { "$ref": "#/$defs/Song" },
+            },
+            "albums": {
+                "type": "array",
+                "description": "Declared albums; each `id` must be unique",
+                "items": { "$ref": "#/$defs/Album" },
+            },
+        },
+        // An album declaration is meaningless without songs to put on it
+        "if": {
+            "required": ["songs"],
+            "properties": { "songs": { "maxItems": 0 } },
+        },
+        "then": {
+            "properties": { "albums": { "maxItems": 0 } },
+        },
+        "$defs": {
+            "Song": {
+                "type": "object",
+                "description": "A single track",
+                "required": REQUIRED_SONG_FIELDS,
+                "properties": {
+                    "id": non_empty_string("Unique identifier, e.g. \"song-001\""),
+                    "title": non_empty_string("Song title"),
+                    "albumName": non_empty_string("Name of the album the song belongs to"),
+                    "albumId": non_empty_string("`id` of the declared album the song belongs to"),
+                    "trackNumber": position("Position on its disc; unique and gapless within the album"),
+                    "discNumber": position("Disc the track is on; defaults to 1"),
+                    "releaseType": { "$ref": "#/$defs/ReleaseType" },
+                    "hasContentId": {
+                        "type": "boolean",
+                        "description": "Whether the song is registered with YouTube Content ID",
+                    },
+                    "streamingLink": {
+                        "type": "string",
+                        "minLength": 1,
+                        "description": "http(s) URL where the song can be streamed",
+                    },
+                    "license": { "$ref": "#/$defs/License" },
+                    "releaseYear": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "maximum": u32::MAX,
+                        "description": "Year the song was released",
+                    },
+                    "albumArtwork": {
+                        "type": "string",
+                        "description": "URL of the album artwork",
+                    },
+                },
+            },
+            "Album": {
+                "type": "object",
+                "description": "An album declaration; songs join it through `albumId`",
+                "required": REQUIRED_ALBUM_FIELDS,
+                "properties": {
+                    "id": non_empty_string("Unique identifier, e.g. \"album-001\""),
+                    "name": non_empty_string("Album name"),
+                    "albumType": { "$ref": "#/$defs/AlbumType" },
+                    "artwork": {
+                        "type": "string",
+                        "description": "URL of the cover art",
+                    },
+                    "releaseDate": {
+                        "type": "string",
+                        "pattern": "^[0-9]{4}(-(0[1-9]|1[0-2])(-(0[1-9]|[12][0-9]|3[01]))?)?$",
+                        "description": "ISO 8601 calendar date: \"2023\", \"2023-06\" or \"2023-06-15\"",
+                    },
+                    "description": { "type": "string" },
+                    "upc": {
+                        "type": "string",
+                        "pattern": "^[0-9]{12,13}$",
+                        "description": "Universal Product Code: 12 digits (UPC-A) or 13 (EAN-13)",
+                    },
+                },
+            },
+            "AlbumType": {
+                "description": "Kind of release",
+                "enum": ALBUM_TYPES,
+            },
+            "ReleaseType": {
+                "description": "How the song was released",
+                "enum": RELEASE_TYPES,
+            },
+            "License": {
+                "type": "string",
+                "description": "License name or SPDX-style expression. Recognised forms: Creative Commons \
+                    (\"CC BY-SA 4.0\", \"CC-BY-SA-4.0\", \"CC BY-NC 3.0 DE\"), \"CC0 1.0\", \"Public Domain Mark 1.0\", \
+                    \"BGML\", \"BGML-P\" and \"All Rights Reserved\", combined with AND, OR, WITH and parentheses. \
+                    Empty means no license; anything else is kept as custom license text",
+                "examples": license_examples(),
+            },
+        },
+    })
+}
+
+/// Returns the JSON Schema for `music.json` as pretty-printed JSON
+#[wasm_bindgen]
+pub fn music_json_schema() -> String {
+    serde_json::to_string_pretty(&music_data_schema()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::DiagnosticCode;
+    use crate::testing;
+    use crate::validation::check_music_data;
+
+    /// Documents the schema and the validator must agree on
+    /// (valid?, document)
+    fn corpus() -> Vec<(bool, Value)> {
+        let song = json!({
+            "id": "song-001",
+            "title": "Digital Dreams",
+            "albumName": "Synthwave Chronicles",
+            "releaseType": "Independent",
+            "hasContentId": false,
+            "streamingLink": "https://push.fm/digital-dreams",
+            "license": "CC BY 4.0",
+        });
+        let with = |key: &str, value: Value| {
+            let mut song = song.clone();
+            song[key] = value;
+            song
+        };
+        let album = json!({
+            "id": "album-001",
+            "name": "Synthwave Chronicles",
+            "albumType": "LP",
+            "artwork": "https://example.com/art.jpg",
+            "releaseDate": "2023-06-15",
+            "description": "Retro synths",
+            "upc": "036000291452",
+        });
+        let mut first_disc = with("trackNumber", json!(1));
+        first_disc["discNumber"] = json!(1);
+        let without = |key: &str| {
+            let mut song = song.clone();
+            song.as_object_mut().unwrap().remove(key);
+            song
+        };
+
+        vec![
+            (true, json!({ "songs": [] })),
+            (true, json!({ "songs": [song] })),
+            (true, json!({ "songs": [with("license", json!(""))] })),
+            (true, json!({ "songs": [with("license", json!("Some custom terms"))] })),
+            (true, json!({ "songs": [with("license", json!("CC-BY-4.0 AND"))] })),
+            (true, json!({ "songs": [with("streamingLink", json!("ftp://example.com"))] })),
+            (true, json!({ "songs": [with("releaseYear", json!(2024))] })),
+            (true, json!({ "songs": [with("trackNumber", json!(1))] })),
+            (true, json!({ "songs": [first_disc] })),
+            (true, json!({ "songs": [with("albumArtwork", json!("https://example.com/art.jpg"))] })),
+            (true, json!({ "songs": [with("featuring", json!(["someone"]))] })),
+            (true, json!({ "songs": [song], "albums": [{ "id": "album-001", "name": "Synthwave Chronicles" }] })),
+            (true, json!({ "songs": [with("albumId", json!("album-001"))], "albums": [album.clone()] })),
+            (true, json!({ "songs": [song], "albums": [{ "id": "a", "name": "Singles", "albumType": "Single", "releaseDate": "2023" }] })),
+            (true, json!({ "songs": [song], "albums": [{ "id": "a", "name": "N", "releaseDate": "2023-06", "upc": "4006381333931", "mood": "calm" }] })),
+            (true, json!({ "songs": [song], "$schema": SCHEMA_ID })),
+            (false, json!([])),
+            (false, json!({})),
+            (false, json!({ "songs": {} })),
+            (false, json!({ "songs": ["not a song"] })),
+            (false, json!({ "songs": [without("license")] })),
+            (false, json!({ "songs": [without("hasContentId")] })),
+            (false, json!({ "songs": [with("id", json!(""))] })),
+            (false, json!({ "songs": [with("title", json!(7))] })),
+            (false, json!({ "songs": [with("releaseType", json!("Bandcamp"))] })),
+            (false, json!({ "songs": [with("releaseType", json!(null))] })),
+            (false, json!({ "songs": [with("hasContentId", json!("false"))] })),
+            (false, json!({ "songs": [with("streamingLink", json!(""))] })),
+            (false, json!({ "songs": [with("license", json!(null))] })),
+            (false, json!({ "songs": [with("releaseYear", json!(-1))] })),
+            (false, json!({ "songs": [with("releaseYear", json!("2024"))] })),
+            (false, json!({ "songs": [with("releaseYear", json!(5_000_000_000u64))] })),
+            (false, json!({ "songs": [with("trackNumber", json!(0))] })),
+            (false, json!({ "songs": [with("trackNumber", json!(1.5))] })),
+            (false, json!({ "songs": [with("discNumber", json!("1"))] })),
+            (false, json!({ "songs": [with("albumArtwork", json!(false))] })),
+            (false, json!({ "songs": [song], "albums": "Synthwave Chronicles" })),
+            (false, json!({ "songs": [song], "albums": [{ "name": "Synthwave Chronicles" }] })),
+            (false, json!({ "songs": [song], "albums": [{ "id": "", "name": "X" }] })),
+            (false, json!({ "songs": [song], "albums": [{ "id": "a", "name": 3 }] })),
+            (false, json!({ "songs": [song], "albums": [{ "id": "a", "name": "X", "albumType": "Mixtape" }] })),
+            (false, json!({ "songs": [song], "albums": [{ "id": "a", "name": "X", "releaseDate": "June 2023" }] })),
+            (false, json!({ "songs": [song], "albums": [{ "id": "a", "name": "X", "releaseDate": "2023-13-01" }] })),
+            (false, json!({ "songs": [song], "albums": [{ "id": "a", "name": "X", "upc": "12345" }] })),
+            (false, json!({ "songs": [song], "albums": [{ "id": "a", "name": "X", "artwork": 1 }] })),
+            (false, json!({ "songs": [with("albumId", json!(""))] })),
+            (false, json!({ "songs": [], "albums": [album] })),
+        ]
+    }
+
+    #[test]
+    fn test_schema_and_validator_agree() {
+        let schema = music_data_schema();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        for (expected, document) in corpus() {
+            let report = check_music_data(&document.to_string());
+            assert_eq!(report.is_valid(), expected, "validator disagrees on {}: {:?}", document, report.diagnostics);
+            assert_eq!(validator.is_valid(&document), expected, "schema disagrees on {}", document);
+        }
+    }
+
+    #[test]
+    fn test_cross_field_checks_are_validator_only() {
+        // Uniqueness, references and ordering across array items aren't expressible
+        // in JSON Schema, nor are calendar and check digit rules
+        let song = testing::song("a").json();
+        let mut referencing = song.clone();
+        referencing["albumId"] = json!("missing");
+        let album = json!({ "id": "x", "name": "A" });
+        let numbered = |id: &str, track: u32| {
+            let mut song = song.clone();
+            song["id"] = json!(id);
+            song["trackNumber"] = json!(track);
+            song
+        };
+        let (first_track, second_track, same_track) = (numbered("a", 1), numbered("b", 2), numbered("c", 1));
+        let schema = jsonschema::validator_for(&music_data_schema()).unwrap();
+
+        for (document, code) in [
+            (json!({ "songs": [song, song] }), DiagnosticCode::DuplicateId),
+            (json!({ "songs": [song], "albums": [album, album] }), DiagnosticCode::DuplicateId),
+            (json!({ "songs": [referencing] }), DiagnosticCode::UnknownAlbum),
+            (json!({ "songs": [first_track, same_track] }), DiagnosticCode::DuplicateTrack),
+            (json!({ "songs": [second_track] }), DiagnosticCode::TrackGap),
+            (json!({ "songs": [song], "albums": [{ "id": "x", "name": "A", "releaseDate": "2023-02-30" }] }), DiagnosticCode::InvalidValue),
+            (json!({ "songs": [song], "albums": [{ "id": "x", "name": "A", "upc": "036000291450" }] }), DiagnosticCode::InvalidValue),
+        ] {
+            let report = check_music_data(&document.to_string());
+            assert_eq!(report.first_error().unwrap().code, code, "{}", document);
+            assert!(schema.is_valid(&document), "{}", document);
+        }
+    }
+
+    #[test]
+    fn test_schema_shape() {
+        let schema = music_data_schema();
+        assert_eq!(schema["$schema"], SCHEMA_DIALECT);
+        assert_eq!(schema["$defs"]["ReleaseType"]["enum"], json!(["Independent", "NCS", "Monstercat"]));
+        assert_eq!(schema["$defs"]["AlbumType"]["enum"], json!(["Single", "EP", "LP", "Compilation"]));
+        assert_eq!(schema["$defs"]["Album"]["required"], json!(["id", "name"]));
+        assert_eq!(schema["$defs"]["Song"]["required"].as_array().unwrap().len(), 7);
+
+        let examples = schema["$defs"]["License"]["examples"].as_array().unwrap();
+        assert!(examples.contains(&json!("CC BY-NC-SA 4.0")));
+        assert!(examples.contains(&json!("BGML-P")));
+        // Every example round-trips through the license parser unchanged
+        for example in examples {
+            assert_eq!(&License::parse(example.as_str().unwrap()).to_string(), example);
+        }
+    }
+
+    #[test]
+    fn test_site_data_matches_schema() {
+        let data: Value = serde_json::from_str(include_str!("../../src/data/music.json")).unwrap();
+        assert!(jsonschema::validator_for(&music_data_schema()).unwrap().is_valid(&data));
+    }
+
+    #[test]
+    fn test_published_schema_is_current() {
+        // Regenerate with `UPDATE_SCHEMA=1 cargo test published_schema`
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../public/music.schema.json");
+        let expected = format!("{}\n", music_json_schema());
+        if std::env::var_os("UPDATE_SCHEMA").is_some() {
+            std::fs::write(path, &expected).unwrap();
+        }
+        let published = std::fs::read_to_string(path).unwrap_or_default();
+        assert!(published == expected, "public/music.schema.json is out of date; rerun with UPDATE_SCHEMA=1");
+    }
+}
@@ -0,0 +1,376 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::{Bound, Range};
+use unicode_normalization::char::is_combining_mark;
+use crate::index_file::songs_fingerprint;
+use crate::js::{from_js, to_js};
+use crate::text::{edit_distance, fold};
+use crate::validation::Song;
+
+/// BM25 term frequency saturation
+const K1: f64 = 1.2;
+/// BM25 field length normalization
+const B: f64 = 0.75;
+
+/// Song field covered by the search index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchField {
+    Title,
+    /// Album name
+    Album,
+    License,
+    ReleaseType,
+}
+
+impl SearchField {
+    pub const ALL: [SearchField; 4] = [SearchField::Title, SearchField::Album, SearchField::License, SearchField::ReleaseType];
+
+    /// How much a match in this field counts towards a song's score
+    pub fn weight(&self) -> f64 {
+        match self {
+            SearchField::Title => 3.0,
+            SearchField::Album => 2.0,
+            SearchField::License | SearchField::ReleaseType => 1.0,
+        }
+    }
+
+    pub(crate) fn text(&self, song: &Song) -> String {
+        match self {
+            SearchField::Title => song.title.clone(),
+            SearchField::Album => song.album_name.clone(),
+            SearchField::License => song.license.clone(),
+            SearchField::ReleaseType => format!("{:?}", song.release_type),
+        }
+    }
+}
+
+/// Byte ranges of the words in `text`: runs of letters and digits (with their
+/// combining marks), keeping decimal points inside numbers
+pub(crate) fn word_spans(text: &str) -> Vec<Range<usize>> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, &(offset, c)) in chars.iter().enumerate() {
+        let decimal_point = c == '.'
+            && i > 0
+            && chars[i - 1].1.is_ascii_digit()
+            && chars.get(i + 1).is_some_and(|(_, next)| next.is_ascii_digit());
+        let in_word = c.is_alphanumeric() || decimal_point || (start.is_some() && is_combining_mark(c));
+        match (in_word, start) {
+            (true, None) => start = Some(offset),
+            (false, Some(word_start)) => {
+                spans.push(word_start..offset);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(word_start) = start {
+        spans.push(word_start..text.len());
+    }
+    spans
+}
+
+/// Splits text into search terms: its words, folded (see `text::fold`)
+/// e.g. "Café Nights (CC BY 4.0)" gives ["cafe", "nights", "cc", "by", "4.0"]
+pub fn tokenize(text: &str) -> Vec<String> {
+    word_spans(text).into_iter().map(|span| fold(&text[span])).filter(|term| !term.is_empty()).collect()
+}
+
+/// How many edits a query term may be from a word and still match it:
+/// none for short terms, where a typo often makes another real word
+pub(crate) fn typo_budget(term: &str) -> usize {
+    match term.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Occurrences of a term in one field of one song
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Posting {
+    /// Position of the song in the indexed list
+    pub(crate) song: u32,
+    pub(crate) field: SearchField,
+    pub(crate) count: u32,
+}
+
+/// A ranked search result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+pub struct SearchHit {
+    pub id: String,
+    /// BM25 relevance; only meaningful relative to other hits for the same query
+    pub score: f64,
+}
+
+/// Inverted index over song titles, album names, licenses and release types,
+/// ranking matches with BM25
+/// Queries match songs containing every query term; the last term also
+/// matches words it begins, so results keep up while a query is being typed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchIndex {
+    /// Song IDs, by position in the indexed list
+    pub(crate) ids: Vec<String>,
+    /// Term to postings, ordered by song
+    pub(crate) postings: BTreeMap<String, Vec<Posting>>,
+    /// Number of terms in each field of each song, in `SearchField::ALL` order
+    pub(crate) field_lengths: Vec<[u32; 4]>,
+    /// Mean of `field_lengths` over all songs
+    pub(crate) average_lengths: [f64; 4],
+    /// `songs_fingerprint` of the indexed songs
+    pub(crate) fingerprint: u64,
+}
+
+impl SearchIndex {
+    pub fn build(songs: &[Song]) -> Self {
+        let mut index = SearchIndex {
+            ids: songs.iter().map(|song| song.id.clone()).collect(),
+            fingerprint: songs_fingerprint(songs),
+            ..Default::default()
+        };
+        for (position, song) in songs.iter().enumerate() {
+            let mut lengths = [0; 4];
+            for (slot, field) in SearchField::ALL.into_iter().enumerate() {
+                let terms = tokenize(&field.text(song));
+                lengths[slot] = terms.len() as u32;
+
+                let mut counts: HashMap<String, u32> = HashMap::new();
+                for term in terms {
+                    *counts.entry(term).or_default() += 1;
+                }
+                for (term, count) in counts {
+                    index.postings.entry(term).or_default().push(Posting { song: position as u32, field, count });
+                }
+            }
+            index.field_lengths.push(lengths);
+        }
+        index.compute_average_lengths();
+        index
+    }
+
+    pub(crate) fn compute_average_lengths(&mut self) {
+        if self.field_lengths.is_empty() {
+            return;
+        }
+        for slot in 0..4 {
+            let total: u32 = self.field_lengths.iter().map(|lengths| lengths[slot]).sum();
+            self.average_lengths[slot] = f64::from(total) / self.field_lengths.len() as f64;
+        }
+    }
+
+    /// Number of indexed songs
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Scores every song containing `term`, by song position
+    fn score_term(&self, term: &str, scores: &mut HashMap<u32, f64>) {
+        let Some(postings) = self.postings.get(term) else { return };
+
+        let mut songs: Vec<u32> = postings.iter().map(|posting| posting.song).collect();
+        songs.dedup();
+        let total = self.len() as f64;
+        let matching = songs.len() as f64;
+        let idf = (1.0 + (total - matching + 0.5) / (matching + 0.5)).ln();
+
+        for posting in postings {
+            let slot = posting.field as usize;
+            let length = f64::from(self.field_lengths[posting.song as usize][slot]);
+            let average = self.average_lengths[slot].max(1.0);
+            let count = f64::from(posting.count);
+            let saturated = count * (K1 + 1.0) / (count + K1 * (1.0 - B + B * length / average));
+            *scores.entry(posting.song).or_default() += idf * posting.field.weight() * saturated;
+        }
+    }
+
+    /// Scores songs by the best of several terms, each discounted by a factor
+    fn score_best<'t>(&self, terms: impl Iterator<Item = (&'t str, f64)>) -> HashMap<u32, f64> {
+        let mut scores: HashMap<u32, f64> = HashMap::new();
+        for (term, discount) in terms {
+            let mut term_scores = HashMap::new();
+            self.score_term(term, &mut term_scores);
+            for (song, score) in term_scores {
+                let best = scores.entry(song).or_default();
+                *best = best.max(score * discount);
+            }
+        }
+        scores
+    }
+
+    /// Scores songs by the indexed words `term` may be a misspelling of
+    /// Each edit halves the score, down from the exact match's
+    fn score_fuzzy(&self, term: &str) -> HashMap<u32, f64> {
+        let budget = typo_budget(term);
+        let length = term.chars().count();
+        let candidates = self
+            .postings
+            .keys()
+            .filter(|candidate| budget > 0 && candidate.chars().count().abs_diff(length) <= budget)
+            .map(|candidate| (candidate.as_str(), edit_distance(term, candidate)))
+            .filter(|&(_, distance)| distance <= budget)
+            .map(|(candidate, distance)| (candidate, 0.5_f64.powi(distance as i32)));
+        self.score_best(candidates)
+    }
+
+    /// Songs matching `query`, best first; ties keep index order
+    /// A term that matches no word exactly (or, for the last term, no word's
+    /// beginning) matches words within its typo budget instead, so "nigths"
+    /// finds "Nights". An empty query matches nothing
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let terms = tokenize(query);
+        let typing_last_term = !query.ends_with(char::is_whitespace);
+
+        let mut totals: Option<HashMap<u32, f64>> = None;
+        for (i, term) in terms.iter().enumerate() {
+            let scores = if i + 1 == terms.len() && typing_last_term {
+                // Each song scores by its best word starting with the term
+                let candidates = self
+                    .postings
+                    .range::<str, _>((Bound::Included(term.as_str()), Bound::Unbounded))
+                    .map(|(candidate, _)| candidate.as_str())
+                    .take_while(|candidate| candidate.starts_with(term.as_str()));
+                self.score_best(candidates.map(|candidate| (candidate, 1.0)))
+            } else {
+                self.score_best(std::iter::once((term.as_str(), 1.0)))
+            };
+            let scores = if scores.is_empty() { self.score_fuzzy(term) } else { scores };
+
+            totals = Some(match totals {
+                None => scores,
+                Some(totals) => totals
+                    .into_iter()
+                    .filter_map(|(song, total)| scores.get(&song).map(|score| (song, total + score)))
+                    .collect(),
+            });
+        }
+
+        let mut hits: Vec<(u32, f64)> = totals.unwrap_or_default().into_iter().collect();
+        hits.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        hits.into_iter().map(|(song, score)| SearchHit { id: self.ids[song as usize].clone(), score }).collect()
+    }
+
+    /// IDs of the songs matching `query`, best first
+    pub fn search_ids(&self, query: &str) -> Vec<String> {
+        self.search(query).into_iter().map(|hit| hit.id).collect()
+    }
+}
+
+/// Searches songs by title, album, license and release type
+/// Returns JSON string of the matching song IDs, best match first
+#[wasm_bindgen]
+pub fn search_song_ids(songs_json: &str, query: &str) -> String {
+    let songs: Vec<Song> = match serde_json::from_str(songs_json) {
+        Ok(s) => s,
+        Err(e) => return json!({ "error": format!("Invalid JSON: {}", e) }).to_string(),
+    };
+    json!(SearchIndex::build(&songs).search_ids(query)).to_string()
+}
+
+/// Searches an array of song objects, returning `{ id, score }` hits, best first
+/// Every query term must match; the last one also matches words it begins
+#[wasm_bindgen(js_name = searchSongs, unchecked_return_type = "SearchHit[]")]
+pub fn search_songs_js(#[wasm_bindgen(unchecked_param_type = "Song[]")] songs: JsValue, query: &str) -> Result<JsValue, JsError> {
+    let songs: Vec<Song> = from_js(songs, "song list")?;
+    to_js(&SearchIndex::build(&songs).search(query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use crate::validation::ReleaseType;
+
+    fn song(id: &str, title: &str, album: &str, release_type: ReleaseType, license: &str) -> Song {
+        testing::song(id)
+            .title(title)
+            .album(album)
+            .release_type(release_type)
+            .license(license)
+            .build()
+    }
+
+    fn catalog() -> Vec<Song> {
+        vec![
+            song("s1", "Digital Dreams", "Synthwave Chronicles", ReleaseType::Independent, "CC BY 4.0"),
+            song("s2", "Neon Nights", "Synthwave Chronicles", ReleaseType::Independent, "All Rights Reserved"),
+            song("s3", "Electric Pulse", "Pulse", ReleaseType::NCS, ""),
+            song("s4", "Café Pulse Pulse", "Night Drive", ReleaseType::Monstercat, "BGML-P"),
+        ]
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("Café Nights (CC BY 4.0)"), ["cafe", "nights", "cc", "by", "4.0"]);
+        assert_eq!(tokenize("  8-Bit  Adventures... v2."), ["8", "bit", "adventures", "v2"]);
+        assert!(tokenize(" - ").is_empty());
+    }
+
+    #[test]
+    fn test_search_ranks_matches() {
+        let index = SearchIndex::build(&catalog());
+        assert_eq!(index.len(), 4);
+
+        // Matches in several fields add up
+        assert_eq!(index.search_ids("pulse "), ["s3", "s4"]);
+        assert_eq!(index.search_ids("CAFE"), ["s4"]);
+        assert_eq!(index.search_ids("neon nights "), ["s2"]);
+        assert_eq!(index.search_ids("ncs"), ["s3"]);
+        assert_eq!(index.search_ids("cc by"), ["s1"]);
+        assert!(index.search_ids("neon pulse").is_empty());
+        assert!(index.search_ids("").is_empty());
+
+        let hits = index.search("synthwave ");
+        assert_eq!(hits.iter().map(|hit| hit.id.as_str()).collect::<Vec<_>>(), ["s1", "s2"]);
+        assert_eq!(hits[0].score, hits[1].score);
+    }
+
+    #[test]
+    fn test_search_matches_prefix_of_last_term() {
+        let index = SearchIndex::build(&catalog());
+        assert_eq!(index.search_ids("synth"), ["s1", "s2"]);
+        assert_eq!(index.search_ids("neon nig"), ["s2"]);
+        assert!(index.search_ids("nig neon").is_empty());
+        assert!(index.search_ids("synth ").is_empty());
+        // Words the term begins score like the term itself, so s2's title beats s4's album
+        assert_eq!(index.search_ids("night"), ["s2", "s4"]);
+    }
+
+    #[test]
+    fn test_search_tolerates_typos() {
+        let index = SearchIndex::build(&catalog());
+        assert_eq!(index.search_ids("Neon Nigths"), ["s2"]);
+        assert_eq!(index.search_ids("electirc "), ["s3"]);
+        assert_eq!(index.search_ids("synthwvae chronciles"), ["s1", "s2"]);
+        // Exact matches rank above typos
+        let exact = index.search("pulse ")[0].score;
+        assert!(index.search("pluse ")[0].score < exact);
+        assert!(index.search("pluze ").is_empty());
+        // Short terms must be exact
+        assert!(index.search_ids("cx ").is_empty());
+        assert_eq!(typo_budget("abc"), 0);
+        assert_eq!(typo_budget("chronicles"), 2);
+    }
+
+    #[test]
+    fn test_word_spans() {
+        let text = "Cafe\u{301} 4.0, ok.";
+        let words: Vec<&str> = word_spans(text).into_iter().map(|span| &text[span]).collect();
+        assert_eq!(words, ["Cafe\u{301}", "4.0", "ok"]);
+    }
+
+    #[test]
+    fn test_search_song_ids() {
+        let songs = serde_json::to_string(&catalog()).unwrap();
+        assert_eq!(search_song_ids(&songs, "electric"), r#"["s3"]"#);
+        assert!(search_song_ids("{", "x").contains("error"));
+        assert_eq!(search_song_ids("[]", "x"), "[]");
+    }
+}
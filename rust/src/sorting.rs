@@ -0,0 +1,364 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use serde_json::json;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use unicode_normalization::UnicodeNormalization;
+use crate::grouping::Album;
+use crate::js::{from_js, from_js_optional, to_js};
+use crate::text::fold;
+use crate::validation::Song;
+
+/// Leading articles skipped by `Collation::ignore_articles`
+const ARTICLES: [&str; 3] = ["the", "a", "an"];
+
+/// How text is compared
+/// Comparison is case- and accent-insensitive first; only otherwise equal
+/// strings are told apart by accents (unaccented first), then case (lowercase
+/// first), so "apple" < "Äpple" < "banana"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Collation {
+    /// Compare runs of digits by value, so "Album 2" sorts before "Album 10"
+    pub natural: bool,
+    /// Skip a leading "The", "A" or "An", so "The Sound" sorts under S
+    pub ignore_articles: bool,
+}
+
+impl Default for Collation {
+    fn default() -> Self {
+        Collation { natural: true, ignore_articles: false }
+    }
+}
+
+/// One run of a folded string: digits (when comparing naturally) or other text
+/// Numbers sort before text, and by value: fewer significant digits first
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Chunk {
+    Number { digits: usize, value: String },
+    Text(String),
+}
+
+/// Precomputed sort key; comparing keys compares their strings
+/// Worth building once when a string is compared many times, as in a sort
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CollationKey {
+    /// Folded text: no case, accents or extra whitespace
+    primary: Vec<Chunk>,
+    /// Lowercase text with accents, decomposed so bare letters sort first
+    secondary: String,
+    /// Which characters are uppercase
+    tertiary: Vec<bool>,
+    /// The original text, so only identical strings compare equal
+    text: String,
+}
+
+impl Collation {
+    pub fn key(&self, text: &str) -> CollationKey {
+        let stripped = if self.ignore_articles { strip_article(text) } else { text };
+        let folded = fold(stripped);
+        let primary = if self.natural { natural_chunks(&folded) } else { vec![Chunk::Text(folded)] };
+        CollationKey {
+            primary,
+            secondary: stripped.nfd().flat_map(char::to_lowercase).collect(),
+            tertiary: stripped.chars().map(char::is_uppercase).collect(),
+            text: text.to_string(),
+        }
+    }
+
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        self.key(a).cmp(&self.key(b))
+    }
+}
+
+/// `text` without a leading article, unless the article is all there is
+fn strip_article(text: &str) -> &str {
+    let trimmed = text.trim_start();
+    for article in ARTICLES {
+        let rest = trimmed.get(article.len()..).filter(|_| trimmed[..article.len()].eq_ignore_ascii_case(article));
+        if let Some(rest) = rest.filter(|rest| rest.starts_with(char::is_whitespace) && !rest.trim().is_empty()) {
+            return rest.trim_start();
+        }
+    }
+    text
+}
+
+fn natural_chunks(folded: &str) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut rest = folded;
+    while let Some(first) = rest.chars().next() {
+        let is_digit = first.is_ascii_digit();
+        let end = rest.find(|c: char| c.is_ascii_digit() != is_digit).unwrap_or(rest.len());
+        let (run, tail) = rest.split_at(end);
+        chunks.push(if is_digit {
+            let value = run.trim_start_matches('0');
+            Chunk::Number { digits: value.len(), value: value.to_string() }
+        } else {
+            Chunk::Text(run.to_string())
+        });
+        rest = tail;
+    }
+    chunks
+}
+
+/// Song attribute to sort on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum SortField {
+    Title,
+    /// Album name
+    Album,
+    /// Release year; songs without one come last in either direction
+    Year,
+    ReleaseType,
+    License,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+pub struct SortSpec {
+    pub field: SortField,
+    #[serde(default)]
+    pub order: SortOrder,
+}
+
+/// How `sort_songs` orders songs, e.g. `{ "by": [{ "field": "year", "order": "desc" }, { "field": "title" }] }`
+/// Songs equal on every field are ordered by ID
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[serde(default)]
+pub struct SongSort {
+    /// Fields to sort by, most significant first
+    pub by: Vec<SortSpec>,
+    /// How titles, album names and other text fields compare
+    #[serde(flatten)]
+    pub collation: Collation,
+}
+
+/// A song's value for one sort field
+#[derive(PartialEq, Eq)]
+enum FieldKey {
+    Text(CollationKey),
+    Year(Option<u32>),
+}
+
+/// Sort key for a whole song: its field values in spec order, then its ID
+#[derive(PartialEq, Eq)]
+struct SongKey {
+    fields: Vec<(FieldKey, SortOrder)>,
+    id: String,
+}
+
+impl Ord for SongKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.fields
+            .iter()
+            .zip(&other.fields)
+            .map(|((a, order), (b, _))| {
+                let ordering = match (a, b) {
+                    (FieldKey::Year(Some(_)), FieldKey::Year(None)) => return Ordering::Less,
+                    (FieldKey::Year(None), FieldKey::Year(Some(_))) => return Ordering::Greater,
+                    _ => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+                };
+                if *order == SortOrder::Desc { ordering.reverse() } else { ordering }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| self.id.cmp(&other.id))
+    }
+}
+
+impl PartialOrd for SongKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialOrd for FieldKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (FieldKey::Text(a), FieldKey::Text(b)) => Some(a.cmp(b)),
+            (FieldKey::Year(a), FieldKey::Year(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+}
+
+impl SongSort {
+    fn key(&self, song: &Song) -> SongKey {
+        let text = |text: &str| FieldKey::Text(self.collation.key(text));
+        let fields = self
+            .by
+            .iter()
+            .map(|spec| {
+                let key = match spec.field {
+                    SortField::Title => text(&song.title),
+                    SortField::Album => text(&song.album_name),
+                    SortField::Year => FieldKey::Year(song.release_year),
+                    SortField::ReleaseType => text(&format!("{:?}", song.release_type)),
+                    SortField::License => text(&song.license),
+                };
+                (key, spec.order)
+            })
+            .collect();
+        SongKey { fields, id: song.id.clone() }
+    }
+}
+
+/// Sorts songs (or references to them) in place by `sort`
+pub fn sort_songs<S: Borrow<Song>>(songs: &mut [S], sort: &SongSort) {
+    songs.sort_by_cached_key(|song| sort.key(song.borrow()));
+}
+
+/// Sorts albums in place by name, then ID
+pub fn sort_albums(albums: &mut [Album], collation: &Collation) {
+    albums.sort_by_cached_key(|album| (collation.key(&album.name), album.id.clone()));
+}
+
+/// Sorts songs by a sort spec (`SongSort` as JSON)
+/// Returns JSON string of the sorted songs
+#[wasm_bindgen]
+pub fn sort_song_list(songs_json: &str, sort_json: &str) -> String {
+    let mut songs: Vec<Song> = match serde_json::from_str(songs_json) {
+        Ok(s) => s,
+        Err(e) => return json!({ "error": format!("Invalid JSON: {}", e) }).to_string(),
+    };
+    let sort: SongSort = match serde_json::from_str(sort_json) {
+        Ok(sort) => sort,
+        Err(e) => return json!({ "error": format!("Invalid sort: {}", e) }).to_string(),
+    };
+
+    sort_songs(&mut songs, &sort);
+    match serde_json::to_string(&songs) {
+        Ok(json) => json,
+        Err(e) => json!({ "error": format!("Serialization error: {}", e) }).to_string(),
+    }
+}
+
+/// Sorts an array of song objects by `{ by: [{ field, order }], natural, ignoreArticles }`
+#[wasm_bindgen(js_name = sortSongs, unchecked_return_type = "Song[]")]
+pub fn sort_songs_js(
+    #[wasm_bindgen(unchecked_param_type = "Song[]")] songs: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "SongSort")] sort: JsValue,
+) -> Result<JsValue, JsError> {
+    let mut songs: Vec<Song> = from_js(songs, "song list")?;
+    let sort: SongSort = from_js(sort, "sort")?;
+    sort_songs(&mut songs, &sort);
+    to_js(&songs)
+}
+
+/// Sorts an array of albums by name
+#[wasm_bindgen(js_name = sortAlbums, unchecked_return_type = "Album[]")]
+pub fn sort_albums_js(
+    #[wasm_bindgen(unchecked_param_type = "Album[]")] albums: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "Collation")] collation: Option<JsValue>,
+) -> Result<JsValue, JsError> {
+    let mut albums: Vec<Album> = from_js(albums, "album list")?;
+    let collation: Option<Collation> = from_js_optional(collation, "collation")?;
+    sort_albums(&mut albums, &collation.unwrap_or_default());
+    to_js(&albums)
+}
+
+/// Compares two strings for sorting, like `localeCompare`: negative, zero or positive
+#[wasm_bindgen(js_name = compareText)]
+pub fn compare_text_js(
+    a: &str,
+    b: &str,
+    #[wasm_bindgen(unchecked_param_type = "Collation")] collation: Option<JsValue>,
+) -> Result<i32, JsError> {
+    let collation: Option<Collation> = from_js_optional(collation, "collation")?;
+    Ok(collation.unwrap_or_default().compare(a, b) as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn sorted(texts: &[&str], collation: Collation) -> Vec<String> {
+        let mut texts: Vec<String> = texts.iter().map(|text| text.to_string()).collect();
+        texts.sort_by_cached_key(|text| collation.key(text));
+        texts
+    }
+
+    #[test]
+    fn test_collation() {
+        let collation = Collation::default();
+        assert_eq!(sorted(&["album 10", "Album 2", "album 1"], collation), ["album 1", "Album 2", "album 10"]);
+        assert_eq!(sorted(&["Zebra", "Écho", "echo", "Echo", "apple"], collation), ["apple", "echo", "Echo", "Écho", "Zebra"]);
+        assert_eq!(sorted(&["track 007", "track 7", "track 10"], collation), ["track 007", "track 7", "track 10"]);
+        assert_eq!(collation.compare("Synthwave  Chronicles", "Synthwave Chronicles"), Ordering::Less);
+        assert_eq!(collation.compare("Ｎights", "Nights"), Ordering::Greater);
+        assert_eq!(collation.compare("Nights", "Nights"), Ordering::Equal);
+
+        let plain = Collation { natural: false, ..Collation::default() };
+        assert_eq!(sorted(&["album 10", "album 2"], plain), ["album 10", "album 2"]);
+    }
+
+    #[test]
+    fn test_collation_ignores_articles() {
+        let collation = Collation { ignore_articles: true, ..Collation::default() };
+        assert_eq!(
+            sorted(&["The Sound", "Anthem", "A Rush", "The", "Theory", "an echo"], collation),
+            ["Anthem", "an echo", "A Rush", "The Sound", "The", "Theory"]
+        );
+        assert_eq!(sorted(&["The Sound", "Rush"], Collation::default()), ["Rush", "The Sound"]);
+    }
+
+    fn song(id: &str, title: &str, album: &str, year: Option<u32>) -> Song {
+        testing::song(id).title(title).album(album).year(year).build()
+    }
+
+    fn ids(songs: &[Song]) -> Vec<&str> {
+        songs.iter().map(|song| song.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_songs_by_multiple_fields() {
+        let mut songs = vec![
+            song("s1", "Track 10", "Élan", Some(2023)),
+            song("s2", "track 2", "Elan", None),
+            song("s3", "Aurora", "Zenith", Some(2024)),
+            song("s4", "Track 2", "Elan", Some(2023)),
+        ];
+
+        let sort: SongSort = serde_json::from_str(r#"{"by": [{"field": "title"}]}"#).unwrap();
+        sort_songs(&mut songs, &sort);
+        assert_eq!(ids(&songs), ["s3", "s2", "s4", "s1"]);
+
+        let sort: SongSort = serde_json::from_str(r#"{"by": [{"field": "year", "order": "desc"}, {"field": "title"}]}"#).unwrap();
+        sort_songs(&mut songs, &sort);
+        assert_eq!(ids(&songs), ["s3", "s4", "s1", "s2"]);
+
+        let sort: SongSort = serde_json::from_str(r#"{"by": [{"field": "year"}]}"#).unwrap();
+        sort_songs(&mut songs, &sort);
+        assert_eq!(ids(&songs), ["s1", "s4", "s3", "s2"]);
+
+        let sort: SongSort = serde_json::from_str(r#"{"by": [{"field": "album", "order": "desc"}, {"field": "title", "order": "desc"}]}"#).unwrap();
+        sort_songs(&mut songs, &sort);
+        assert_eq!(ids(&songs), ["s3", "s1", "s4", "s2"]);
+    }
+
+    #[test]
+    fn test_sort_song_list() {
+        let songs = json!([song("b", "Beta", "A", None), song("a", "alpha", "A", None)]).to_string();
+        let sorted: Vec<Song> = serde_json::from_str(&sort_song_list(&songs, r#"{"by": [{"field": "title"}]}"#)).unwrap();
+        assert_eq!(ids(&sorted), ["a", "b"]);
+
+        let error: serde_json::Value = serde_json::from_str(&sort_song_list(&songs, r#"{"by": [{"field": "bpm"}]}"#)).unwrap();
+        assert!(error["error"].as_str().unwrap().starts_with("Invalid sort"));
+    }
+
+    #[test]
+    fn test_sort_albums() {
+        let mut albums = vec![Album::named("Vol. 10", vec![]), Album::named("vol. 9", vec![]), Album::named("Ämbient", vec![])];
+        sort_albums(&mut albums, &Collation::default());
+        assert_eq!(albums.iter().map(|album| album.name.as_str()).collect::<Vec<_>>(), ["Ämbient", "vol. 9", "Vol. 10"]);
+    }
+}
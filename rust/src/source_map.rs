@@ -0,0 +1,290 @@
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use std::collections::HashMap;
+use crate::report::pointer_join;
+
+/// Location of a value in the original JSON text
+/// Byte offsets are 0-based and end-exclusive, lines and columns are 1-based
+/// with columns counted in characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceSpan {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+/// Maps JSON pointers to the spans of their values in a source document
+/// Built with a single scan of the text, separate from the serde parse
+#[derive(Debug, Clone)]
+pub struct SourceMap<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+    spans: HashMap<String, (usize, usize)>,
+}
+
+impl<'a> SourceMap<'a> {
+    /// Indexes `text`. Malformed JSON is tolerated: values scanned before the
+    /// first syntax error are still located, and offsets can always be converted
+    pub fn new(text: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        let mut scanner = Scanner { bytes: text.as_bytes(), text, pos: 0, depth: 0, spans: HashMap::new() };
+        let _ = scanner.value(String::new());
+
+        SourceMap { text, line_starts, spans: scanner.spans }
+    }
+
+    /// Span of the value at `pointer`, or of its nearest existing ancestor
+    /// (so a missing field points at the object it is missing from)
+    pub fn locate(&self, pointer: &str) -> Option<SourceSpan> {
+        let mut pointer = pointer;
+        loop {
+            if let Some(&(start, end)) = self.spans.get(pointer) {
+                return Some(self.span(start, end));
+            }
+            pointer = &pointer[..pointer.rfind('/')?];
+        }
+    }
+
+    /// Builds a span from byte offsets
+    pub fn span(&self, start: usize, end: usize) -> SourceSpan {
+        let (line, column) = self.position(start);
+        let (end_line, end_column) = self.position(end);
+        SourceSpan { start, end, line, column, end_line, end_column }
+    }
+
+    /// Converts a 1-based line and byte column (as reported by serde_json) to a
+    /// byte offset, moved back to the start of the character it falls in
+    pub fn offset_of(&self, line: usize, column: usize) -> usize {
+        let Some(&line_start) = self.line_starts.get(line.saturating_sub(1)) else {
+            return self.text.len();
+        };
+        let line_end = self.line_starts.get(line).map_or(self.text.len(), |&next| next - 1);
+        let mut offset = (line_start + column.saturating_sub(1)).min(line_end);
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    }
+
+    /// Converts a byte offset to a 1-based line/column
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.text.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let line_start = self.line_starts[line - 1];
+        let column = self.text.get(line_start..offset).map_or(1, |s| s.chars().count() + 1);
+        (line, column)
+    }
+}
+
+/// Deepest nesting the scanner follows, matching serde_json's recursion limit;
+/// deeper documents are rejected by serde anyway
+const MAX_DEPTH: usize = 128;
+
+/// Minimal JSON scanner that records the byte span of every value
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    text: &'a str,
+    pos: usize,
+    /// Arrays and objects currently open
+    depth: usize,
+    spans: HashMap<String, (usize, usize)>,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.skip_whitespace();
+        if self.peek()? != byte {
+            return None;
+        }
+        self.pos += 1;
+        Some(())
+    }
+
+    fn value(&mut self, pointer: String) -> Option<()> {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.peek()? {
+            b'{' | b'[' if self.depth == MAX_DEPTH => return None,
+            b'{' => self.nested(|scanner| scanner.object(&pointer))?,
+            b'[' => self.nested(|scanner| scanner.array(&pointer))?,
+            b'"' => {
+                self.string()?;
+            }
+            _ => {
+                while matches!(self.peek(), Some(b) if !b",]} \t\n\r".contains(&b)) {
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    return None;
+                }
+            }
+        }
+        self.spans.insert(pointer, (start, self.pos));
+        Some(())
+    }
+
+    /// Scans an array or object one level deeper
+    fn nested(&mut self, scan: impl FnOnce(&mut Self) -> Option<()>) -> Option<()> {
+        self.depth += 1;
+        let result = scan(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn object(&mut self, pointer: &str) -> Option<()> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek()? == b'}' {
+            self.pos += 1;
+            return Some(());
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(b':')?;
+            self.value(pointer_join(pointer, &key))?;
+            self.skip_whitespace();
+            match self.peek()? {
+                b',' => self.pos += 1,
+                b'}' => {
+                    self.pos += 1;
+                    return Some(());
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn array(&mut self, pointer: &str) -> Option<()> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek()? == b']' {
+            self.pos += 1;
+            return Some(());
+        }
+        let mut index = 0;
+        loop {
+            self.value(format!("{}/{}", pointer, index))?;
+            index += 1;
+            self.skip_whitespace();
+            match self.peek()? {
+                b',' => self.pos += 1,
+                b']' => {
+                    self.pos += 1;
+                    return Some(());
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// Scans a string literal and returns its unescaped contents
+    fn string(&mut self) -> Option<String> {
+        let start = self.pos;
+        if self.peek()? != b'"' {
+            return None;
+        }
+        self.pos += 1;
+        loop {
+            match self.peek()? {
+                b'"' => break,
+                b'\\' => self.pos += 2,
+                _ => self.pos += 1,
+            }
+        }
+        self.pos += 1;
+        serde_json::from_str(self.text.get(start..self.pos)?).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "{\n  \"songs\": [\n    {\"id\": \"a\", \"title\": \"Café\", \"releaseType\": \"Label\"}\n  ],\n  \"a/b\": null\n}";
+
+    #[test]
+    fn test_locate_values() {
+        let map = SourceMap::new(DOC);
+
+        let span = map.locate("/songs/0/releaseType").unwrap();
+        assert_eq!(&DOC[span.start..span.end], "\"Label\"");
+        assert_eq!((span.line, span.column), (3, 49));
+        assert_eq!((span.end_line, span.end_column), (3, 56));
+
+        let span = map.locate("/songs/0/title").unwrap();
+        assert_eq!(&DOC[span.start..span.end], "\"Café\"");
+
+        let span = map.locate("/a~1b").unwrap();
+        assert_eq!(&DOC[span.start..span.end], "null");
+        assert_eq!((span.line, span.column), (5, 10));
+    }
+
+    #[test]
+    fn test_locate_missing_field_falls_back_to_parent() {
+        let map = SourceMap::new(DOC);
+        let span = map.locate("/songs/0/license").unwrap();
+        assert!(DOC[span.start..span.end].starts_with("{\"id\""));
+        assert_eq!((span.line, span.column), (3, 5));
+
+        let root = map.locate("").unwrap();
+        assert_eq!((root.start, root.end), (0, DOC.len()));
+    }
+
+    #[test]
+    fn test_malformed_json_still_locates_prefix() {
+        let text = "{\"songs\": [{\"id\": \"a\"}, {\"id\": ";
+        let map = SourceMap::new(text);
+        assert!(map.locate("/songs/0/id").is_some());
+        assert!(map.locate("").is_none());
+    }
+
+    #[test]
+    fn test_offset_of_line_and_column() {
+        let map = SourceMap::new(DOC);
+        // Byte column: "Café" earlier on the line takes one column more than its characters
+        let offset = map.offset_of(3, 50);
+        assert_eq!(&DOC[offset..offset + 7], "\"Label\"");
+        assert_eq!(map.offset_of(99, 1), DOC.len());
+    }
+
+    #[test]
+    fn test_offset_of_counts_bytes() {
+        // serde_json reports byte columns: "é" is two bytes wide
+        let text = "{\"title\": \"Café\" x}";
+        let error = serde_json::from_str::<serde_json::Value>(text).unwrap_err();
+        let map = SourceMap::new(text);
+        let offset = map.offset_of(error.line(), error.column());
+        assert_eq!(&text[offset..], "x}");
+        // Columns inside a character move back to its start
+        assert_eq!(map.offset_of(1, 16), text.find('é').unwrap());
+    }
+
+    #[test]
+    fn test_deep_nesting_is_not_followed() {
+        let text = "[".repeat(200_000);
+        let map = SourceMap::new(&text);
+        assert!(map.locate("/0").is_none());
+
+        let text = format!("{}1{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+        let map = SourceMap::new(&text);
+        assert!(map.locate("").is_some());
+    }
+}
@@ -0,0 +1,311 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use serde_json::json;
+use std::collections::HashSet;
+use std::ops::Range;
+use unicode_normalization::char::is_combining_mark;
+use crate::grouping::{AlbumInfo, AlbumResolver};
+use crate::js::{from_js, to_js};
+use crate::search::{tokenize, typo_budget, word_spans};
+use crate::sorting::Collation;
+use crate::text::{edit_distance, fold};
+use crate::validation::Song;
+
+/// Number of suggestions returned when no limit is given
+pub const DEFAULT_SUGGESTION_LIMIT: usize = 10;
+
+/// What a suggestion completes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub enum SuggestionKind {
+    Title,
+    Album,
+}
+
+/// Part of a suggestion's text that matches the query
+/// Offsets are in UTF-16 code units, as `String.prototype.slice` takes them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+pub struct Highlight {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A song title or album name the query may be the start of, or a misspelling of
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct Suggestion {
+    /// The title or album name, as written
+    pub text: String,
+    pub kind: SuggestionKind,
+    /// Song the title belongs to; absent for albums
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub song_id: Option<String>,
+    /// From 0 to 1: whole words score highest, then word beginnings, then typos
+    pub score: f64,
+    /// Matching words (or the matching start of them), in order
+    pub highlights: Vec<Highlight>,
+}
+
+/// A word of a candidate text: folded, and where it is in the original
+#[derive(Debug, Clone)]
+struct Word {
+    folded: String,
+    span: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct Candidate {
+    text: String,
+    kind: SuggestionKind,
+    song_id: Option<String>,
+    words: Vec<Word>,
+}
+
+impl Candidate {
+    fn new(text: &str, kind: SuggestionKind, song_id: Option<String>) -> Self {
+        let words = word_spans(text)
+            .into_iter()
+            .map(|span| Word { folded: fold(&text[span.clone()]), span })
+            .filter(|word| !word.folded.is_empty())
+            .collect();
+        Candidate { text: text.to_string(), kind, song_id, words }
+    }
+
+    /// Scores the candidate against folded query terms; every term must match
+    /// one of its words. `None` when some term doesn't
+    fn suggest(&self, terms: &[String]) -> Option<Suggestion> {
+        let mut total = 0.0;
+        let mut matched: Vec<Range<usize>> = Vec::new();
+        for term in terms {
+            let (score, word, length) = self
+                .words
+                .iter()
+                .filter_map(|word| match_word(term, &word.folded).map(|(score, length)| (score, word, length)))
+                .max_by(|a, b| a.0.total_cmp(&b.0))?;
+            total += score;
+            let end = word.span.start + original_length(&self.text[word.span.clone()], length);
+            matched.push(word.span.start..end);
+        }
+
+        // Terms matching the same word highlight its longest match
+        matched.sort_by_key(|range| (range.start, std::cmp::Reverse(range.end)));
+        matched.dedup_by_key(|range| range.start);
+        let utf16 = |offset: usize| self.text[..offset].encode_utf16().count();
+        let highlights = matched.into_iter().map(|range| Highlight { start: utf16(range.start), end: utf16(range.end) }).collect();
+
+        Some(Suggestion {
+            text: self.text.clone(),
+            kind: self.kind,
+            song_id: self.song_id.clone(),
+            score: total / terms.len() as f64,
+            highlights,
+        })
+    }
+}
+
+/// How well folded query `term` matches folded `word`, and how many of the
+/// word's characters it covers
+/// Whole words score 1, word beginnings 0.8 to 1 depending on how much of the
+/// word is typed, and misspellings (of the word or of its beginning) at most
+/// 0.6, less for each edit
+fn match_word(term: &str, word: &str) -> Option<(f64, usize)> {
+    let term_length = term.chars().count();
+    let word_length = word.chars().count();
+    if term == word {
+        return Some((1.0, word_length));
+    }
+    if word.starts_with(term) {
+        return Some((0.8 + 0.2 * term_length as f64 / word_length as f64, term_length));
+    }
+
+    let budget = typo_budget(term);
+    if budget == 0 {
+        return None;
+    }
+    let whole = edit_distance(term, word);
+    let beginning: String = word.chars().take(term_length).collect();
+    let partial = edit_distance(term, &beginning);
+    let (distance, length) = if whole <= partial { (whole, word_length) } else { (partial, term_length.min(word_length)) };
+    (distance <= budget).then(|| (0.6 * (1.0 - distance as f64 / (term_length as f64 + 1.0)), length))
+}
+
+/// Byte length of the start of `word` whose folded form is `folded_length`
+/// characters long, keeping combining marks with their letters
+fn original_length(word: &str, folded_length: usize) -> usize {
+    let mut length = 0;
+    for (offset, c) in word.char_indices() {
+        if length >= folded_length && !is_combining_mark(c) {
+            return offset;
+        }
+        length += fold(c.encode_utf8(&mut [0; 4])).chars().count();
+    }
+    word.len()
+}
+
+/// Autocomplete over song titles and album names
+/// Query terms match words anywhere in a title or name: whole, by their
+/// beginning, or within a typo budget (see `search::typo_budget`), so
+/// "neon nigths" still suggests "Neon Nights"
+#[derive(Debug, Clone, Default)]
+pub struct Suggester {
+    /// Titles in song order, then album names in first-appearance order
+    candidates: Vec<Candidate>,
+}
+
+impl Suggester {
+    /// Indexes the songs' titles and their albums' names; albums are matched
+    /// to `declared` albums like `group_songs_with` does, and named once each
+    pub fn new(songs: &[Song], declared: &[AlbumInfo]) -> Self {
+        let mut candidates: Vec<Candidate> =
+            songs.iter().map(|song| Candidate::new(&song.title, SuggestionKind::Title, Some(song.id.clone()))).collect();
+
+        let resolver = AlbumResolver::new(declared, songs);
+        let mut seen = HashSet::new();
+        for song in songs {
+            let key = resolver.key(song);
+            let name = resolver.name(&key);
+            if !name.trim().is_empty() && seen.insert(key.clone()) {
+                candidates.push(Candidate::new(name, SuggestionKind::Album, None));
+            }
+        }
+        Suggester { candidates }
+    }
+
+    /// Up to `limit` suggestions for `query`, best first
+    /// Ties go to titles over albums, then alphabetically. An empty query
+    /// suggests nothing
+    pub fn suggest(&self, query: &str, limit: usize) -> Vec<Suggestion> {
+        let terms = tokenize(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let collation = Collation::default();
+        let mut suggestions: Vec<Suggestion> = self.candidates.iter().filter_map(|candidate| candidate.suggest(&terms)).collect();
+        suggestions.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then(a.kind.cmp(&b.kind))
+                .then_with(|| collation.compare(&a.text, &b.text))
+                .then_with(|| a.song_id.cmp(&b.song_id))
+        });
+        suggestions.truncate(limit);
+        suggestions
+    }
+}
+
+/// Suggests song titles and album names for a search box query
+/// Returns JSON string of up to `limit` (default 10) suggestions, best first
+#[wasm_bindgen]
+pub fn suggest_songs(songs_json: &str, query: &str, limit: Option<u32>) -> String {
+    let songs: Vec<Song> = match serde_json::from_str(songs_json) {
+        Ok(s) => s,
+        Err(e) => return json!({ "error": format!("Invalid JSON: {}", e) }).to_string(),
+    };
+    let limit = limit.map_or(DEFAULT_SUGGESTION_LIMIT, |limit| limit as usize);
+    json!(Suggester::new(&songs, &[]).suggest(query, limit)).to_string()
+}
+
+/// Suggests titles and album names from an array of song objects, with match
+/// scores and highlight ranges, best first
+#[wasm_bindgen(js_name = suggestSongs, unchecked_return_type = "Suggestion[]")]
+pub fn suggest_songs_js(
+    #[wasm_bindgen(unchecked_param_type = "Song[]")] songs: JsValue,
+    query: &str,
+    limit: Option<u32>,
+) -> Result<JsValue, JsError> {
+    let songs: Vec<Song> = from_js(songs, "song list")?;
+    let limit = limit.map_or(DEFAULT_SUGGESTION_LIMIT, |limit| limit as usize);
+    to_js(&Suggester::new(&songs, &[]).suggest(query, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use crate::validation::ReleaseType;
+
+    fn song(id: &str, title: &str, album: &str) -> Song {
+        testing::song(id)
+            .title(title)
+            .album(album)
+            .release_type(ReleaseType::Independent)
+            .build()
+    }
+
+    fn suggester() -> Suggester {
+        Suggester::new(
+            &[
+                song("s1", "Neon Nights", "Synthwave Chronicles"),
+                song("s2", "Night Drive", "synthwave chronicles"),
+                song("s3", "Café Noir", "Night Drive"),
+                song("s4", "Digital Dreams", "Synthwave Chronicles"),
+            ],
+            &[],
+        )
+    }
+
+    fn texts(suggestions: &[Suggestion]) -> Vec<(&str, SuggestionKind)> {
+        suggestions.iter().map(|suggestion| (suggestion.text.as_str(), suggestion.kind)).collect()
+    }
+
+    #[test]
+    fn test_suggest_completes_word_beginnings() {
+        let suggestions = suggester().suggest("nig", 10);
+        assert_eq!(
+            texts(&suggestions),
+            [
+                ("Night Drive", SuggestionKind::Title),
+                ("Night Drive", SuggestionKind::Album),
+                ("Neon Nights", SuggestionKind::Title),
+            ]
+        );
+        assert_eq!(suggestions[0].song_id.as_deref(), Some("s2"));
+        assert_eq!(suggestions[1].song_id, None);
+        // More of "Night" than of "Nights" is typed
+        assert!(suggestions[2].score < suggestions[0].score);
+        assert_eq!(suggestions[2].highlights, [Highlight { start: 5, end: 8 }]);
+
+        // Albums are suggested once, in their most common spelling
+        assert_eq!(texts(&suggester().suggest("chron", 10)), [("Synthwave Chronicles", SuggestionKind::Album)]);
+        assert_eq!(suggester().suggest("dr", 1).len(), 1);
+        assert!(suggester().suggest("  ", 10).is_empty());
+    }
+
+    #[test]
+    fn test_suggest_tolerates_typos() {
+        let suggestions = suggester().suggest("Neon Nigths", 10);
+        assert_eq!(texts(&suggestions), [("Neon Nights", SuggestionKind::Title)]);
+        assert_eq!(suggestions[0].highlights, [Highlight { start: 0, end: 4 }, Highlight { start: 5, end: 11 }]);
+        assert!(suggestions[0].score < 1.0);
+        assert_eq!(suggester().suggest("Neon Nights", 10)[0].score, 1.0);
+
+        // A misspelled beginning of a word still completes it
+        assert_eq!(texts(&suggester().suggest("digti", 10)), [("Digital Dreams", SuggestionKind::Title)]);
+        // Short terms must match exactly
+        assert!(suggester().suggest("nx", 10).is_empty());
+    }
+
+    #[test]
+    fn test_highlights_use_utf16_offsets() {
+        let suggester = Suggester::new(&[song("s1", "🎵 Cafe\u{301} Noir", "")], &[]);
+        let suggestions = suggester.suggest("cafe no", 10);
+        assert_eq!(texts(&suggestions), [("🎵 Cafe\u{301} Noir", SuggestionKind::Title)]);
+        // The emoji is two code units; the combining accent stays with its letter
+        assert_eq!(suggestions[0].highlights, [Highlight { start: 3, end: 8 }, Highlight { start: 9, end: 11 }]);
+        assert_eq!(original_length("e\u{301}x", 1), 3);
+    }
+
+    #[test]
+    fn test_suggest_songs() {
+        let songs = serde_json::to_string(&[song("s1", "Neon Nights", "Synthwave")]).unwrap();
+        let suggestions: serde_json::Value = serde_json::from_str(&suggest_songs(&songs, "synth", None)).unwrap();
+        assert_eq!(suggestions[0]["kind"], "album");
+        assert_eq!(suggestions[0]["highlights"], json!([{ "start": 0, "end": 5 }]));
+        assert!(suggestions[0].get("songId").is_none());
+        assert!(suggest_songs("{", "x", None).contains("error"));
+        assert_eq!(suggest_songs(&songs, "n", Some(0)), "[]");
+    }
+}
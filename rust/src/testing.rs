@@ -0,0 +1,87 @@
+use serde_json::Value;
+use crate::validation::{ReleaseType, Song};
+
+/// Builds songs for unit tests
+/// Fields left unset keep the defaults from `song`, so adding a field to
+/// `Song` only means updating this module
+pub(crate) struct SongBuilder(Song);
+
+/// A valid song: NCS release titled "T" on album "A", no Content ID, no license
+pub(crate) fn song(id: &str) -> SongBuilder {
+    SongBuilder(Song {
+        id: id.to_string(),
+        title: "T".to_string(),
+        album_name: "A".to_string(),
+        album_id: None,
+        track_number: None,
+        disc_number: None,
+        release_type: ReleaseType::NCS,
+        has_content_id: false,
+        streaming_link: "https://example.com".to_string(),
+        license: String::new(),
+        release_year: None,
+        album_artwork: None,
+        extra: Default::default(),
+    })
+}
+
+impl SongBuilder {
+    pub(crate) fn title(mut self, title: &str) -> Self {
+        self.0.title = title.to_string();
+        self
+    }
+
+    pub(crate) fn album(mut self, album_name: &str) -> Self {
+        self.0.album_name = album_name.to_string();
+        self
+    }
+
+    pub(crate) fn album_id(mut self, album_id: &str) -> Self {
+        self.0.album_id = Some(album_id.to_string());
+        self
+    }
+
+    pub(crate) fn track(mut self, track_number: impl Into<Option<u32>>) -> Self {
+        self.0.track_number = track_number.into();
+        self
+    }
+
+    pub(crate) fn disc(mut self, disc_number: impl Into<Option<u32>>) -> Self {
+        self.0.disc_number = disc_number.into();
+        self
+    }
+
+    pub(crate) fn release_type(mut self, release_type: ReleaseType) -> Self {
+        self.0.release_type = release_type;
+        self
+    }
+
+    pub(crate) fn content_id(mut self, has_content_id: bool) -> Self {
+        self.0.has_content_id = has_content_id;
+        self
+    }
+
+    pub(crate) fn link(mut self, streaming_link: &str) -> Self {
+        self.0.streaming_link = streaming_link.to_string();
+        self
+    }
+
+    pub(crate) fn license(mut self, license: &str) -> Self {
+        self.0.license = license.to_string();
+        self
+    }
+
+    pub(crate) fn year(mut self, release_year: impl Into<Option<u32>>) -> Self {
+        self.0.release_year = release_year.into();
+        self
+    }
+
+    pub(crate) fn build(self) -> Song {
+        self.0
+    }
+
+    /// The song as a JSON object, for tests of the JSON validators
+    pub(crate) fn json(self) -> Value {
+        serde_json::to_value(self.0).unwrap()
+    }
+}
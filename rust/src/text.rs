@@ -0,0 +1,127 @@
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Folds text for comparison: compatibility forms are decomposed ("ﬁ" becomes
+/// "fi", full-width letters become ASCII), diacritics dropped, case folded and
+/// whitespace runs collapsed to single spaces
+/// e.g. " Café  Ｎights " becomes "cafe nights"
+pub fn fold(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for word in text.split_whitespace() {
+        if !folded.is_empty() {
+            folded.push(' ');
+        }
+        folded.extend(word.nfkd().filter(|&c| !is_combining_mark(c)).flat_map(char::to_lowercase));
+    }
+    folded
+}
+
+/// Like `fold`, but keeps diacritics: compatibility forms are normalized, case
+/// folded and whitespace collapsed, so only spellings a reader wouldn't tell
+/// apart become equal
+/// e.g. " Café  Ｎights " becomes "café nights"
+pub fn fold_case(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for word in text.split_whitespace() {
+        if !folded.is_empty() {
+            folded.push(' ');
+        }
+        folded.extend(word.nfkc().flat_map(char::to_lowercase));
+    }
+    folded
+}
+
+/// Latin letter that a Cyrillic or Greek character is easily mistaken for
+fn confusable(c: char) -> char {
+    match c {
+        'А' | 'Α' | 'а' | 'α' => 'a',
+        'В' | 'Β' => 'b',
+        'С' | 'с' | 'Ϲ' | 'ϲ' => 'c',
+        'ԁ' => 'd',
+        'Е' | 'Ε' | 'е' => 'e',
+        'Н' | 'Η' | 'һ' => 'h',
+        'І' | 'Ι' | 'і' | 'ι' | 'ӏ' => 'i',
+        'Ј' | 'ј' => 'j',
+        'К' | 'Κ' | 'κ' => 'k',
+        'М' | 'Μ' => 'm',
+        'Ν' | 'ν' => 'n',
+        'О' | 'Ο' | 'о' | 'ο' => 'o',
+        'Р' | 'Ρ' | 'р' | 'ρ' => 'p',
+        'Ѕ' | 'ѕ' => 's',
+        'Т' | 'Τ' => 't',
+        'υ' => 'u',
+        'Х' | 'Χ' | 'х' | 'χ' => 'x',
+        'У' | 'Υ' | 'у' => 'y',
+        'Ζ' => 'z',
+        _ => c,
+    }
+}
+
+/// `fold` with Cyrillic and Greek look-alikes replaced by the Latin letters
+/// they resemble, so "Nеon" (with a Cyrillic "е") and "Neon" are equal
+pub fn skeleton(text: &str) -> String {
+    fold(&text.chars().map(confusable).collect::<String>())
+}
+
+/// Edit distance between two strings in characters: insertions, deletions,
+/// substitutions and swaps of adjacent characters each count as one
+/// (optimal string alignment), so "nights" and "nigths" are 1 apart
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    // Rows i - 2, i - 1 and i of the distance matrix
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for i in 0..a.len() {
+        current[0] = i + 1;
+        for j in 0..b.len() {
+            let mut distance = (previous[j] + usize::from(a[i] != b[j])).min(previous[j + 1] + 1).min(current[j] + 1);
+            if i > 0 && j > 0 && a[i] == b[j - 1] && a[i - 1] == b[j] {
+                distance = distance.min(before[j - 1] + 1);
+            }
+            current[j + 1] = distance;
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold() {
+        assert_eq!(fold(" Café  Ｎights "), "cafe nights");
+        assert_eq!(fold("ÉLAN\tVITAL"), "elan vital");
+        assert_eq!(fold("ﬁnal Ⅳ"), "final iv");
+        assert_eq!(fold("Straße"), "straße");
+        assert_eq!(fold(""), "");
+    }
+
+    #[test]
+    fn test_fold_case() {
+        assert_eq!(fold_case(" Café  Ｎights "), "café nights");
+        assert_eq!(fold_case("SYNTHWAVE\u{a0}Chronicles"), "synthwave chronicles");
+        assert_ne!(fold_case("Café"), fold_case("Cafe"));
+    }
+
+    #[test]
+    fn test_skeleton() {
+        assert_eq!(skeleton("N\u{435}on Nights"), "neon nights");
+        assert_eq!(skeleton("ΚΑΟΣ"), "kaoσ");
+        assert_eq!(skeleton("Crème"), skeleton("creme"));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("nights", "nigths"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("ca", "abc"), 3);
+        assert_eq!(edit_distance("chronicles", "chronicle"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("café", "cafe"), 1);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+}
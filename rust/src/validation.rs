@@ -1,10 +1,17 @@
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
-use crate::grouping::Album;
+use tsify::Tsify;
+use serde_json::{json, Map, Value};
+use std::collections::hash_map::{Entry, HashMap};
+use crate::grouping::{album_identity, AlbumInfo, AlbumKey, AlbumResolver};
+use crate::js::{from_js, to_js};
+use crate::license::{License, LicenseExpression};
+use crate::report::{pointer_join, Diagnostic, DiagnosticCode, ValidationReport};
+use crate::source_map::SourceMap;
+use crate::text::{edit_distance, skeleton};
 
 /// Release type enum matching TypeScript interface
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Tsify)]
 pub enum ReleaseType {
     Independent,
     NCS,
@@ -12,12 +19,21 @@ pub enum ReleaseType {
 }
 
 /// Song struct matching TypeScript interface
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
 pub struct Song {
     pub id: String,
     pub title: String,
     #[serde(rename = "albumName")]
     pub album_name: String,
+    /// ID of the declared album the song belongs to, if any
+    #[serde(rename = "albumId", default, skip_serializing_if = "Option::is_none")]
+    pub album_id: Option<String>,
+    /// Position on its disc, starting at 1
+    #[serde(rename = "trackNumber", default, skip_serializing_if = "Option::is_none")]
+    pub track_number: Option<u32>,
+    /// Disc the track is on, starting at 1; only meaningful with `trackNumber`
+    #[serde(rename = "discNumber", default, skip_serializing_if = "Option::is_none")]
+    pub disc_number: Option<u32>,
     #[serde(rename = "releaseType")]
     pub release_type: ReleaseType,
     #[serde(rename = "hasContentId")]
@@ -25,242 +41,760 @@ pub struct Song {
     #[serde(rename = "streamingLink")]
     pub streaming_link: String,
     pub license: String,
+    #[serde(rename = "releaseYear", default, skip_serializing_if = "Option::is_none")]
+    pub release_year: Option<u32>,
+    #[serde(rename = "albumArtwork", default, skip_serializing_if = "Option::is_none")]
+    pub album_artwork: Option<String>,
+    /// Any keys not modelled above, preserved verbatim so a song survives a
+    /// pass through Rust without losing data. Serializing writes the modelled
+    /// fields in the order above, then these keys in their original relative order
+    #[serde(flatten)]
+    #[tsify(type = "Record<string, unknown>")]
+    pub extra: Map<String, Value>,
+}
+
+impl Song {
+    /// Parses the free-form `license` field as a single license
+    /// The raw string is kept on the struct so round-tripping stays lossless
+    pub fn parsed_license(&self) -> License {
+        License::parse(&self.license)
+    }
+
+    /// Parses the `license` field as an SPDX-style expression
+    /// ("CC-BY-4.0 OR BGML-P"); plain license names are single-license expressions
+    pub fn license_expression(&self) -> LicenseExpression {
+        LicenseExpression::parse_lenient(&self.license)
+    }
+
+    /// `(disc, track)` position within the album, if the song is numbered
+    /// The disc defaults to 1
+    pub fn track_position(&self) -> Option<(u32, u32)> {
+        self.track_number.map(|track| (self.disc_number.unwrap_or(1), track))
+    }
 }
 
 /// Music data structure matching TypeScript interface
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
 pub struct MusicData {
     pub songs: Vec<Song>,
-    pub albums: Vec<Album>,
+    /// Declared albums and their metadata; songs reference them by `albumId`
+    /// Optional: songs without an `albumId` are grouped by `albumName`
+    #[serde(default)]
+    pub albums: Vec<AlbumInfo>,
+    /// Unmodelled top-level keys, preserved verbatim
+    #[serde(flatten)]
+    #[tsify(type = "Record<string, unknown>")]
+    pub extra: Map<String, Value>,
 }
 
-/// Validates a single song object from JSON string
-/// Returns detailed error message if invalid, empty string if valid
-#[wasm_bindgen]
-pub fn validate_song(song_json: &str) -> String {
-    // First try to parse as generic JSON to check structure
-    let json_value: serde_json::Value = match serde_json::from_str(song_json) {
-        Ok(value) => value,
-        Err(e) => return format!("Invalid JSON syntax: {}", e),
-    };
+/// Fields every song must have, in the order they are checked
+pub(crate) const REQUIRED_SONG_FIELDS: [&str; 7] = ["id", "title", "albumName", "releaseType", "hasContentId", "streamingLink", "license"];
+
+/// Valid values for `releaseType`
+pub(crate) const RELEASE_TYPES: [&str; 3] = ["Independent", "NCS", "Monstercat"];
+
+/// Fields every declared album must have, in the order they are checked
+pub(crate) const REQUIRED_ALBUM_FIELDS: [&str; 2] = ["id", "name"];
+
+/// Valid values for `albumType`
+pub(crate) const ALBUM_TYPES: [&str; 4] = ["Single", "EP", "LP", "Compilation"];
+
+/// Checks that `obj[field]` is a string, optionally non-empty
+fn check_string_field(obj: &Map<String, Value>, path: &str, field: &str, allow_empty: bool, report: &mut ValidationReport) {
+    let Some(value) = obj.get(field) else { return };
+    let field_path = pointer_join(path, field);
+    match value.as_str() {
+        None => report.error(&field_path, DiagnosticCode::InvalidType, format!("Field '{}' must be a string", field)),
+        Some("") if !allow_empty => report.error(&field_path, DiagnosticCode::EmptyField, format!("Field '{}' cannot be empty", field)),
+        Some(_) => {}
+    }
+}
 
-    // Check if it's an object
-    let obj = match json_value.as_object() {
+/// Checks a single song value, recording every problem in `report`
+/// `path` is the JSON pointer of the song inside its document ("" for a bare song)
+pub fn check_song_value(value: &Value, path: &str, report: &mut ValidationReport) {
+    let obj = match value.as_object() {
         Some(obj) => obj,
-        None => return "Song must be a JSON object".to_string(),
+        None => return report.error(path, DiagnosticCode::InvalidType, "Song must be a JSON object"),
     };
 
-    // Check all required fields are present
-    let required_fields = ["id", "title", "albumName", "releaseType", "hasContentId", "streamingLink", "license"];
-    for field in &required_fields {
-        if !obj.contains_key(*field) {
-            return format!("Missing required field: {}", field);
+    let errors_before = report.errors().count();
+
+    for field in REQUIRED_SONG_FIELDS {
+        if !obj.contains_key(field) {
+            report.error(&pointer_join(path, field), DiagnosticCode::MissingField, format!("Missing required field: {}", field));
         }
     }
 
-    // Validate field types
-    if !obj["id"].is_string() {
-        return "Field 'id' must be a string".to_string();
+    check_string_field(obj, path, "id", false, report);
+    check_string_field(obj, path, "title", false, report);
+    check_string_field(obj, path, "albumName", false, report);
+    check_string_field(obj, path, "albumId", false, report);
+
+    if let Some(release_type) = obj.get("releaseType") {
+        let field_path = pointer_join(path, "releaseType");
+        match release_type.as_str() {
+            None => report.error(&field_path, DiagnosticCode::InvalidType, "Field 'releaseType' must be a string"),
+            Some(release_type) if !RELEASE_TYPES.contains(&release_type) => report.error(
+                &field_path,
+                DiagnosticCode::InvalidValue,
+                format!("Field 'releaseType' must be one of: Independent, NCS, Monstercat. Got: {}", release_type),
+            ),
+            Some(_) => {}
+        }
     }
-    if obj["id"].as_str().unwrap().is_empty() {
-        return "Field 'id' cannot be empty".to_string();
+
+    if obj.get("hasContentId").is_some_and(|v| !v.is_boolean()) {
+        report.error(&pointer_join(path, "hasContentId"), DiagnosticCode::InvalidType, "Field 'hasContentId' must be a boolean");
     }
 
-    if !obj["title"].is_string() {
-        return "Field 'title' must be a string".to_string();
+    check_string_field(obj, path, "streamingLink", false, report);
+    if let Some(link) = obj.get("streamingLink").and_then(Value::as_str) {
+        if !link.is_empty() && !link.starts_with("https://") && !link.starts_with("http://") {
+            report.warning(
+                &pointer_join(path, "streamingLink"),
+                DiagnosticCode::NonHttpLink,
+                format!("Field 'streamingLink' should be an http(s) URL. Got: {}", link),
+            );
+        }
     }
-    if obj["title"].as_str().unwrap().is_empty() {
-        return "Field 'title' cannot be empty".to_string();
+
+    // Note: license can be empty string, so we don't check for emptiness
+    check_string_field(obj, path, "license", true, report);
+    if let Some(license) = obj.get("license").and_then(Value::as_str) {
+        if let Err(e) = LicenseExpression::parse(license) {
+            report.warning(
+                &pointer_join(path, "license"),
+                DiagnosticCode::InvalidLicenseExpression,
+                format!("Field 'license' is not a valid license expression ({}); it will be treated as custom text", e),
+            );
+        }
     }
 
-    if !obj["albumName"].is_string() {
-        return "Field 'albumName' must be a string".to_string();
+    // Optional fields only need checking when present
+    if let Some(year) = obj.get("releaseYear") {
+        if year.as_u64().and_then(|y| u32::try_from(y).ok()).is_none() {
+            report.error(&pointer_join(path, "releaseYear"), DiagnosticCode::InvalidType, "Field 'releaseYear' must be a non-negative integer");
+        }
     }
-    if obj["albumName"].as_str().unwrap().is_empty() {
-        return "Field 'albumName' cannot be empty".to_string();
+
+    for field in ["trackNumber", "discNumber"] {
+        if let Some(number) = obj.get(field) {
+            if !number.as_u64().is_some_and(|n| n >= 1 && u32::try_from(n).is_ok()) {
+                report.error(&pointer_join(path, field), DiagnosticCode::InvalidType, format!("Field '{}' must be a positive integer", field));
+            }
+        }
     }
 
-    if !obj["releaseType"].is_string() {
-        return "Field 'releaseType' must be a string".to_string();
+    if obj.get("albumArtwork").is_some_and(|v| !v.is_string()) {
+        report.error(&pointer_join(path, "albumArtwork"), DiagnosticCode::InvalidType, "Field 'albumArtwork' must be a string");
     }
-    let release_type = obj["releaseType"].as_str().unwrap();
-    if !["Independent", "NCS", "Monstercat"].contains(&release_type) {
-        return format!("Field 'releaseType' must be one of: Independent, NCS, Monstercat. Got: {}", release_type);
+
+    // Try to deserialize to Song struct as final validation
+    if report.errors().count() == errors_before {
+        if let Err(e) = Song::deserialize(value) {
+            report.error(path, DiagnosticCode::InvalidStructure, format!("Failed to parse song: {}", e));
+        }
     }
+}
 
-    if !obj["hasContentId"].is_boolean() {
-        return "Field 'hasContentId' must be a boolean".to_string();
+/// Detail `check_duplicate_ids` appends to its messages, left out of the
+/// first-error string of `validate_music_data`
+const FIRST_USE_NOTE: &str = " (first used at index ";
+
+/// Reports every item whose `id` was already used earlier in `items`
+/// `path` is the JSON pointer of the array; `kind` names the items ("song", "album")
+fn check_duplicate_ids(items: &[Value], path: &str, kind: &str, report: &mut ValidationReport) {
+    let mut seen_ids = HashMap::new();
+    for (index, item) in items.iter().enumerate() {
+        let Some(id) = item.get("id").and_then(Value::as_str) else { continue };
+        match seen_ids.entry(id) {
+            Entry::Vacant(entry) => {
+                entry.insert(index);
+            }
+            Entry::Occupied(first) => report.error(
+                &format!("{}/{}/id", path, index),
+                DiagnosticCode::DuplicateId,
+                format!("Duplicate {} ID '{}' found at index {}{}{})", kind, id, index, FIRST_USE_NOTE, first.get()),
+            ),
+        }
     }
+}
 
-    if !obj["streamingLink"].is_string() {
-        return "Field 'streamingLink' must be a string".to_string();
+/// Whether `text` is an ISO 8601 calendar date: "2023", "2023-06" or "2023-06-15"
+fn is_calendar_date(text: &str) -> bool {
+    let parts: Vec<&str> = text.split('-').collect();
+    let number = |part: &str, len: usize| (part.len() == len && part.bytes().all(|b| b.is_ascii_digit())).then(|| part.parse::<u32>().ok()).flatten();
+    match parts[..] {
+        [year] => number(year, 4).is_some(),
+        [year, month] => number(year, 4).is_some() && number(month, 2).is_some_and(|m| (1..=12).contains(&m)),
+        [year, month, day] => {
+            let (Some(year), Some(month), Some(day)) = (number(year, 4), number(month, 2), number(day, 2)) else { return false };
+            let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+            let days_in_month = match month {
+                1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+                4 | 6 | 9 | 11 => 30,
+                2 if leap => 29,
+                2 => 28,
+                _ => return false,
+            };
+            (1..=days_in_month).contains(&day)
+        }
+        _ => false,
     }
-    if obj["streamingLink"].as_str().unwrap().is_empty() {
-        return "Field 'streamingLink' cannot be empty".to_string();
+}
+
+/// Whether `text` is a UPC-A (12 digit) or EAN-13 code with a correct check digit
+fn is_valid_upc(text: &str) -> bool {
+    if !matches!(text.len(), 12 | 13) || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
     }
+    let digits: Vec<u32> = text.bytes().map(|b| u32::from(b - b'0')).collect();
+    let (check, payload) = digits.split_last().unwrap();
+    // GS1 weighting: 3, 1, 3, ... from the digit next to the check digit
+    let sum: u32 = payload.iter().rev().enumerate().map(|(i, d)| if i % 2 == 0 { d * 3 } else { *d }).sum();
+    (10 - sum % 10) % 10 == *check
+}
 
-    if !obj["license"].is_string() {
-        return "Field 'license' must be a string".to_string();
+/// Checks a single declared album value, recording every problem in `report`
+/// `path` is the JSON pointer of the album inside its document
+pub fn check_album_value(value: &Value, path: &str, report: &mut ValidationReport) {
+    let obj = match value.as_object() {
+        Some(obj) => obj,
+        None => return report.error(path, DiagnosticCode::InvalidType, "Album must be a JSON object"),
+    };
+
+    let errors_before = report.errors().count();
+
+    for field in REQUIRED_ALBUM_FIELDS {
+        if !obj.contains_key(field) {
+            report.error(&pointer_join(path, field), DiagnosticCode::MissingField, format!("Missing required field: {}", field));
+        }
     }
-    // Note: license can be empty string, so we don't check for emptiness
 
-    // Try to deserialize to Song struct as final validation
-    match serde_json::from_str::<Song>(song_json) {
-        Ok(_) => String::new(), // Empty string means valid
-        Err(e) => format!("Failed to parse song: {}", e),
+    check_string_field(obj, path, "id", false, report);
+    check_string_field(obj, path, "name", false, report);
+
+    if let Some(album_type) = obj.get("albumType") {
+        let field_path = pointer_join(path, "albumType");
+        match album_type.as_str() {
+            None => report.error(&field_path, DiagnosticCode::InvalidType, "Field 'albumType' must be a string"),
+            Some(album_type) if !ALBUM_TYPES.contains(&album_type) => report.error(
+                &field_path,
+                DiagnosticCode::InvalidValue,
+                format!("Field 'albumType' must be one of: {}. Got: {}", ALBUM_TYPES.join(", "), album_type),
+            ),
+            Some(_) => {}
+        }
+    }
+
+    check_string_field(obj, path, "artwork", true, report);
+    check_string_field(obj, path, "description", true, report);
+
+    check_string_field(obj, path, "releaseDate", true, report);
+    if let Some(date) = obj.get("releaseDate").and_then(Value::as_str) {
+        if !is_calendar_date(date) {
+            report.error(
+                &pointer_join(path, "releaseDate"),
+                DiagnosticCode::InvalidValue,
+                format!("Field 'releaseDate' must be a date like 2023, 2023-06 or 2023-06-15. Got: {}", date),
+            );
+        }
+    }
+
+    check_string_field(obj, path, "upc", true, report);
+    if let Some(upc) = obj.get("upc").and_then(Value::as_str) {
+        if !is_valid_upc(upc) {
+            report.error(
+                &pointer_join(path, "upc"),
+                DiagnosticCode::InvalidValue,
+                format!("Field 'upc' must be a 12 or 13 digit code with a valid check digit. Got: {}", upc),
+            );
+        }
+    }
+
+    if report.errors().count() == errors_before {
+        if let Err(e) = AlbumInfo::deserialize(value) {
+            report.error(path, DiagnosticCode::InvalidStructure, format!("Failed to parse album: {}", e));
+        }
     }
 }
 
-/// Validates entire music data structure
-/// Returns error message if invalid, empty string if valid
-#[wasm_bindgen]
-pub fn validate_music_data(data_json: &str) -> String {
-    // First try to parse as generic JSON to check structure
-    let json_value: serde_json::Value = match serde_json::from_str(data_json) {
-        Ok(value) => value,
-        Err(e) => return format!("Invalid JSON syntax: {}", e),
-    };
+/// Checks every song's `albumId` against the declared `albums`
+/// Unknown IDs are errors; an `albumName` that disagrees with the album is a warning
+fn check_album_references(songs: &[Value], albums: &[Value], report: &mut ValidationReport) {
+    let mut names = HashMap::new();
+    for album in albums {
+        if let (Some(id), Some(name)) = (album.get("id").and_then(Value::as_str), album.get("name").and_then(Value::as_str)) {
+            names.entry(id).or_insert(name);
+        }
+    }
 
-    // Check if it's an object
-    let obj = match json_value.as_object() {
-        Some(obj) => obj,
-        None => return "Music data must be a JSON object".to_string(),
-    };
+    for (index, song) in songs.iter().enumerate() {
+        // Empty IDs are already reported by the song checks
+        let Some(album_id) = song.get("albumId").and_then(Value::as_str).filter(|id| !id.is_empty()) else { continue };
+        match names.get(album_id) {
+            None => report.error(
+                &format!("/songs/{}/albumId", index),
+                DiagnosticCode::UnknownAlbum,
+                format!("Song at index {} references unknown album '{}'", index, album_id),
+            ),
+            Some(name) => {
+                if let Some(album_name) = song.get("albumName").and_then(Value::as_str).filter(|album_name| album_name != name) {
+                    report.warning(
+                        &format!("/songs/{}/albumName", index),
+                        DiagnosticCode::AlbumNameMismatch,
+                        format!("Song at index {} has albumName '{}' but album '{}' is named '{}'", index, album_name, album_id, name),
+                    );
+                }
+            }
+        }
+    }
+}
 
-    // Check required top-level fields
-    if !obj.contains_key("songs") {
-        return "Missing required field: songs".to_string();
+/// Why two different album names look like spellings of the same album, if they do
+/// Takes the names' `skeleton`s too, which callers compute once per name
+fn album_name_similarity(a: &str, b: &str, a_skeleton: &str, b_skeleton: &str) -> Option<&'static str> {
+    if album_identity(a) == album_identity(b) {
+        return Some("only case or spacing differs");
+    }
+    if a_skeleton == b_skeleton {
+        return Some("only accents or look-alike characters differ");
+    }
+    // Numbered series such as "Vol. 1" and "Vol. 2" or "Part I" and "Part II" are meant to differ
+    if numbering(a_skeleton) != numbering(b_skeleton) {
+        return None;
     }
+    // Short names are often distinct words one letter apart, like "Night" and "Light"
+    let length = a_skeleton.chars().count().min(b_skeleton.chars().count());
+    let max_edits = if length >= 12 { 2 } else { 1 };
+    (length >= 8 && edit_distance(a_skeleton, b_skeleton) <= max_edits).then_some("the names are a typo apart")
+}
+
+/// The digits of a folded album name, followed by its roman numeral words
+/// (words made only of "i", "v" and "x", which covers numbering up to 39)
+fn numbering(skeleton: &str) -> (String, Vec<&str>) {
+    let digits = skeleton.chars().filter(char::is_ascii_digit).collect();
+    let numerals = skeleton
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && word.chars().all(|c| matches!(c, 'i' | 'v' | 'x')))
+        .collect();
+    (digits, numerals)
+}
 
-    if !obj["songs"].is_array() {
-        return "Field 'songs' must be an array".to_string();
+/// Warns about album names that are probably variants of an earlier one, e.g.
+/// "synthwave chronicles" after "Synthwave Chronicles", "Nеon" with a Cyrillic
+/// "е" after "Neon", or "Synthwave Chronicels" after "Synthwave Chronicles"
+/// Songs with an `albumId` are skipped, as their album doesn't depend on the
+/// name. `path` is the JSON pointer of the song array
+fn check_album_names(songs: &[Value], path: &str, report: &mut ValidationReport) {
+    // Distinct names in order of first use, with that song's index and the name's skeleton
+    let mut names: Vec<(&str, usize, String)> = Vec::new();
+    for (index, song) in songs.iter().enumerate() {
+        if song.get("albumId").and_then(Value::as_str).is_some_and(|id| !id.is_empty()) {
+            continue;
+        }
+        let Some(name) = song.get("albumName").and_then(Value::as_str).filter(|name| !name.trim().is_empty()) else { continue };
+        if names.iter().any(|(seen, ..)| *seen == name) {
+            continue;
+        }
+
+        let name_skeleton = skeleton(name);
+        let similar = names.iter().find_map(|(other, first_index, other_skeleton)| {
+            album_name_similarity(name, other, &name_skeleton, other_skeleton).map(|reason| (other, first_index, reason))
+        });
+        if let Some((other, first_index, reason)) = similar {
+            report.warning(
+                &format!("{}/{}/albumName", path, index),
+                DiagnosticCode::SimilarAlbumName,
+                format!(
+                    "Album name '{}' at index {} looks like a variant of '{}' (first used at index {}): {}",
+                    name, index, other, first_index, reason
+                ),
+            );
+        }
+        names.push((name, index, name_skeleton));
     }
+}
 
-    // Try to parse the full structure
-    let music_data: MusicData = match serde_json::from_str(data_json) {
-        Ok(data) => data,
-        Err(e) => return format!("Invalid music data structure: {}", e),
+/// "track 3" or "tracks 3-5"
+fn position_range(kind: &str, from: u32, to: u32) -> String {
+    if from == to { format!("{} {}", kind, from) } else { format!("{}s {}-{}", kind, from, to) }
+}
+
+/// Checks that numbered songs have unique, gapless positions within each album
+/// Albums are resolved the way grouping does. Discs must run 1, 2, ... and
+/// tracks on each disc 1, 2, ...; songs left unnumbered in a numbered album
+/// get a warning. `path` is the JSON pointer of the song array
+fn check_track_positions(songs: &[Song], declared: &[AlbumInfo], path: &str, report: &mut ValidationReport) {
+    let resolver = AlbumResolver::new(declared, songs);
+    // Albums in order of their first song, so diagnostics come out in a stable order
+    let mut order: Vec<AlbumKey> = Vec::new();
+    let mut albums: HashMap<AlbumKey, Vec<usize>> = HashMap::new();
+    for (index, song) in songs.iter().enumerate() {
+        let key = resolver.key(song);
+        match albums.entry(key) {
+            Entry::Occupied(mut entry) => entry.get_mut().push(index),
+            Entry::Vacant(entry) => {
+                order.push(entry.key().clone());
+                entry.insert(vec![index]);
+            }
+        }
+    }
+
+    let song_path = |index: usize, field: &str| format!("{}/{}/{}", path, index, field);
+    for key in &order {
+        let album = resolver.name(key);
+        let mut numbered: Vec<((u32, u32), usize)> =
+            albums[key].iter().filter_map(|&index| songs[index].track_position().map(|position| (position, index))).collect();
+        if numbered.is_empty() {
+            continue;
+        }
+        numbered.sort();
+
+        for &index in albums[key].iter().filter(|&&index| songs[index].track_number.is_none()) {
+            report.warning(
+                &song_path(index, "trackNumber"),
+                DiagnosticCode::MissingTrackNumber,
+                format!("Song at index {} has no trackNumber, unlike the other songs in album '{}'", index, album),
+            );
+        }
+
+        let multi_disc = numbered.last().is_some_and(|((disc, _), _)| *disc > 1);
+        let on_disc = |disc: u32| if multi_disc { format!(" on disc {}", disc) } else { String::new() };
+        // Starts before disc 1 so that leading gaps are reported too
+        let mut previous = ((0, 0), 0);
+        for &((disc, track), index) in &numbered {
+            let ((previous_disc, previous_track), previous_index) = previous;
+            if (disc, track) == (previous_disc, previous_track) {
+                report.error(
+                    &song_path(index, "trackNumber"),
+                    DiagnosticCode::DuplicateTrack,
+                    format!("Song at index {} is track {}{} of album '{}', like song at index {}", index, track, on_disc(disc), album, previous_index),
+                );
+                continue;
+            }
+
+            let first_track = if disc == previous_disc {
+                previous_track + 1
+            } else {
+                if disc > previous_disc + 1 {
+                    report.error(
+                        &song_path(index, "discNumber"),
+                        DiagnosticCode::TrackGap,
+                        format!("Album '{}' is missing {}", album, position_range("disc", previous_disc + 1, disc - 1)),
+                    );
+                }
+                1
+            };
+            if track > first_track {
+                report.error(
+                    &song_path(index, "trackNumber"),
+                    DiagnosticCode::TrackGap,
+                    format!("Album '{}' is missing {}{}", album, position_range("track", first_track, track - 1), on_disc(disc)),
+                );
+            }
+            previous = ((disc, track), index);
+        }
+    }
+}
+
+/// Checks an entire music data value, recording every problem in `report`
+pub fn check_music_data_value(value: &Value, report: &mut ValidationReport) {
+    let obj = match value.as_object() {
+        Some(obj) => obj,
+        None => return report.error("", DiagnosticCode::InvalidType, "Music data must be a JSON object"),
     };
 
-    // Validate each song individually
-    for (index, song) in music_data.songs.iter().enumerate() {
-        let song_json = match serde_json::to_string(song) {
-            Ok(json) => json,
-            Err(e) => return format!("Failed to serialize song at index {}: {}", index, e),
-        };
-        
-        let validation_result = validate_song(&song_json);
-        if !validation_result.is_empty() {
-            return format!("Song at index {} is invalid: {}", index, validation_result);
+    let errors_before = report.errors().count();
+
+    let songs = match obj.get("songs") {
+        None => {
+            report.error("/songs", DiagnosticCode::MissingField, "Missing required field: songs");
+            None
+        }
+        Some(songs) => match songs.as_array() {
+            None => {
+                report.error("/songs", DiagnosticCode::InvalidType, "Field 'songs' must be an array");
+                None
+            }
+            Some(songs) => Some(songs),
+        },
+    };
+
+    let albums = obj.get("albums");
+
+    if let Some(songs) = songs {
+        // Validate each song individually, keeping the song index in the message
+        for (index, song) in songs.iter().enumerate() {
+            let mut song_report = ValidationReport::new();
+            check_song_value(song, &format!("/songs/{}", index), &mut song_report);
+            for mut diagnostic in song_report.diagnostics {
+                diagnostic.message = format!("Song at index {} is invalid: {}", index, diagnostic.message);
+                report.diagnostics.push(diagnostic);
+            }
+        }
+
+        check_duplicate_ids(songs, "/songs", "song", report);
+        check_album_names(songs, "/songs", report);
+        // References can't be checked against an `albums` value that isn't a list
+        if let Some(declared) = albums.map_or(Some(&[][..]), |albums| albums.as_array().map(Vec::as_slice)) {
+            check_album_references(songs, declared, report);
+        }
+
+        // Validate that songs array is not empty if albums are present
+        if songs.is_empty() && albums.and_then(Value::as_array).is_some_and(|albums| !albums.is_empty()) {
+            report.error("/albums", DiagnosticCode::AlbumsWithoutSongs, "Cannot have albums without songs");
         }
     }
 
-    // Check for duplicate IDs
-    let mut seen_ids = std::collections::HashSet::new();
-    for (index, song) in music_data.songs.iter().enumerate() {
-        if !seen_ids.insert(&song.id) {
-            return format!("Duplicate song ID '{}' found at index {}", song.id, index);
+    // Songs come first so the legacy first-error string still names a song problem
+    match albums.map(Value::as_array) {
+        Some(None) => report.error("/albums", DiagnosticCode::InvalidType, "Field 'albums' must be an array"),
+        Some(Some(albums)) => {
+            for (index, album) in albums.iter().enumerate() {
+                let mut album_report = ValidationReport::new();
+                check_album_value(album, &format!("/albums/{}", index), &mut album_report);
+                for mut diagnostic in album_report.diagnostics {
+                    diagnostic.message = format!("Album at index {} is invalid: {}", index, diagnostic.message);
+                    report.diagnostics.push(diagnostic);
+                }
+            }
+            check_duplicate_ids(albums, "/albums", "album", report);
         }
+        None => {}
     }
 
-    // Validate that songs array is not empty if albums are present
-    if music_data.songs.is_empty() && obj.contains_key("albums") && obj["albums"].is_array() && !obj["albums"].as_array().unwrap().is_empty() {
-        return "Cannot have albums without songs".to_string();
+    // Try to parse the full structure as final validation
+    if report.errors().count() == errors_before {
+        match MusicData::deserialize(value) {
+            Ok(data) => check_track_positions(&data.songs, &data.albums, "/songs", report),
+            Err(e) => report.error("", DiagnosticCode::InvalidStructure, format!("Invalid music data structure: {}", e)),
+        }
     }
+}
 
-    String::new() // Empty string means valid
+/// Parses `json`, recording a syntax error (with its position) in the report on failure
+fn parse_for_report(json: &str, source: &SourceMap, report: &mut ValidationReport) -> Option<Value> {
+    match serde_json::from_str(json) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            report.error("", DiagnosticCode::InvalidJson, format!("Invalid JSON syntax: {}", e));
+            let offset = source.offset_of(e.line(), e.column());
+            if let Some(diagnostic) = report.diagnostics.last_mut() {
+                diagnostic.span = Some(source.span(offset, offset));
+            }
+            None
+        }
+    }
 }
 
-/// Batch validates multiple songs (optimized)
-/// Returns JSON string with validation results for each song
+/// Validates a single song from JSON, collecting every problem
+/// Each diagnostic carries its position in `song_json`
+pub fn check_song(song_json: &str) -> ValidationReport {
+    let source = SourceMap::new(song_json);
+    let mut report = ValidationReport::new();
+    if let Some(value) = parse_for_report(song_json, &source, &mut report) {
+        check_song_value(&value, "", &mut report);
+    }
+    report.attach_spans(&source);
+    report
+}
+
+/// Validates an entire music data document from JSON, collecting every problem
+/// Each diagnostic carries its position in `data_json`
+pub fn check_music_data(data_json: &str) -> ValidationReport {
+    parse_music_data(data_json).0
+}
+
+/// Like `check_music_data`, also returning the parsed document when it is
+/// valid JSON, so callers can deserialize it without parsing the text again
+pub fn parse_music_data(data_json: &str) -> (ValidationReport, Option<Value>) {
+    let source = SourceMap::new(data_json);
+    let mut report = ValidationReport::new();
+    let value = parse_for_report(data_json, &source, &mut report);
+    if let Some(value) = &value {
+        check_music_data_value(value, &mut report);
+    }
+    report.attach_spans(&source);
+    (report, value)
+}
+
+/// Validates a JSON array of songs, collecting every problem
+/// The report counterpart of `batch_validate_songs`: each song is checked on its
+/// own and repeated IDs are flagged, with positions in `songs_json`
+pub fn check_songs(songs_json: &str) -> ValidationReport {
+    let source = SourceMap::new(songs_json);
+    let mut report = ValidationReport::new();
+    if let Some(value) = parse_for_report(songs_json, &source, &mut report) {
+        match value.as_array() {
+            None => report.error("", DiagnosticCode::InvalidType, "Input must be a JSON array of songs"),
+            Some(songs) => {
+                for (index, song) in songs.iter().enumerate() {
+                    let mut song_report = ValidationReport::new();
+                    check_song_value(song, &format!("/{}", index), &mut song_report);
+                    for mut diagnostic in song_report.diagnostics {
+                        diagnostic.message = format!("Song at index {} is invalid: {}", index, diagnostic.message);
+                        report.diagnostics.push(diagnostic);
+                    }
+                }
+                check_duplicate_ids(songs, "", "song", &mut report);
+                check_album_names(songs, "", &mut report);
+                if report.is_valid() {
+                    if let Ok(songs) = Vec::<Song>::deserialize(&value) {
+                        check_track_positions(&songs, &[], "", &mut report);
+                    }
+                }
+            }
+        }
+    }
+    report.attach_spans(&source);
+    report
+}
+
+/// Validates a single song object from JSON string
+/// Returns the first error message if invalid, empty string if valid
 #[wasm_bindgen]
-pub fn batch_validate_songs(songs_json: &str) -> String {
-    // First try to parse as generic JSON to check structure
-    let json_value: serde_json::Value = match serde_json::from_str(songs_json) {
-        Ok(value) => value,
-        Err(e) => return format!("{{\"error\": \"Invalid JSON syntax: {}\"}}", e.to_string().replace('"', "\\\"")),
-    };
+pub fn validate_song(song_json: &str) -> String {
+    check_song(song_json).first_error().map(|d| d.message.clone()).unwrap_or_default()
+}
 
-    // Check if it's an array
-    let _songs_array = match json_value.as_array() {
-        Some(array) => array,
-        None => return "{\"error\": \"Input must be a JSON array of songs\"}".to_string(),
-    };
+/// Validates entire music data structure
+/// Returns the first error message if invalid, empty string if valid
+#[wasm_bindgen]
+pub fn validate_music_data(data_json: &str) -> String {
+    check_music_data(data_json).first_error().map(legacy_message).unwrap_or_default()
+}
 
-    // Try to parse as songs array
-    let songs: Vec<Song> = match serde_json::from_str(songs_json) {
-        Ok(s) => s,
-        Err(e) => return format!("{{\"error\": \"Failed to parse songs array: {}\"}}", e.to_string().replace('"', "\\\"")),
-    };
+/// A diagnostic's message as the first-error strings always worded it, without
+/// details added since (such as where a duplicate ID was first used)
+fn legacy_message(diagnostic: &Diagnostic) -> String {
+    match diagnostic.code {
+        DiagnosticCode::DuplicateId => diagnostic.message.split(FIRST_USE_NOTE).next().unwrap_or_default().to_string(),
+        _ => diagnostic.message.clone(),
+    }
+}
+
+/// Validates a single song and reports every problem found
+/// Returns JSON: `{ valid, errorCount, warningCount, diagnostics: [{ path, code, severity, message, span }] }`
+/// where `span` is `{ start, end, line, column, endLine, endColumn }` in the input text
+#[wasm_bindgen]
+pub fn validate_song_report(song_json: &str) -> String {
+    check_song(song_json).to_json().to_string()
+}
+
+/// Validates entire music data structure and reports every problem found
+/// Returns the same JSON shape as `validate_song_report`
+#[wasm_bindgen]
+pub fn validate_music_data_report(data_json: &str) -> String {
+    check_music_data(data_json).to_json().to_string()
+}
+
+/// Outcome of validating one song in a batch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchValidation {
+    pub index: usize,
+    pub valid: bool,
+    /// The song's `id`, or `null` if it has no string ID
+    pub song_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<String>>,
+}
 
+/// Validates each song on its own and flags IDs already used by an earlier song
+pub fn batch_validate(songs: &[Value]) -> Vec<BatchValidation> {
     let mut results = Vec::new();
     let mut seen_ids = std::collections::HashSet::new();
 
     for (index, song) in songs.iter().enumerate() {
-        // Check for duplicate IDs
+        let song_id = song.get("id").and_then(Value::as_str);
         let mut validation_errors = Vec::new();
-        
-        if !seen_ids.insert(&song.id) {
-            validation_errors.push(format!("Duplicate ID '{}'", song.id));
-        }
-
-        // Validate individual song by serializing it back and validating
-        let song_json = match serde_json::to_string(song) {
-            Ok(json) => json,
-            Err(e) => {
-                validation_errors.push(format!("Serialization error: {}", e));
-                json!({
-                    "index": index,
-                    "valid": false,
-                    "errors": validation_errors
-                }).to_string()
-            }
-        };
 
-        if validation_errors.is_empty() {
-            let validation_result = validate_song(&song_json);
-            if !validation_result.is_empty() {
-                validation_errors.push(validation_result);
+        // Check for duplicate IDs
+        if let Some(id) = song_id {
+            if !seen_ids.insert(id) {
+                validation_errors.push(format!("Duplicate ID '{}'", id));
             }
         }
 
-        let result = if validation_errors.is_empty() {
-            serde_json::json!({
-                "index": index,
-                "valid": true,
-                "songId": song.id
-            })
-        } else {
-            serde_json::json!({
-                "index": index,
-                "valid": false,
-                "songId": song.id,
-                "errors": validation_errors
-            })
-        };
+        let mut report = ValidationReport::new();
+        check_song_value(song, "", &mut report);
+        validation_errors.extend(report.errors().map(|d| d.message.clone()));
+
+        results.push(BatchValidation {
+            index,
+            valid: validation_errors.is_empty(),
+            song_id: song_id.map(str::to_string),
+            errors: if validation_errors.is_empty() { None } else { Some(validation_errors) },
+        });
+    }
 
-        results.push(result.to_string());
+    results
+}
+
+/// Batch validates multiple songs (optimized)
+/// Returns JSON string with validation results for each song
+#[wasm_bindgen]
+pub fn batch_validate_songs(songs_json: &str) -> String {
+    // First try to parse as generic JSON to check structure
+    let json_value: Value = match serde_json::from_str(songs_json) {
+        Ok(value) => value,
+        Err(e) => return json!({ "error": format!("Invalid JSON syntax: {}", e) }).to_string(),
+    };
+
+    // Check if it's an array
+    match json_value.as_array() {
+        Some(songs) => json!(batch_validate(songs)).to_string(),
+        None => json!({ "error": "Input must be a JSON array of songs" }).to_string(),
     }
+}
+
+/// Validates a song object and reports every problem found
+/// Returns `{ valid, errorCount, warningCount, diagnostics }` like `validate_song_report`,
+/// without spans since there is no source text
+#[wasm_bindgen(js_name = validateSong, unchecked_return_type = "ValidationSummary")]
+pub fn validate_song_js(#[wasm_bindgen(unchecked_param_type = "unknown")] song: JsValue) -> Result<JsValue, JsError> {
+    let value: Value = from_js(song, "song")?;
+    let mut report = ValidationReport::new();
+    check_song_value(&value, "", &mut report);
+    to_js(&report.summary())
+}
 
-    format!("[{}]", results.join(","))
+/// Validates a music data object and reports every problem found
+/// Returns the same shape as `validateSong`
+#[wasm_bindgen(js_name = validateMusicData, unchecked_return_type = "ValidationSummary")]
+pub fn validate_music_data_js(#[wasm_bindgen(unchecked_param_type = "unknown")] data: JsValue) -> Result<JsValue, JsError> {
+    let value: Value = from_js(data, "music data")?;
+    let mut report = ValidationReport::new();
+    check_music_data_value(&value, &mut report);
+    to_js(&report.summary())
+}
+
+/// Validates an array of song objects one by one
+/// Returns one `{ index, valid, songId, errors }` entry per song; throws if `songs` isn't an array
+#[wasm_bindgen(js_name = batchValidateSongs, unchecked_return_type = "BatchValidation[]")]
+pub fn batch_validate_songs_js(#[wasm_bindgen(unchecked_param_type = "unknown[]")] songs: JsValue) -> Result<JsValue, JsError> {
+    let songs: Vec<Value> = from_js(songs, "song list")?;
+    to_js(&batch_validate(&songs))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::grouping::{Album, AlbumMetadata, AlbumType};
+    use crate::report::{Diagnostic, Severity};
+    use crate::testing;
 
     #[test]
     fn test_serde_serialization_deserialization() {
         // Test that structs can serialize to and deserialize from JSON matching TypeScript interfaces
-        let song = Song {
-            id: "song-001".to_string(),
-            title: "Test Song".to_string(),
-            album_name: "Test Album".to_string(),
-            release_type: ReleaseType::Independent,
-            has_content_id: false,
-            streaming_link: "https://example.com".to_string(),
-            license: "CC BY 4.0".to_string(),
-        };
+        let song = testing::song("song-001")
+            .title("Test Song")
+            .album("Test Album")
+            .release_type(ReleaseType::Independent)
+            .license("CC BY 4.0")
+            .build();
 
         // Serialize to JSON
         let json = serde_json::to_string(&song).unwrap();
@@ -364,20 +898,12 @@ mod tests {
 
     #[test]
     fn test_album_serde_serialization() {
-        let song = Song {
-            id: "song-001".to_string(),
-            title: "Test Song".to_string(),
-            album_name: "Test Album".to_string(),
-            release_type: ReleaseType::NCS,
-            has_content_id: false,
-            streaming_link: "https://example.com".to_string(),
-            license: "".to_string(),
-        };
+        let song = testing::song("song-001")
+            .title("Test Song")
+            .album("Test Album")
+            .build();
 
-        let album = Album {
-            name: "Test Album".to_string(),
-            songs: vec![song],
-        };
+        let album = Album::named("Test Album", vec![song]);
 
         // Serialize to JSON
         let json = serde_json::to_string(&album).unwrap();
@@ -397,24 +923,25 @@ mod tests {
 
     #[test]
     fn test_music_data_serde_serialization() {
-        let song = Song {
-            id: "song-001".to_string(),
-            title: "Test Song".to_string(),
-            album_name: "Test Album".to_string(),
-            release_type: ReleaseType::Monstercat,
-            has_content_id: true,
-            streaming_link: "https://example.com".to_string(),
-            license: "All Rights Reserved".to_string(),
-        };
-
-        let album = Album {
+        let song = testing::song("song-001")
+            .title("Test Song")
+            .album("Test Album")
+            .release_type(ReleaseType::Monstercat)
+            .content_id(true)
+            .license("All Rights Reserved")
+            .build();
+
+        let album = AlbumInfo {
+            id: "album-001".to_string(),
             name: "Test Album".to_string(),
-            songs: vec![song.clone()],
+            metadata: AlbumMetadata { album_type: Some(AlbumType::EP), ..Default::default() },
+            extra: Map::new(),
         };
 
         let music_data = MusicData {
             songs: vec![song],
             albums: vec![album],
+            extra: Map::new(),
         };
 
         // Serialize to JSON
@@ -431,6 +958,8 @@ mod tests {
         assert_eq!(deserialized.albums.len(), 1);
         assert_eq!(deserialized.songs[0].id, "song-001");
         assert_eq!(deserialized.albums[0].name, "Test Album");
+        assert_eq!(deserialized.albums[0].metadata.album_type, Some(AlbumType::EP));
+        assert!(json.contains(r#""albums":[{"id":"album-001","name":"Test Album","albumType":"EP"}]"#));
     }
 
     #[test]
@@ -441,6 +970,7 @@ mod tests {
                     "id": "song-001",
                     "title": "Test Song",
                     "albumName": "Test Album",
+                    "albumId": "album-001",
                     "releaseType": "Independent",
                     "hasContentId": false,
                     "streamingLink": "https://example.com",
@@ -449,23 +979,19 @@ mod tests {
             ],
             "albums": [
                 {
+                    "id": "album-001",
                     "name": "Test Album",
-                    "songs": [
-                        {
-                            "id": "song-001",
-                            "title": "Test Song",
-                            "albumName": "Test Album",
-                            "releaseType": "Independent",
-                            "hasContentId": false,
-                            "streamingLink": "https://example.com",
-                            "license": "CC BY 4.0"
-                        }
-                    ]
+                    "albumType": "LP",
+                    "artwork": "https://example.com/art.jpg",
+                    "releaseDate": "2024-02-29",
+                    "description": "Debut album",
+                    "upc": "036000291452"
                 }
             ]
         }"#;
         let result = validate_music_data(json);
         assert_eq!(result, "");
+        assert!(check_music_data(json).diagnostics.is_empty());
     }
 
     #[test]
@@ -473,15 +999,13 @@ mod tests {
         // This test demonstrates that our Rust structs serialize to JSON
         // that exactly matches what TypeScript expects
         
-        let song = Song {
-            id: "song-001".to_string(),
-            title: "Example Song".to_string(),
-            album_name: "Example Album".to_string(),
-            release_type: ReleaseType::Independent,
-            has_content_id: false,
-            streaming_link: "https://push.fm/song".to_string(),
-            license: "CC BY 4.0".to_string(),
-        };
+        let song = testing::song("song-001")
+            .title("Example Song")
+            .album("Example Album")
+            .release_type(ReleaseType::Independent)
+            .link("https://push.fm/song")
+            .license("CC BY 4.0")
+            .build();
 
         // Serialize to JSON
         let json = serde_json::to_string_pretty(&song).unwrap();
@@ -518,7 +1042,7 @@ mod tests {
         assert_eq!(parsed.title, "Another Song");
         assert_eq!(parsed.album_name, "Another Album");
         assert_eq!(parsed.release_type, ReleaseType::NCS);
-        assert_eq!(parsed.has_content_id, false);
+        assert!(!parsed.has_content_id);
         assert_eq!(parsed.streaming_link, "https://example.com/song2");
         assert_eq!(parsed.license, "");
     }
@@ -574,6 +1098,458 @@ mod tests {
         assert!(result.contains("releaseType") || result.contains("InvalidType"));
     }
 
+    #[test]
+    fn test_song_round_trip_preserves_all_fields() {
+        // Compact JSON with the known fields in model order and unknown keys
+        // after them comes back byte-for-byte
+        let json = r#"{"id":"song-001","title":"Digital Dreams","albumName":"Synthwave Chronicles","releaseType":"Independent","hasContentId":false,"streamingLink":"https://push.fm/digital-dreams","license":"CC BY 4.0","releaseYear":2023,"albumArtwork":"https://example.com/art.jpg","bpm":120,"tags":["synthwave","retro"],"credits":{"mix":"babafun"}}"#;
+
+        let song: Song = serde_json::from_str(json).unwrap();
+        assert_eq!(song.release_year, Some(2023));
+        assert_eq!(song.album_artwork.as_deref(), Some("https://example.com/art.jpg"));
+        assert_eq!(song.extra.len(), 3);
+        assert_eq!(song.extra["bpm"], 120);
+
+        assert_eq!(serde_json::to_string(&song).unwrap(), json);
+    }
+
+    #[test]
+    fn test_song_round_trip_moves_unknown_keys_after_known_fields() {
+        // Known fields are written in model order, so an unknown key between
+        // them moves to the end; unknown keys keep their relative order
+        let json = r#"{"bpm":120,"id":"song-001","title":"T","albumName":"A","releaseType":"NCS","mood":"calm","hasContentId":false,"streamingLink":"https://example.com","license":"","albumId":"album-1","tags":[]}"#;
+        let expected = r#"{"id":"song-001","title":"T","albumName":"A","albumId":"album-1","releaseType":"NCS","hasContentId":false,"streamingLink":"https://example.com","license":"","bpm":120,"mood":"calm","tags":[]}"#;
+
+        let song: Song = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&song).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_song_optional_fields_omitted_when_absent() {
+        let json = r#"{"id":"song-001","title":"Test Song","albumName":"Test Album","releaseType":"NCS","hasContentId":false,"streamingLink":"https://example.com","license":""}"#;
+
+        let song: Song = serde_json::from_str(json).unwrap();
+        assert_eq!(song.release_year, None);
+        assert_eq!(song.album_artwork, None);
+        assert!(song.extra.is_empty());
+
+        // No `null`s are introduced for fields that were never there
+        assert_eq!(serde_json::to_string(&song).unwrap(), json);
+    }
+
+    #[test]
+    fn test_music_data_round_trip_preserves_unknown_keys() {
+        let json = r#"{"songs":[{"id":"song-001","title":"Test Song","albumName":"Test Album","releaseType":"Independent","hasContentId":false,"streamingLink":"https://example.com","license":"","releaseYear":2024,"mood":"calm"}],"albums":[],"artist":"babafun"}"#;
+
+        let data: MusicData = serde_json::from_str(json).unwrap();
+        assert_eq!(data.extra["artist"], "babafun");
+        assert_eq!(data.songs[0].extra["mood"], "calm");
+
+        assert_eq!(serde_json::to_string(&data).unwrap(), json);
+    }
+
+    #[test]
+    fn test_validate_music_data_without_albums() {
+        // music.json only ships songs; albums are derived at runtime
+        let json = r#"{
+            "songs": [
+                {
+                    "id": "song-001",
+                    "title": "Test Song",
+                    "albumName": "Test Album",
+                    "releaseType": "Independent",
+                    "hasContentId": false,
+                    "streamingLink": "https://example.com",
+                    "license": "CC BY 4.0",
+                    "releaseYear": 2023,
+                    "albumArtwork": "https://example.com/art.jpg"
+                }
+            ]
+        }"#;
+        assert_eq!(validate_music_data(json), "");
+    }
+
+    #[test]
+    fn test_album_declarations_are_validated() {
+        let json = r#"{
+            "songs": [{"id": "a", "title": "T", "albumName": "A", "releaseType": "NCS", "hasContentId": false, "streamingLink": "https://example.com", "license": ""}],
+            "albums": [
+                {"id": "album-1", "name": "", "albumType": "Mixtape", "releaseDate": "2023-02-30", "upc": "036000291453"},
+                {"name": 3, "artwork": false},
+                {"id": "album-1", "name": "Again", "releaseDate": "2023-06", "upc": "4006381333931"}
+            ]
+        }"#;
+        let report = check_music_data(json);
+        let errors: Vec<(&str, &str)> = report.errors().map(|d| (d.path.as_str(), d.message.as_str())).collect();
+        assert_eq!(
+            errors,
+            vec![
+                ("/albums/0/name", "Album at index 0 is invalid: Field 'name' cannot be empty"),
+                ("/albums/0/albumType", "Album at index 0 is invalid: Field 'albumType' must be one of: Single, EP, LP, Compilation. Got: Mixtape"),
+                ("/albums/0/releaseDate", "Album at index 0 is invalid: Field 'releaseDate' must be a date like 2023, 2023-06 or 2023-06-15. Got: 2023-02-30"),
+                ("/albums/0/upc", "Album at index 0 is invalid: Field 'upc' must be a 12 or 13 digit code with a valid check digit. Got: 036000291453"),
+                ("/albums/1/id", "Album at index 1 is invalid: Missing required field: id"),
+                ("/albums/1/name", "Album at index 1 is invalid: Field 'name' must be a string"),
+                ("/albums/1/artwork", "Album at index 1 is invalid: Field 'artwork' must be a string"),
+                ("/albums/2/id", "Duplicate album ID 'album-1' found at index 2 (first used at index 0)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_song_errors_come_before_album_errors() {
+        let data = json!({
+            "songs": [testing::song("a").json(), testing::song("a").json()],
+            "albums": [{ "id": "album-1", "name": "" }],
+        });
+        // The legacy string keeps its baseline wording; the report has the detail
+        assert_eq!(validate_music_data(&data.to_string()), "Duplicate song ID 'a' found at index 1");
+        let report = check_music_data(&data.to_string());
+        assert_eq!(report.first_error().unwrap().message, "Duplicate song ID 'a' found at index 1 (first used at index 0)");
+        assert_eq!(report.errors().last().unwrap().path, "/albums/0/name");
+    }
+
+    #[test]
+    fn test_album_references() {
+        let song = |id: &str, album_name: &str, album_id: &str| testing::song(id).album(album_name).album_id(album_id).json();
+        let data = json!({
+            "songs": [song("a", "Nights", "album-1"), song("b", "Days", "album-1"), song("c", "Nights", "album-9"), song("d", "Nights", "")],
+            "albums": [{ "id": "album-1", "name": "Nights" }],
+        });
+        let report = check_music_data(&data.to_string());
+        let diagnostics: Vec<(Severity, &str, DiagnosticCode)> = report.diagnostics.iter().map(|d| (d.severity, d.path.as_str(), d.code)).collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                (Severity::Error, "/songs/3/albumId", DiagnosticCode::EmptyField),
+                (Severity::Warning, "/songs/1/albumName", DiagnosticCode::AlbumNameMismatch),
+                (Severity::Error, "/songs/2/albumId", DiagnosticCode::UnknownAlbum),
+            ]
+        );
+        assert_eq!(report.diagnostics[2].message, "Song at index 2 references unknown album 'album-9'");
+
+        // Without declarations every reference is unknown
+        let data = json!({ "songs": [song("a", "Nights", "album-1")] });
+        assert_eq!(check_music_data(&data.to_string()).first_error().unwrap().code, DiagnosticCode::UnknownAlbum);
+    }
+
+    #[test]
+    fn test_similar_album_names() {
+        let song = |id: &str, album: &str| testing::song(id).album(album).json();
+        let mut with_id = song("id", "Neon  nights");
+        with_id["albumId"] = json!("album-1");
+        let data = json!({
+            "songs": [
+                song("s0", "Synthwave Chronicles"),
+                song("s1", "synthwave  chronicles "),
+                song("s2", "Neon Nights"),
+                song("s3", "N\u{435}on Nights"),
+                song("s4", "Synthwave Chronicels"),
+                song("s5", "Vol. 1"),
+                song("s6", "Vol. 2"),
+                song("s7", "Synthwave Chronicles"),
+                with_id,
+            ],
+            "albums": [{ "id": "album-1", "name": "Neon Nights" }],
+        });
+        let report = check_music_data(&data.to_string());
+        assert!(report.is_valid());
+        let warnings: Vec<(&str, &str)> = report
+            .warnings()
+            .filter(|d| d.code == DiagnosticCode::SimilarAlbumName)
+            .map(|d| (d.path.as_str(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![
+                ("/songs/1/albumName", "Album name 'synthwave  chronicles ' at index 1 looks like a variant of 'Synthwave Chronicles' (first used at index 0): only case or spacing differs"),
+                ("/songs/3/albumName", "Album name 'N\u{435}on Nights' at index 3 looks like a variant of 'Neon Nights' (first used at index 2): only accents or look-alike characters differ"),
+                ("/songs/4/albumName", "Album name 'Synthwave Chronicels' at index 4 looks like a variant of 'Synthwave Chronicles' (first used at index 0): the names are a typo apart"),
+            ]
+        );
+
+        let songs = json!([song("a", "Pulse"), song("b", "pulse")]);
+        assert_eq!(check_songs(&songs.to_string()).warnings().next().unwrap().path, "/1/albumName");
+
+        // Distinct albums that happen to be close in spelling
+        for (first, second) in [("Night", "Light"), ("Part I", "Part II"), ("Chapter IV", "Chapter V"), ("Vol. 1", "Vol. 2"), ("Dreamer", "Dreamers")] {
+            let songs = json!([song("a", first), song("b", second)]);
+            assert_eq!(check_songs(&songs.to_string()).warnings().count(), 0, "{} / {}", first, second);
+        }
+    }
+
+    #[test]
+    fn test_track_positions() {
+        let song = |id: &str, album: &str, position: Option<(u32, u32)>| match position {
+            Some((disc, track)) => testing::song(id).album(album).disc(disc).track(track).json(),
+            None => testing::song(id).album(album).json(),
+        };
+        let data = json!({ "songs": [
+            song("a4", "A", Some((1, 4))),
+            song("a1", "A", Some((1, 1))),
+            song("a2", "A", Some((1, 2))),
+            song("a2-again", "A", Some((1, 2))),
+            song("a-loose", "A", None),
+            song("b1", "B", Some((1, 1))),
+            song("b2", "B", Some((1, 2))),
+            song("b3", "B", Some((3, 2))),
+            song("c", "C", None),
+            song("c2", "C", None),
+        ]});
+        let report = check_music_data(&data.to_string());
+        let diagnostics: Vec<(&str, DiagnosticCode, &str)> = report.diagnostics.iter().map(|d| (d.path.as_str(), d.code, d.message.as_str())).collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                ("/songs/4/trackNumber", DiagnosticCode::MissingTrackNumber, "Song at index 4 has no trackNumber, unlike the other songs in album 'A'"),
+                ("/songs/3/trackNumber", DiagnosticCode::DuplicateTrack, "Song at index 3 is track 2 of album 'A', like song at index 2"),
+                ("/songs/0/trackNumber", DiagnosticCode::TrackGap, "Album 'A' is missing track 3"),
+                ("/songs/7/discNumber", DiagnosticCode::TrackGap, "Album 'B' is missing disc 2"),
+                ("/songs/7/trackNumber", DiagnosticCode::TrackGap, "Album 'B' is missing track 1 on disc 3"),
+            ]
+        );
+
+        // Bare song arrays are checked the same way
+        let songs = json!([song("x", "X", Some((1, 3)))]);
+        assert_eq!(check_songs(&songs.to_string()).first_error().unwrap().message, "Album 'X' is missing tracks 1-2");
+
+        let mut zero = song("z", "Z", None);
+        zero["trackNumber"] = json!(0);
+        assert_eq!(check_song(&zero.to_string()).first_error().unwrap().message, "Field 'trackNumber' must be a positive integer");
+    }
+
+    #[test]
+    fn test_calendar_dates_and_upcs() {
+        for date in ["2023", "2023-06", "2023-06-15", "2024-02-29", "2000-02-29"] {
+            assert!(is_calendar_date(date), "{}", date);
+        }
+        for date in ["23", "2023-6", "2023-13", "2023-02-29", "1900-02-29", "2023-04-31", "2023-06-15T00:00", "June 2023", ""] {
+            assert!(!is_calendar_date(date), "{}", date);
+        }
+        assert!(is_valid_upc("036000291452"));
+        assert!(is_valid_upc("4006381333931"));
+        assert!(!is_valid_upc("036000291450"));
+        assert!(!is_valid_upc("03600029145"));
+        assert!(!is_valid_upc("03600029145x"));
+    }
+
+    #[test]
+    fn test_check_songs_array() {
+        let song = testing::song("a").json();
+        let report = check_songs(&format!("[{}, {}, {{\"id\": \"b\"}}]", song, song));
+        let errors: Vec<(&str, DiagnosticCode)> = report.errors().map(|d| (d.path.as_str(), d.code)).collect();
+        assert_eq!(errors[0], ("/2/title", DiagnosticCode::MissingField));
+        assert_eq!(errors.last(), Some(&("/1/id", DiagnosticCode::DuplicateId)));
+        assert!(report.errors().all(|d| d.span.is_some()));
+
+        let report = check_songs("{}");
+        assert_eq!(report.first_error().unwrap().message, "Input must be a JSON array of songs");
+    }
+
+    #[test]
+    fn test_validate_song_invalid_optional_fields() {
+        let json = r#"{
+            "id": "song-001",
+            "title": "Test Song",
+            "albumName": "Test Album",
+            "releaseType": "Independent",
+            "hasContentId": false,
+            "streamingLink": "https://example.com",
+            "license": "",
+            "releaseYear": "2023"
+        }"#;
+        assert!(validate_song(json).contains("releaseYear' must be a non-negative integer"));
+
+        let json = r#"{
+            "id": "song-001",
+            "title": "Test Song",
+            "albumName": "Test Album",
+            "releaseType": "Independent",
+            "hasContentId": false,
+            "streamingLink": "https://example.com",
+            "license": "",
+            "albumArtwork": 42
+        }"#;
+        assert!(validate_song(json).contains("albumArtwork' must be a string"));
+    }
+
+    #[test]
+    fn test_check_song_collects_all_errors() {
+        let json = r#"{
+            "id": "",
+            "title": 7,
+            "albumName": "Test Album",
+            "releaseType": "Bandcamp",
+            "hasContentId": "no",
+            "license": ""
+        }"#;
+        let report = check_song(json);
+        assert!(!report.is_valid());
+
+        let found: Vec<(&str, &str)> = report.errors().map(|d| (d.path.as_str(), d.code.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("/streamingLink", "missing-field"),
+                ("/id", "empty-field"),
+                ("/title", "invalid-type"),
+                ("/releaseType", "invalid-value"),
+                ("/hasContentId", "invalid-type"),
+            ]
+        );
+
+        // The legacy string API still reports the first problem only
+        assert_eq!(validate_song(json), "Missing required field: streamingLink");
+    }
+
+    #[test]
+    fn test_check_song_non_http_link_is_warning() {
+        let json = r#"{
+            "id": "song-001",
+            "title": "Test Song",
+            "albumName": "Test Album",
+            "releaseType": "Independent",
+            "hasContentId": false,
+            "streamingLink": "spotify:track:123",
+            "license": ""
+        }"#;
+        let report = check_song(json);
+        assert!(report.is_valid());
+        assert_eq!(report.warnings().count(), 1);
+        assert_eq!(report.diagnostics[0].code, DiagnosticCode::NonHttpLink);
+        assert_eq!(validate_song(json), "");
+    }
+
+    #[test]
+    fn test_check_song_malformed_license_expression_is_warning() {
+        let json = r#"{
+            "id": "song-001",
+            "title": "Test Song",
+            "albumName": "Test Album",
+            "releaseType": "Independent",
+            "hasContentId": false,
+            "streamingLink": "https://example.com",
+            "license": "CC-BY-4.0 OR"
+        }"#;
+        let report = check_song(json);
+        assert!(report.is_valid());
+        assert_eq!(report.diagnostics[0].code, DiagnosticCode::InvalidLicenseExpression);
+        assert_eq!(report.diagnostics[0].path, "/license");
+
+        let json = json.replace("CC-BY-4.0 OR", "CC-BY-4.0 OR BGML-P");
+        assert!(check_song(&json).diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_check_music_data_reports_every_song() {
+        let json = r#"{
+            "songs": [
+                {
+                    "id": "song-001",
+                    "title": "Test Song 1",
+                    "albumName": "Test Album",
+                    "releaseType": "Independent",
+                    "hasContentId": false,
+                    "streamingLink": "https://example.com",
+                    "license": ""
+                },
+                {
+                    "id": "song-002",
+                    "title": "",
+                    "albumName": "Test Album",
+                    "releaseType": "Independent",
+                    "hasContentId": false,
+                    "streamingLink": "https://example.com",
+                    "license": ""
+                },
+                {
+                    "id": "song-001",
+                    "title": "Test Song 3",
+                    "albumName": "Test Album",
+                    "releaseType": "Label",
+                    "hasContentId": false,
+                    "streamingLink": "https://example.com",
+                    "license": ""
+                }
+            ]
+        }"#;
+        let report = check_music_data(json);
+        let found: Vec<(&str, DiagnosticCode)> = report.errors().map(|d| (d.path.as_str(), d.code)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("/songs/1/title", DiagnosticCode::EmptyField),
+                ("/songs/2/releaseType", DiagnosticCode::InvalidValue),
+                ("/songs/2/id", DiagnosticCode::DuplicateId),
+            ]
+        );
+        assert!(report.diagnostics[0].message.starts_with("Song at index 1 is invalid"));
+        assert!(report.diagnostics[2].message.contains("first used at index 0"));
+    }
+
+    #[test]
+    fn test_validate_music_data_report_json() {
+        let result = validate_music_data_report(r#"{"songs": {}, "albums": 3}"#);
+        let parsed: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(parsed["valid"], false);
+        assert_eq!(parsed["errorCount"], 2);
+        assert_eq!(parsed["diagnostics"][0]["path"], "/songs");
+        assert_eq!(parsed["diagnostics"][0]["code"], "invalid-type");
+        assert_eq!(parsed["diagnostics"][1]["path"], "/albums");
+
+        let result = validate_song_report("{");
+        let parsed: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(parsed["diagnostics"][0]["code"], "invalid-json");
+        assert_eq!(parsed["diagnostics"][0]["severity"], "error");
+    }
+
+    #[test]
+    fn test_check_music_data_diagnostics_carry_positions() {
+        let json = "{\n  \"songs\": [\n    {\n      \"id\": \"song-001\",\n      \"title\": \"Test\",\n      \"albumName\": \"Album\",\n      \"releaseType\": \"Label\",\n      \"hasContentId\": false,\n      \"streamingLink\": \"https://example.com\"\n    }\n  ]\n}";
+        let report = check_music_data(json);
+        let errors: Vec<&Diagnostic> = report.errors().collect();
+        assert_eq!(errors.len(), 2);
+
+        // Missing field points at the song object that lacks it
+        assert_eq!(errors[0].code, DiagnosticCode::MissingField);
+        let span = errors[0].span.unwrap();
+        assert_eq!((span.line, span.column, span.end_line), (3, 5, 10));
+
+        // Invalid value points at the value itself
+        assert_eq!(errors[1].path, "/songs/0/releaseType");
+        let span = errors[1].span.unwrap();
+        assert_eq!(&json[span.start..span.end], "\"Label\"");
+        assert_eq!((span.line, span.column), (7, 22));
+
+        let parsed: Value = serde_json::from_str(&validate_music_data_report(json)).unwrap();
+        assert_eq!(parsed["diagnostics"][1]["span"]["line"], 7);
+        assert_eq!(parsed["diagnostics"][1]["span"]["endColumn"], 29);
+    }
+
+    #[test]
+    fn test_check_song_syntax_error_position() {
+        let report = check_song("{\n  \"id\": \"song-001\",\n  \"title\" \"Test\"\n}");
+        let error = report.first_error().unwrap();
+        assert_eq!(error.code, DiagnosticCode::InvalidJson);
+        let span = error.span.unwrap();
+        assert_eq!(span.line, 3);
+        assert_eq!(span.start, span.end);
+    }
+
+    #[test]
+    fn test_batch_validate_songs_collects_all_errors() {
+        let json = r#"[
+            { "id": "song-001", "title": "", "albumName": "", "releaseType": "NCS", "hasContentId": false, "streamingLink": "https://example.com", "license": "" },
+            "not a song"
+        ]"#;
+        let parsed: Value = serde_json::from_str(&batch_validate_songs(json)).unwrap();
+        let array = parsed.as_array().unwrap();
+        assert_eq!(array[0]["valid"], false);
+        assert_eq!(array[0]["errors"].as_array().unwrap().len(), 2);
+        assert_eq!(array[1]["valid"], false);
+        assert_eq!(array[1]["songId"], Value::Null);
+    }
+
     #[test]
     fn test_validate_music_data_missing_songs_field() {
         let json = r#"{
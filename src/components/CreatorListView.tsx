@@ -14,13 +14,8 @@
 
 import React from 'react';
 import type { Song } from '../types/music';
-import { filterCreatorFriendly } from '../utils/filters';
-import { 
-  isCreatorFriendlySong, 
-  getCreatorFriendlyReason, 
-  shouldDisplayLicense,
-  getStreamingLinkText 
-} from '../utils/display';
+import { filterCreatorFriendly, getCreatorFriendlyReason } from '../utils/filters';
+import { shouldDisplayLicense, getStreamingLinkText } from '../utils/display';
 
 /**
  * Props interface for CreatorListView component
@@ -153,10 +148,8 @@ interface CreatorStatsProps {
 const CreatorStats: React.FC<CreatorStatsProps> = ({ totalSongs, creatorFriendlySongs }) => {
   // Count by release type
   const ncsCount = creatorFriendlySongs.filter(song => song.releaseType === 'NCS').length;
-  const ccCount = creatorFriendlySongs.filter(song => 
-    song.releaseType !== 'NCS' && 
-    (song.license.toLowerCase().includes('cc') || song.license.toLowerCase().includes('bgml-p'))
-  ).length;
+  // Every other creator-friendly song qualifies through its license
+  const ccCount = creatorFriendlySongs.length - ncsCount;
   
   const percentage = totalSongs > 0 ? Math.round((creatorFriendlySongs.length / totalSongs) * 100) : 0;
 
@@ -207,15 +200,7 @@ export const CreatorListView: React.FC<CreatorListViewProps> = ({ songs }) => {
   }
 
   // Filter to creator-friendly songs using the WASM-powered utility
-  const creatorFriendlySongs = React.useMemo(() => {
-    try {
-      return filterCreatorFriendly(songs);
-    } catch (error) {
-      console.error('Error filtering creator-friendly songs:', error);
-      // Fallback to JavaScript filtering if WASM fails
-      return songs.filter(song => isCreatorFriendlySong(song));
-    }
-  }, [songs]);
+  const creatorFriendlySongs = React.useMemo(() => filterCreatorFriendly(songs), [songs]);
 
   // Handle case where no creator-friendly songs exist
   if (creatorFriendlySongs.length === 0) {
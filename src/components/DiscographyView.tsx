@@ -6,7 +6,8 @@
  * 
  * Features:
  * - Groups songs by album name
- * - Displays album names as section headers
+ * - Displays album names as section headers, with declared album metadata
+ *   (artwork, type, release date, description) when present
  * - Lists songs under each album
  * - Applies consistent purple theme styling
  * - Uses semantic HTML for accessibility
@@ -14,6 +15,7 @@
 
 import React from 'react';
 import type { Album, Song } from '../types/music';
+import { isCreatorFriendlySong } from '../utils/filters';
 
 /**
  * Props interface for DiscographyView component
@@ -34,7 +36,12 @@ const SongItem: React.FC<SongItemProps> = ({ song }) => {
   return (
     <article className="song-card" role="article">
       <header>
-        <h4 className="mb-1">{song.title}</h4>
+        <h4 className="mb-1">
+          {song.trackNumber !== undefined && (
+            <span className="track-number text-muted">{song.trackNumber}. </span>
+          )}
+          {song.title}
+        </h4>
       </header>
       
       <div className="song-meta">
@@ -48,9 +55,7 @@ const SongItem: React.FC<SongItemProps> = ({ song }) => {
           </span>
         )}
         
-        {(song.releaseType === 'NCS' || 
-          song.license.toLowerCase().includes('cc') || 
-          song.license.toLowerCase().includes('bgml-p')) && (
+        {isCreatorFriendlySong(song) && (
           <span className="badge creator-friendly" title="This song is free for creators to use">
             Creator Friendly
           </span>
@@ -102,9 +107,18 @@ const AlbumSection: React.FC<AlbumSectionProps> = ({ album }) => {
         >
           {album.name}
         </h2>
+        {album.artwork && (
+          <img src={album.artwork} alt={`${album.name} cover art`} className="album-artwork" loading="lazy" />
+        )}
+        {(album.albumType || album.releaseDate) && (
+          <p className="album-meta text-muted">
+            {[album.albumType, album.releaseDate].filter(Boolean).join(' • ')}
+          </p>
+        )}
         <p className="text-muted mb-2">
           {album.songs.length} song{album.songs.length !== 1 ? 's' : ''}
         </p>
+        {album.description && <p className="album-description">{album.description}</p>}
       </header>
       
       <div className="album-songs">
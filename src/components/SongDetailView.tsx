@@ -15,6 +15,7 @@
 import React from 'react';
 import type { Song } from '../types/music';
 import { shouldDisplayLicense } from '../utils/display';
+import { isCreatorFriendlySong } from '../utils/filters';
 
 /**
  * Props interface for SongDetailView component
@@ -32,24 +33,6 @@ interface SongDetailCardProps {
 }
 
 const SongDetailCard: React.FC<SongDetailCardProps> = ({ song }) => {
-  /**
-   * Determines if a song is creator-friendly based on license and release type
-   */
-  const isCreatorFriendly = (song: Song): boolean => {
-    // NCS releases are always creator-friendly
-    if (song.releaseType === 'NCS') {
-      return true;
-    }
-    
-    // Check for commercial CC licenses
-    const license = song.license.toLowerCase();
-    if (license.includes('cc by') || license.includes('cc0') || license.includes('bgml-p')) {
-      return true;
-    }
-    
-    return false;
-  };
-
   /**
    * Gets a human-readable description of the Content ID status
    */
@@ -93,7 +76,7 @@ const SongDetailCard: React.FC<SongDetailCardProps> = ({ song }) => {
           </div>
         )}
 
-        {isCreatorFriendly(song) && (
+        {isCreatorFriendlySong(song) && (
           <div className="metadata-row mt-1">
             <span className="badge creator-friendly" title="This song is free for creators to use">
               ✓ Creator Friendly
@@ -6,6 +6,7 @@ import type { MusicData, Song } from '../types/music';
 const AlbumPage: React.FC = () => {
   const { albumId } = useParams<{ albumId: string }>();
   const [musicData, setMusicData] = useState<MusicData | null>(null);
+  const [creatorFriendlyIds, setCreatorFriendlyIds] = useState<Set<string>>(new Set());
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
 
@@ -13,7 +14,11 @@ const AlbumPage: React.FC = () => {
     const loadData = async () => {
       try {
         const dataLoader = DataLoader.getInstance();
-        const data = await dataLoader.loadMusicData();
+        // Albums and creator-friendly verdicts come from the catalog handle,
+        // so the song list isn't sent back into WASM
+        const { data, catalog } = await dataLoader.loadMusicCatalog();
+        setCreatorFriendlyIds(new Set(catalog.creatorFriendly().map((song: Song) => song.id)));
+        catalog.free();
         setMusicData(data);
       } catch (err) {
         setError(err instanceof Error ? err.message : 'Failed to load music data');
@@ -64,16 +69,13 @@ const AlbumPage: React.FC = () => {
   // Sort songs by title
   const sortedSongs = [...album.songs].sort((a, b) => a.title.localeCompare(b.title));
 
-  const getAlbumYear = () => {
-    const years = album.songs.map(song => song.releaseYear);
-    return Math.min(...years);
+  // Earliest release year, or undefined when no song has one
+  const getAlbumYear = (): number | undefined => {
+    const years = album.songs.flatMap(song => song.releaseYear ?? []);
+    return years.length > 0 ? Math.min(...years) : undefined;
   };
 
-  const isCreatorFriendly = (song: Song) => {
-    return song.releaseType === 'NCS' || 
-           song.license.toLowerCase().includes('cc') || 
-           song.license === 'BGML-P';
-  };
+  const albumYear = getAlbumYear();
 
   return (
     <div className="page album-page">
@@ -106,7 +108,7 @@ const AlbumPage: React.FC = () => {
           )}
           <h1 className="album-title">{album.name}</h1>
           <div className="album-meta">
-            <span className="album-year">{getAlbumYear()}</span>
+            {albumYear !== undefined && <span className="album-year">{albumYear}</span>}
             <span className="album-count">{album.songs.length} songs</span>
           </div>
           <div className="album-labels">
@@ -148,7 +150,7 @@ const AlbumPage: React.FC = () => {
                     <span className={`label label-${song.releaseType.toLowerCase()}`}>
                       {song.releaseType}
                     </span>
-                    {isCreatorFriendly(song) && (
+                    {creatorFriendlyIds.has(song.id) && (
                       <span className="label label-creator-friendly">Creator Friendly</span>
                     )}
                     {song.hasContentId && (
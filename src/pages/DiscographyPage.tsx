@@ -12,7 +12,9 @@ const DiscographyPage: React.FC = () => {
     const loadData = async () => {
       try {
         const dataLoader = DataLoader.getInstance();
-        const data = await dataLoader.loadMusicData();
+        // Albums come from the catalog handle, so the song list isn't sent back into WASM
+        const { data, catalog } = await dataLoader.loadMusicCatalog();
+        catalog.free();
         setMusicData(data);
       } catch (err) {
         setError(err instanceof Error ? err.message : 'Failed to load music data');
@@ -28,9 +30,10 @@ const DiscographyPage: React.FC = () => {
     return albumName.toLowerCase().replace(/\s+/g, '-').replace(/[^a-z0-9-]/g, '');
   };
 
-  const getAlbumYear = (album: Album) => {
-    const years = album.songs.map(song => song.releaseYear);
-    return Math.min(...years);
+  // Earliest release year, or undefined when no song has one
+  const getAlbumYear = (album: Album): number | undefined => {
+    const years = album.songs.flatMap(song => song.releaseYear ?? []);
+    return years.length > 0 ? Math.min(...years) : undefined;
   };
 
   if (loading) {
@@ -54,9 +57,14 @@ const DiscographyPage: React.FC = () => {
     );
   }
 
-  // Sort albums by year (newest first)
+  // Sort albums by year (newest first), albums without a year last
   const sortedAlbums = [...musicData.albums].sort((a, b) => {
-    return getAlbumYear(b) - getAlbumYear(a);
+    const yearA = getAlbumYear(a);
+    const yearB = getAlbumYear(b);
+    if (yearA === undefined || yearB === undefined) {
+      return (yearA === undefined ? 1 : 0) - (yearB === undefined ? 1 : 0);
+    }
+    return yearB - yearA;
   });
 
   return (
@@ -97,7 +105,7 @@ const DiscographyPage: React.FC = () => {
                     </Link>
                   </h2>
                   <div className="album-meta">
-                    <span className="album-year">{albumYear}</span>
+                    {albumYear !== undefined && <span className="album-year">{albumYear}</span>}
                     <span className="album-count">{album.songs.length} songs</span>
                   </div>
                   <div className="album-labels">
@@ -2,18 +2,20 @@ import React, { useState, useEffect, useMemo } from 'react';
 import { Link } from 'react-router-dom';
 import { DataLoader } from '../utils/dataLoader';
 import type { MusicData, Song, ReleaseType } from '../types/music';
+import type { Catalog } from '../../rust/pkg/rust';
 
 interface SearchFilters {
   query: string;
   releaseTypes: ReleaseType[];
   licenses: string[];
   creatorFriendlyOnly: boolean;
-  sortBy: 'title' | 'year' | 'album';
+  sortBy: 'relevance' | 'title' | 'year' | 'album';
   sortOrder: 'asc' | 'desc';
 }
 
 const SearchPage: React.FC = () => {
   const [musicData, setMusicData] = useState<MusicData | null>(null);
+  const [catalog, setCatalog] = useState<Catalog | null>(null);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [filtersExpanded, setFiltersExpanded] = useState(false);
@@ -22,24 +24,38 @@ const SearchPage: React.FC = () => {
     releaseTypes: [],
     licenses: [],
     creatorFriendlyOnly: false,
-    sortBy: 'title',
+    sortBy: 'relevance',
     sortOrder: 'asc'
   });
 
   useEffect(() => {
+    let loadedCatalog: Catalog | null = null;
+    let cancelled = false;
+
     const loadData = async () => {
       try {
         const dataLoader = DataLoader.getInstance();
-        const data = await dataLoader.loadMusicData();
+        // One fetch of music.json gives both the songs and the catalog searching them
+        const { data, catalog: searchCatalog } = await dataLoader.loadMusicCatalog();
+        if (cancelled) {
+          searchCatalog.free();
+          return;
+        }
+        loadedCatalog = searchCatalog;
         setMusicData(data);
+        setCatalog(searchCatalog);
       } catch (err) {
-        setError(err instanceof Error ? err.message : 'Failed to load music data');
+        if (!cancelled) setError(err instanceof Error ? err.message : 'Failed to load music data');
       } finally {
-        setLoading(false);
+        if (!cancelled) setLoading(false);
       }
     };
 
     loadData();
+    return () => {
+      cancelled = true;
+      loadedCatalog?.free();
+    };
   }, []);
 
   const createSongId = (songTitle: string) => {
@@ -50,11 +66,14 @@ const SearchPage: React.FC = () => {
     return albumName.toLowerCase().replace(/\s+/g, '-').replace(/[^a-z0-9-]/g, '');
   };
 
-  const isCreatorFriendly = (song: Song) => {
-    return song.releaseType === 'NCS' || 
-           song.license.toLowerCase().includes('cc') || 
-           song.license === 'BGML-P';
-  };
+  // Creator-friendly verdicts come from the catalog's Rust/WASM policy, so they
+  // match every other page without sending the song list back into WASM
+  const creatorFriendlyIds = useMemo(() => {
+    if (!catalog) return new Set<string>();
+    return new Set(catalog.creatorFriendly().map((song: Song) => song.id));
+  }, [catalog]);
+
+  const isCreatorFriendly = (song: Song) => creatorFriendlyIds.has(song.id);
 
   // Get unique values for filter options
   const filterOptions = useMemo(() => {
@@ -72,14 +91,14 @@ const SearchPage: React.FC = () => {
     
     let songs = [...musicData.songs];
     
-    // Apply text search
-    if (filters.query) {
-      const query = filters.query.toLowerCase();
-      songs = songs.filter(song => 
-        song.title.toLowerCase().includes(query) ||
-        song.albumName.toLowerCase().includes(query) ||
-        song.license.toLowerCase().includes(query)
-      );
+    // Apply text search with the catalog's prebuilt search index, remembering
+    // each match's BM25 rank for the relevance sort
+    let ranks: Map<string, number> | null = null;
+    if (filters.query.trim() && catalog) {
+      const rankedIds: string[] = catalog.rankedSearchIds(filters.query);
+      const matchRanks = new Map(rankedIds.map((id, rank) => [id, rank]));
+      songs = songs.filter(song => matchRanks.has(song.id));
+      ranks = matchRanks;
     }
     
     // Apply release type filter
@@ -102,10 +121,20 @@ const SearchPage: React.FC = () => {
       let comparison = 0;
       
       switch (filters.sortBy) {
+        case 'relevance':
+          // Without a query there is no ranking, so fall back to title order
+          comparison = ranks
+            ? (ranks.get(a.id) ?? 0) - (ranks.get(b.id) ?? 0)
+            : a.title.localeCompare(b.title);
+          break;
         case 'title':
           comparison = a.title.localeCompare(b.title);
           break;
         case 'year':
+          // Songs without a year go last in either order, as in the Rust sort
+          if (a.releaseYear === undefined || b.releaseYear === undefined) {
+            return (a.releaseYear === undefined ? 1 : 0) - (b.releaseYear === undefined ? 1 : 0);
+          }
           comparison = a.releaseYear - b.releaseYear;
           break;
         case 'album':
@@ -117,7 +146,7 @@ const SearchPage: React.FC = () => {
     });
     
     return songs;
-  }, [musicData, filters]);
+  }, [musicData, catalog, filters, creatorFriendlyIds]);
 
   const handleFilterChange = (key: keyof SearchFilters, value: any) => {
     setFilters(prev => ({ ...prev, [key]: value }));
@@ -147,7 +176,7 @@ const SearchPage: React.FC = () => {
       releaseTypes: [],
       licenses: [],
       creatorFriendlyOnly: false,
-      sortBy: 'title',
+      sortBy: 'relevance',
       sortOrder: 'asc'
     });
   };
@@ -275,6 +304,7 @@ const SearchPage: React.FC = () => {
                   onChange={(e) => handleFilterChange('sortBy', e.target.value)}
                   className="sort-select"
                 >
+                  <option value="relevance">Relevance</option>
                   <option value="title">Title</option>
                   <option value="year">Release Year</option>
                   <option value="album">Album</option>
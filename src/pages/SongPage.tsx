@@ -6,6 +6,7 @@ import type { MusicData, Song } from '../types/music';
 const SongPage: React.FC = () => {
   const { songId } = useParams<{ songId: string }>();
   const [musicData, setMusicData] = useState<MusicData | null>(null);
+  const [creatorFriendlyIds, setCreatorFriendlyIds] = useState<Set<string>>(new Set());
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
 
@@ -13,7 +14,11 @@ const SongPage: React.FC = () => {
     const loadData = async () => {
       try {
         const dataLoader = DataLoader.getInstance();
-        const data = await dataLoader.loadMusicData();
+        // Albums and creator-friendly verdicts come from the catalog handle,
+        // so the song list isn't sent back into WASM
+        const { data, catalog } = await dataLoader.loadMusicCatalog();
+        setCreatorFriendlyIds(new Set(catalog.creatorFriendly().map((song: Song) => song.id)));
+        catalog.free();
         setMusicData(data);
       } catch (err) {
         setError(err instanceof Error ? err.message : 'Failed to load music data');
@@ -63,12 +68,6 @@ const SongPage: React.FC = () => {
 
   const albumId = createAlbumId(song.albumName);
 
-  const isCreatorFriendly = (song: Song) => {
-    return song.releaseType === 'NCS' || 
-           song.license.toLowerCase().includes('cc') || 
-           song.license === 'BGML-P';
-  };
-
   const getLicenseDescription = (license: string) => {
     if (!license) return null;
     
@@ -128,7 +127,7 @@ const SongPage: React.FC = () => {
                   {song.hasContentId ? 'Yes' : 'No'}
                 </span>
               </div>
-              {isCreatorFriendly(song) && (
+              {creatorFriendlyIds.has(song.id) && (
                 <div className="info-item">
                   <span className="info-label">Creator Friendly:</span>
                   <span className="label label-creator-friendly">Yes</span>
@@ -166,7 +165,7 @@ const SongPage: React.FC = () => {
           </Link>
         </div>
         
-        {isCreatorFriendly(song) && (
+        {creatorFriendlyIds.has(song.id) && (
           <div className="creator-info">
             <h3>For Content Creators</h3>
             <p>
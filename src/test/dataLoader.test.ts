@@ -6,6 +6,7 @@
 import { describe, it, expect, beforeAll, vi } from 'vitest';
 import { DataLoader, loadMusicData, DataLoaderError } from '../utils/dataLoader';
 import { MusicData } from '../types/music';
+import { createCatalog } from '../wasm/bindings';
 
 // Mock fetch for testing
 const mockMusicData = {
@@ -128,6 +129,52 @@ describe('DataLoader', () => {
       .rejects.toThrow(DataLoaderError);
   });
 
+  it('should load music data and a searchable catalog from one fetch', async () => {
+    (global.fetch as any).mockClear();
+
+    // The fetch mock has no search index, so the catalog indexes the songs itself
+    const { data, catalog } = await dataLoader.loadMusicCatalog();
+    const musicJsonFetches = (global.fetch as any).mock.calls.filter(([url]: [string]) => url.includes('music.json'));
+    expect(musicJsonFetches).toHaveLength(1);
+
+    expect(data.songs).toHaveLength(3);
+    expect(data.albums.map(album => album.name)).toEqual(['Electronic Fusion', 'Synthwave Chronicles']);
+    expect(catalog.length).toBe(3);
+    expect(catalog.rankedSearchIds('neon')).toEqual(['song-002']);
+    expect(catalog.creatorFriendly().map((song: { id: string }) => song.id)).toEqual(['song-001', 'song-003']);
+    catalog.free();
+  });
+
+  it('should reject invalid music data when loading a catalog', async () => {
+    (global.fetch as any).mockImplementationOnce(() =>
+      Promise.resolve({ ok: true, status: 200, statusText: 'OK', text: () => Promise.resolve('{"songs": [{"id": ""}]}') })
+    );
+
+    await expect(dataLoader.loadMusicCatalog()).rejects.toThrow(/Data validation failed/);
+  });
+
+  it('should load a searchable catalog with its prebuilt index', async () => {
+    const jsonText = JSON.stringify(mockMusicData);
+    const indexed = createCatalog(jsonText);
+    const searchIndex = indexed.exportIndex();
+    indexed.free();
+
+    (global.fetch as any).mockImplementation((url: string) => {
+      if (url.includes('search-index.bin')) {
+        return Promise.resolve({ ok: true, status: 200, statusText: 'OK', arrayBuffer: () => Promise.resolve(searchIndex.slice().buffer) });
+      }
+      return Promise.resolve({ ok: true, status: 200, statusText: 'OK', text: () => Promise.resolve(jsonText) });
+    });
+
+    // A rejected index would be reported before falling back to indexing in place
+    const warn = vi.spyOn(console, 'warn');
+    const { catalog } = await dataLoader.loadMusicCatalog();
+    expect(warn).not.toHaveBeenCalled();
+    expect(catalog.rankedSearchIds('electric pulse')).toEqual(['song-003']);
+    catalog.free();
+    warn.mockRestore();
+  });
+
   it('should validate raw JSON data', async () => {
     const validJson = JSON.stringify({
       songs: [
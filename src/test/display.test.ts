@@ -12,8 +12,6 @@ import { describe, it, expect } from 'vitest';
 import fc from 'fast-check';
 import {
   shouldDisplayLicense,
-  isCreatorFriendlySong,
-  getCreatorFriendlyReason,
   getContentIdDescription,
   formatReleaseType,
   shouldDisplayStreamingLink,
@@ -21,6 +19,7 @@ import {
   filterSongsForDisplay,
   sortSongsForDisplay
 } from '../utils/display';
+import { isCreatorFriendlySong, getCreatorFriendlyReason } from '../utils/filters';
 import type { Song, ReleaseType } from '../types/music';
 
 // Custom arbitraries for testing
@@ -172,7 +171,7 @@ describe('Display Logic Utilities', () => {
       };
       
       const ccReason = getCreatorFriendlyReason(ccSong);
-      expect(ccReason).toContain('Creative Commons');
+      expect(ccReason).toContain('CC BY 4.0');
       
       // Non-creator-friendly
       const restrictiveSong: Song = {
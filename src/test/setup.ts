@@ -1,18 +1,15 @@
 import { expect, afterEach, beforeAll } from 'vitest';
 import { cleanup } from '@testing-library/react';
 import * as matchers from '@testing-library/jest-dom/matchers';
-import { initWasmSync } from '../utils/filters';
+import { readFileSync } from 'node:fs';
+import { initWasm } from '../wasm/bindings';
 
 // Extend Vitest's expect with jest-dom matchers
 expect.extend(matchers);
 
-// Initialize WASM before running tests
-beforeAll(() => {
-  try {
-    initWasmSync();
-  } catch (error) {
-    console.warn('WASM initialization failed in test setup, tests will use JavaScript fallbacks:', error);
-  }
+// Initialize WASM before running tests; Node can't fetch the module, so read it from disk
+beforeAll(async () => {
+  await initWasm(readFileSync(new URL('../../rust/pkg/rust_bg.wasm', import.meta.url)));
 });
 
 // Cleanup after each test
@@ -1,64 +1,28 @@
 /**
- * TypeScript interfaces for the music portfolio application.
- * These interfaces match the Rust structs and define the data structure
- * for songs, albums, and music data throughout the application.
+ * TypeScript types for the music portfolio application.
+ * The data model lives in the Rust crate: these types are generated from the
+ * Rust structs by `wasm-pack build` (see rust/pkg/rust.d.ts) and re-exported
+ * here so the rest of the app has a single place to import them from.
  */
 
-/**
- * Release type classification for songs
- */
-export type ReleaseType = 'Independent' | 'NCS' | 'Monstercat';
+import type { Album, Song } from '../../rust/pkg/rust';
 
-/**
- * Song interface representing a single music track
- */
-export interface Song {
-  /** Unique identifier for the song */
-  id: string;
-  
-  /** Title of the song */
-  title: string;
-  
-  /** Name of the album this song belongs to */
-  albumName: string;
-  
-  /** Type of release (Independent, NCS, or Monstercat) */
-  releaseType: ReleaseType;
-  
-  /** Whether the song has YouTube Content ID enabled */
-  hasContentId: boolean;
-  
-  /** URL to streaming platform for the song */
-  streamingLink: string;
-  
-  /** License information (can be empty string) */
-  license: string;
-  
-  /** Year the song was released */
-  releaseYear: number;
-  
-  /** Album artwork URL (optional) */
-  albumArtwork?: string;
-}
-
-/**
- * Album interface representing a collection of songs
- */
-export interface Album {
-  /** Name of the album */
-  name: string;
-  
-  /** Array of songs in this album */
-  songs: Song[];
-}
+export type {
+  ReleaseType,
+  Song,
+  Album,
+  AlbumInfo,
+  AlbumMetadata,
+  AlbumType,
+  MusicData as MusicDataFile,
+} from '../../rust/pkg/rust';
 
 /**
  * Main music data structure containing all songs and albums
+ * `music.json` declares album metadata (`MusicDataFile`); the data loader
+ * groups the songs into albums and attaches that metadata
  */
 export interface MusicData {
-  /** Array of all songs in the portfolio */
   songs: Song[];
-  
-  /** Array of albums (derived from songs grouped by album name) */
   albums: Album[];
-}
\ No newline at end of file
+}
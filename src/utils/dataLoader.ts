@@ -8,11 +8,13 @@
  * - Loading JSON data from files
  * - Validating data structure using WASM
  * - Grouping songs by album using WASM
+ * - Loading a searchable catalog with its prebuilt search index
  * - Error handling and type safety
  */
 
-import { Song, Album, MusicData } from '../types/music';
-import { initWasm, validateMusicData, groupByAlbum } from '../wasm/bindings';
+import { Song, Album, AlbumInfo, MusicData } from '../types/music';
+import { initWasm, validateMusicData, groupByAlbum, createCatalog, createCatalogWithIndex } from '../wasm/bindings';
+import type { Catalog, ValidationSummary } from '../../rust/pkg/rust';
 
 /**
  * Error thrown when data loading or validation fails
@@ -24,6 +26,14 @@ export class DataLoaderError extends Error {
   }
 }
 
+/**
+ * Music data with a WASM catalog handle over the same songs
+ */
+export interface MusicCatalog {
+  data: MusicData;
+  catalog: Catalog;
+}
+
 /**
  * DataLoader class for loading and processing music data
  * Uses Rust/WASM for performance-critical validation and grouping operations
@@ -88,7 +98,7 @@ export class DataLoader {
       const jsonText = await response.text();
       
       // Parse JSON first
-      let rawData: { songs: Song[] };
+      let rawData: { songs: Song[]; albums?: AlbumInfo[] };
       try {
         rawData = JSON.parse(jsonText);
       } catch (parseError) {
@@ -103,8 +113,8 @@ export class DataLoader {
         throw new DataLoaderError('Invalid data structure: missing or invalid songs array');
       }
 
-      // Group albums using Rust/WASM for performance
-      const albumsJson = groupByAlbum(JSON.stringify(rawData.songs));
+      // Group albums using Rust/WASM for performance, attaching declared album metadata
+      const albumsJson = groupByAlbum(JSON.stringify(rawData.songs), JSON.stringify(rawData.albums ?? []));
       let albums: Album[];
       try {
         albums = JSON.parse(albumsJson);
@@ -115,19 +125,17 @@ export class DataLoader {
         );
       }
 
-      // Create complete MusicData structure for validation
-      const completeData = {
-        songs: rawData.songs,
-        albums: albums
-      };
-
-      // Validate the complete structure using Rust/WASM
-      const validationError = validateMusicData(JSON.stringify(completeData));
+      // Validate the file as written (including album declarations) using Rust/WASM
+      const validationError = validateMusicData(jsonText);
       if (validationError) {
         throw new DataLoaderError(`Data validation failed: ${validationError}`);
       }
 
       // Return complete MusicData structure
+      const completeData: MusicData = {
+        songs: rawData.songs,
+        albums: albums
+      };
       return completeData;
 
     } catch (error) {
@@ -143,6 +151,78 @@ export class DataLoader {
     }
   }
 
+  /**
+   * Loads music data together with a WASM catalog handle over it
+   * `music.json` is fetched once and parsed and validated once, by the catalog;
+   * albums and creator-friendly verdicts can then be read from the handle
+   * instead of sending the song list back into WASM. The search index prebuilt
+   * by `npm run build:search-index` is loaded with it; if the index can't be
+   * fetched, the catalog indexes the songs itself
+   * 
+   * @param jsonPath - Path to the JSON file (defaults to '/src/data/music.json')
+   * @param indexPath - Path to the search index (defaults to '/search-index.bin')
+   * @returns Promise<MusicCatalog> - Validated music data and its catalog; call
+   * catalog.free() when done with it
+   * @throws DataLoaderError if loading or validation fails
+   */
+  public async loadMusicCatalog(
+    jsonPath: string = '/src/data/music.json',
+    indexPath: string = '/search-index.bin'
+  ): Promise<MusicCatalog> {
+    try {
+      await this.ensureWasmInitialized();
+
+      const [response, searchIndex] = await Promise.all([fetch(jsonPath), this.fetchSearchIndex(indexPath)]);
+      if (!response.ok) {
+        throw new DataLoaderError(
+          `Failed to fetch music data: ${response.status} ${response.statusText}`
+        );
+      }
+
+      const jsonText = await response.text();
+      const catalog = searchIndex ? createCatalogWithIndex(jsonText, searchIndex) : createCatalog(jsonText);
+
+      // The catalog validated the file as written (including album declarations)
+      if (!catalog.isValid) {
+        const report: ValidationSummary = catalog.validationReport();
+        catalog.free();
+        const firstError = report.diagnostics.find(diagnostic => diagnostic.severity === 'error');
+        throw new DataLoaderError(`Data validation failed: ${firstError?.message ?? 'invalid music data'}`);
+      }
+
+      const rawData: { songs: Song[] } = JSON.parse(jsonText);
+      const data: MusicData = {
+        songs: rawData.songs,
+        albums: catalog.groupByAlbum()
+      };
+      return { data, catalog };
+    } catch (error) {
+      if (error instanceof DataLoaderError) {
+        throw error;
+      }
+
+      throw new DataLoaderError(
+        'Unexpected error while loading the catalog',
+        error instanceof Error ? error : new Error(String(error))
+      );
+    }
+  }
+
+  /**
+   * Fetches the prebuilt search index, or null if it isn't available
+   */
+  private async fetchSearchIndex(indexPath: string): Promise<Uint8Array | null> {
+    try {
+      const response = await fetch(indexPath);
+      if (!response.ok) {
+        return null;
+      }
+      return new Uint8Array(await response.arrayBuffer());
+    } catch {
+      return null;
+    }
+  }
+
   /**
    * Validates a raw JSON string containing music data
    * Uses Rust/WASM for validation performance
@@ -171,26 +251,8 @@ export class DataLoader {
         throw new DataLoaderError('Invalid data structure: missing or invalid songs array');
       }
 
-      // Group albums using Rust/WASM for performance
-      const albumsJson = groupByAlbum(JSON.stringify(rawData.songs));
-      let albums: Album[];
-      try {
-        albums = JSON.parse(albumsJson);
-      } catch (parseError) {
-        throw new DataLoaderError(
-          'Failed to parse album grouping results',
-          parseError instanceof Error ? parseError : new Error(String(parseError))
-        );
-      }
-
-      // Create complete MusicData structure for validation
-      const completeData = {
-        songs: rawData.songs,
-        albums: albums
-      };
-
-      // Validate the complete structure using Rust/WASM
-      const validationError = validateMusicData(JSON.stringify(completeData));
+      // Validate the file as written (including album declarations) using Rust/WASM
+      const validationError = validateMusicData(jsonText);
       if (validationError) {
         throw new DataLoaderError(`Validation failed: ${validationError}`);
       }
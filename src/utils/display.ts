@@ -8,6 +8,7 @@
  */
 
 import type { Song } from '../types/music';
+import { filterCreatorFriendly } from './filters';
 
 /**
  * Determines if a license should be displayed
@@ -23,61 +24,6 @@ export function shouldDisplayLicense(license: string): boolean {
   return license.trim() !== '';
 }
 
-/**
- * Determines if a song is creator-friendly based on its properties
- * 
- * A song is considered creator-friendly if ANY of these conditions are true:
- * 1. Release type is 'NCS'
- * 2. License contains commercial CC patterns (CC BY, CC BY-SA, CC0)
- * 3. License is 'BGML-P'
- * 
- * @param song - Song to evaluate
- * @returns true if song is creator-friendly, false otherwise
- */
-export function isCreatorFriendlySong(song: Song): boolean {
-  // NCS releases are always creator-friendly
-  if (song.releaseType === 'NCS') {
-    return true;
-  }
-  
-  // Check for commercial CC licenses (case-insensitive)
-  // Handle null/undefined license gracefully
-  const license = (song.license || '').toLowerCase().trim();
-  if (license.includes('cc by') || license.includes('cc0') || license === 'bgml-p') {
-    return true;
-  }
-  
-  return false;
-}
-
-/**
- * Gets a human-readable description of why a song is creator-friendly
- * 
- * @param song - Song to analyze
- * @returns Description string or null if not creator-friendly
- */
-export function getCreatorFriendlyReason(song: Song): string | null {
-  if (song.releaseType === 'NCS') {
-    return 'NCS Release - Free for creators';
-  }
-  
-  // Handle null/undefined license gracefully
-  const license = (song.license || '').toLowerCase().trim();
-  if (license.includes('cc by')) {
-    return `Creative Commons License (${song.license || 'CC BY'})`;
-  }
-  
-  if (license.includes('cc0')) {
-    return `Public Domain (${song.license || 'CC0'})`;
-  }
-  
-  if (license === 'bgml-p') {
-    return 'BGML-P License - Permissive for games';
-  }
-  
-  return null;
-}
-
 /**
  * Gets a human-readable description of Content ID status
  * 
@@ -179,6 +125,7 @@ export function getStreamingLinkText(streamingLink: string): string {
 
 /**
  * Filters songs based on display criteria
+ * Creator-friendliness is judged by the Rust/WASM verdict (see utils/filters)
  * 
  * @param songs - Array of songs to filter
  * @param showOnlyCreatorFriendly - Whether to show only creator-friendly songs
@@ -189,7 +136,7 @@ export function filterSongsForDisplay(songs: Song[], showOnlyCreatorFriendly: bo
     return songs;
   }
   
-  return songs.filter(song => isCreatorFriendlySong(song));
+  return filterCreatorFriendly(songs);
 }
 
 /**
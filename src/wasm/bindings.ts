@@ -4,23 +4,28 @@
  * with proper type safety and error handling.
  */
 
-import wasmInit, { initSync } from '../../rust/pkg/rust';
+import wasmInit, { initSync, Catalog } from '../../rust/pkg/rust';
+import * as wasmExports from '../../rust/pkg/rust';
 
 // Track initialization state
 let wasmInitialized = false;
 let wasmModule: any = null;
 
 /**
- * Initialize the WASM module synchronously
- * This is used for testing and synchronous operations
+ * Initialize the WASM module
+ * Browsers fetch the compiled module from its URL; where it can't be fetched
+ * (Node tests), pass its bytes and it is instantiated synchronously
  */
-export function initWasm(): void {
+export async function initWasm(module?: BufferSource): Promise<void> {
   if (!wasmInitialized) {
     try {
-      // For testing, we'll skip WASM initialization and rely on JavaScript fallbacks
-      // This is because WASM initialization in Node.js test environment is complex
-      console.warn('WASM initialization skipped in test environment, using JavaScript fallbacks');
-      wasmInitialized = false; // Keep as false to force fallbacks
+      if (module) {
+        initSync({ module });
+      } else {
+        await wasmInit();
+      }
+      wasmModule = wasmExports;
+      wasmInitialized = true;
     } catch (error) {
       console.warn('WASM initialization failed:', error);
       throw error;
@@ -33,16 +38,7 @@ export function initWasm(): void {
  * This is used for browser environments
  */
 export async function initWasmAsync(): Promise<void> {
-  if (!wasmInitialized) {
-    try {
-      // Use the default async initialization
-      wasmModule = await wasmInit();
-      wasmInitialized = true;
-    } catch (error) {
-      console.warn('WASM async initialization failed:', error);
-      throw error;
-    }
-  }
+  return initWasm();
 }
 
 /**
@@ -50,9 +46,7 @@ export async function initWasmAsync(): Promise<void> {
  */
 function ensureInitialized(): void {
   if (!wasmInitialized) {
-    // In test environment, we intentionally don't initialize WASM
-    // The calling code should handle this by falling back to JavaScript
-    throw new Error('WASM module not initialized. Using JavaScript fallback.');
+    throw new Error('WASM module not initialized. Call initWasm() first.');
   }
 }
 
@@ -75,21 +69,115 @@ export function validateMusicData(dataJson: string): string {
 }
 
 /**
- * Groups songs by album name
+ * Validates a single song and reports every problem found
+ * Returns JSON string: { valid, errorCount, warningCount, diagnostics }
+ */
+export function validateSongReport(songJson: string): string {
+  ensureInitialized();
+  return wasmModule.validate_song_report(songJson);
+}
+
+/**
+ * Validates entire music data structure and reports every problem found
+ * Each diagnostic has a JSON pointer path, stable code, severity and message
+ */
+export function validateMusicDataReport(dataJson: string): string {
+  ensureInitialized();
+  return wasmModule.validate_music_data_report(dataJson);
+}
+
+/**
+ * Groups songs by album
+ * `albumsJson` optionally declares album metadata (AlbumInfo[]) to attach;
+ * songs join a declared album through `albumId`, or else by `albumName`
  * Returns JSON string of grouped albums
  */
-export function groupByAlbum(songsJson: string): string {
+export function groupByAlbum(songsJson: string, albumsJson?: string): string {
+  ensureInitialized();
+  return wasmModule.group_by_album(songsJson, albumsJson);
+}
+
+/**
+ * Groups songs by one or more keys, nesting a level of groups per key
+ * keysJson is a JSON array such as ["decade", "releaseType"]; keys are album,
+ * releaseYear, decade, releaseType, licenseFamily, contentId and creatorFriendly
+ * Returns JSON string of groups: { key, value, label, count, groups?, songs? }
+ */
+export function groupByKeys(songsJson: string, keysJson: string, albumsJson?: string): string {
+  ensureInitialized();
+  return wasmModule.group_by_keys(songsJson, keysJson, albumsJson);
+}
+
+/**
+ * Searches songs by title, album, license and release type, ignoring case and
+ * accents; every query word must match, and the last may be a word's beginning
+ * Returns JSON string of the matching song IDs, best match first
+ */
+export function searchSongIds(songsJson: string, query: string): string {
+  ensureInitialized();
+  return wasmModule.search_song_ids(songsJson, query);
+}
+
+/**
+ * Filters songs with a search query such as
+ * `album:"Electronic Fusion" year:2023..2024 license:cc-by -contentid`
+ * Supports free text, field:value, year ranges, -/NOT, OR and parentheses
+ * Returns JSON string of the matching songs, or an error with the column
+ */
+export function querySongs(songsJson: string, query: string, policyJson?: string): string {
+  ensureInitialized();
+  return wasmModule.query_songs(songsJson, query, policyJson);
+}
+
+/**
+ * Runs a faceted search: `requestJson` is `{ query, selections, facets, sort }`
+ * Returns JSON string of `{ songs, facets }`, where each facet lists every
+ * value with its label, count and whether it is selected
+ */
+export function facetSearchSongs(songsJson: string, requestJson: string, policyJson?: string): string {
+  ensureInitialized();
+  return wasmModule.facet_search_songs(songsJson, requestJson, policyJson);
+}
+
+/**
+ * Suggests song titles and album names for a search box query, tolerating typos
+ * Returns JSON string of up to `limit` (default 10) suggestions, best first,
+ * each with a score and highlight ranges into its text
+ */
+export function suggestSongs(songsJson: string, query: string, limit?: number): string {
+  ensureInitialized();
+  return wasmModule.suggest_songs(songsJson, query, limit);
+}
+
+/**
+ * Sorts songs with natural, case- and accent-insensitive text comparison
+ * sortJson is { by: [{ field, order }], natural?, ignoreArticles? } where field
+ * is title, album, year, releaseType or license and order is asc or desc
+ * Returns JSON string of the sorted songs
+ */
+export function sortSongs(songsJson: string, sortJson: string): string {
   ensureInitialized();
-  return wasmModule.group_by_album(songsJson);
+  return wasmModule.sort_song_list(songsJson, sortJson);
 }
 
 /**
  * Filters songs to only creator-friendly ones
+ * Pass a creator-friendliness policy as JSON to override the default rules
  * Returns JSON string of filtered songs
  */
-export function filterCreatorFriendly(songsJson: string): string {
+export function filterCreatorFriendly(songsJson: string, policyJson?: string): string {
+  ensureInitialized();
+  return wasmModule.filter_creator_friendly(songsJson, policyJson);
+}
+
+/**
+ * Generates attribution for a song in plain text, HTML and Markdown
+ * configJson is { artist, siteUrl }; pass an empty string for the site defaults
+ * Returns JSON string: { text, html, markdown }
+ */
+export function generateAttribution(songJson: string, configJson = ''): string {
   ensureInitialized();
-  return wasmModule.filter_creator_friendly(songsJson);
+  return wasmModule.generate_attribution(songJson, configJson);
 }
 
 /**
@@ -108,6 +196,25 @@ export function isBGMLPLicense(license: string): boolean {
   return wasmModule.is_bgml_p_license(license);
 }
 
+/**
+ * Returns the canonical spelling of a license string
+ * e.g. "cc by-sa 4.0" becomes "CC BY-SA 4.0"
+ */
+export function canonicalizeLicense(license: string): string {
+  ensureInitialized();
+  return wasmModule.canonicalize_license(license);
+}
+
+/**
+ * Converts a license string or expression to SPDX form
+ * e.g. "CC BY 4.0 OR BGML-P" becomes "CC-BY-4.0 OR LicenseRef-BGML-P"
+ * Returns an empty string if some license has no SPDX id
+ */
+export function licenseToSpdx(license: string): string {
+  ensureInitialized();
+  return wasmModule.license_to_spdx(license);
+}
+
 /**
  * Checks if a song is creator-friendly
  * Takes a JSON string representing a single song
@@ -117,6 +224,25 @@ export function isCreatorFriendlySong(songJson: string): boolean {
   return wasmModule.is_creator_friendly_song(songJson);
 }
 
+/**
+ * Explains why a song is or isn't creator-friendly
+ * Returns JSON string: { creatorFriendly, matched, failed, summary }
+ */
+export function explainCreatorFriendly(songJson: string): string {
+  ensureInitialized();
+  return wasmModule.explain_creator_friendly(songJson);
+}
+
+/**
+ * Answers "can I use this song for X?"
+ * scenario: monetizedVideo | liveStream | commercialGame | paidAd | nonCommercialRemix | commercialRemix
+ * Returns JSON string: { scenario, verdict, obligations, reasons }
+ */
+export function evaluateSongUsage(songJson: string, scenario: string): string {
+  ensureInitialized();
+  return wasmModule.evaluate_song_usage(songJson, scenario);
+}
+
 /**
  * Batch validates multiple songs (optimized)
  * Returns JSON string with validation results for each song
@@ -126,6 +252,40 @@ export function batchValidateSongs(songsJson: string): string {
   return wasmModule.batch_validate_songs(songsJson);
 }
 
+/**
+ * Loads and validates music data once, returning a catalog handle
+ * Query the handle (getSong, filter, search, rankedSearch, rankedSearchIds, query, facets, suggest, groupByAlbum, groupBy, stats) instead of
+ * passing the whole song list on every call; call free() when done with it
+ */
+export function createCatalog(musicDataJson: string): Catalog {
+  ensureInitialized();
+  return new Catalog(musicDataJson);
+}
+
+/**
+ * Like createCatalog, but loads the search index prebuilt by
+ * `musicctl index` (public/search-index.bin) instead of indexing in the browser
+ * A damaged, outdated or stale index is rejected and the catalog indexes the
+ * songs itself instead
+ */
+export function createCatalogWithIndex(musicDataJson: string, searchIndex: Uint8Array): Catalog {
+  ensureInitialized();
+  try {
+    return Catalog.withIndex(musicDataJson, searchIndex);
+  } catch (error) {
+    console.warn('Prebuilt search index rejected, indexing in the browser:', error);
+    return new Catalog(musicDataJson);
+  }
+}
+
+/**
+ * Returns the JSON Schema (draft 2020-12) for music.json as pretty-printed JSON
+ */
+export function musicJsonSchema(): string {
+  ensureInitialized();
+  return wasmModule.music_json_schema();
+}
+
 /**
  * Get the version of the WASM module
  */
@@ -141,6 +301,3 @@ export function isInitialized(): boolean {
   ensureInitialized();
   return wasmModule.is_initialized();
 }
-
-// Note: WASM module is not exported in test environment to avoid initialization issues
-// The JavaScript fallbacks in the filter utilities will be used instead
\ No newline at end of file
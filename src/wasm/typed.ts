@@ -0,0 +1,68 @@
+/**
+ * Typed bindings for the Rust/WASM module
+ * These take and return plain JS objects and arrays, and throw an Error on
+ * invalid input. The JSON string functions in bindings.ts are kept as
+ * compatibility shims for existing callers.
+ * Parameter and return types are generated from the Rust structs.
+ */
+
+export type {
+  ValidationSummary,
+  Diagnostic,
+  DiagnosticCode,
+  BatchValidation,
+  CreatorFriendlyPolicy,
+  CreatorFriendlyVerdict,
+  UsageScenario,
+  UsageEvaluation,
+  Attribution,
+  AttributionConfig,
+  CatalogFilter,
+  CatalogStats,
+  AlbumInfo,
+  AlbumMetadata,
+  AlbumType,
+  GroupKey,
+  SongGroup,
+  Collation,
+  SongSort,
+  SortSpec,
+  SortField,
+  SortOrder,
+  SearchHit,
+  Suggestion,
+  SuggestionKind,
+  Highlight,
+  SongQuery,
+  QueryPredicate,
+  YearRange,
+  FacetQuery,
+  FacetSelection,
+  FacetResult,
+  FacetCounts,
+  FacetValue,
+} from '../../rust/pkg/rust';
+
+export {
+  validateSong,
+  validateMusicData,
+  batchValidateSongs,
+  explainCreatorFriendly,
+  isCreatorFriendly,
+  filterCreatorFriendly,
+  evaluateSongUsage,
+  groupByAlbum,
+  groupByKeys,
+  sortSongs,
+  sortAlbums,
+  compareText,
+  searchSongs,
+  suggestSongs,
+  parseQuery,
+  querySongs,
+  facetSearch,
+  generateAttribution,
+  formatMusicJson,
+  isCanonicalMusicJson,
+  Catalog,
+} from '../../rust/pkg/rust';